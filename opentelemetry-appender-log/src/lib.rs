@@ -96,7 +96,7 @@
 
 use log::{Level, Metadata, Record};
 use opentelemetry::{
-    logs::{AnyValue, LogRecord, Logger, LoggerProvider, Severity},
+    logs::{AnyValue, LevelControl, LogRecord, Logger, LoggerProvider, Severity},
     InstrumentationScope, Key,
 };
 #[cfg(feature = "experimental_metadata_attributes")]
@@ -108,6 +108,7 @@ where
     L: Logger + Send + Sync,
 {
     logger: L,
+    level_control: Option<LevelControl>,
     _phantom: std::marker::PhantomData<P>, // P is not used in this struct
 }
 
@@ -116,11 +117,16 @@ where
     P: LoggerProvider<Logger = L> + Send + Sync,
     L: Logger + Send + Sync,
 {
-    fn enabled(&self, _metadata: &Metadata) -> bool {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if let Some(level_control) = &self.level_control {
+            if !level_control.is_enabled(severity_of_level(metadata.level()), metadata.target()) {
+                return false;
+            }
+        }
         #[cfg(feature = "spec_unstable_logs_enabled")]
         return self
             .logger
-            .event_enabled(severity_of_level(_metadata.level()), _metadata.target());
+            .event_enabled(severity_of_level(metadata.level()), metadata.target());
         #[cfg(not(feature = "spec_unstable_logs_enabled"))]
         true
     }
@@ -175,9 +181,20 @@ where
 
         OpenTelemetryLogBridge {
             logger: provider.logger_with_scope(scope),
+            level_control: None,
             _phantom: Default::default(),
         }
     }
+
+    /// Skips the records disabled by `level_control` before creating an
+    /// OpenTelemetry log record for them.
+    ///
+    /// Clones of `level_control` can be shared with other bridges and the
+    /// `LoggerProvider`, and used to change the levels at runtime.
+    pub fn with_level_control(mut self, level_control: LevelControl) -> Self {
+        self.level_control = Some(level_control);
+        self
+    }
 }
 
 const fn severity_of_level(level: Level) -> Severity {
@@ -752,7 +769,10 @@ mod any_value {
 mod tests {
     use super::OpenTelemetryLogBridge;
 
-    use opentelemetry::{logs::AnyValue, StringValue};
+    use opentelemetry::{
+        logs::{AnyValue, LevelControl},
+        StringValue,
+    };
     use opentelemetry_sdk::{logs::LoggerProvider, testing::logs::InMemoryLogExporter};
 
     use log::Log;
@@ -776,6 +796,48 @@ mod tests {
         assert!(otel_log_appender.enabled(&log::Metadata::builder().build()));
     }
 
+    #[test]
+    fn logbridge_with_level_control() {
+        let exporter = InMemoryLogExporter::default();
+
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+
+        let level_control = LevelControl::new("warn,my_crate::db=debug").unwrap();
+        let otel_log_appender =
+            OpenTelemetryLogBridge::new(&logger_provider).with_level_control(level_control.clone());
+
+        let log = |target: &str, level: log::Level| {
+            otel_log_appender.log(
+                &log::RecordBuilder::new()
+                    .target(target)
+                    .level(level)
+                    .args(format_args!("message"))
+                    .build(),
+            );
+        };
+
+        log("my_crate::db", log::Level::Debug);
+        log("my_crate::http", log::Level::Info);
+        level_control.set_directives("info").unwrap();
+        log("my_crate::db", log::Level::Debug);
+        log("my_crate::http", log::Level::Info);
+
+        let logs = exporter.get_emitted_logs().unwrap();
+        let targets: Vec<_> = logs
+            .iter()
+            .map(|log| log.record.target.as_deref().unwrap())
+            .collect();
+        assert_eq!(targets, vec!["my_crate::db", "my_crate::http"]);
+        assert!(!otel_log_appender.enabled(
+            &log::Metadata::builder()
+                .target("my_crate::db")
+                .level(log::Level::Debug)
+                .build()
+        ));
+    }
+
     #[test]
     fn logbridge_with_record_can_log() {
         let exporter = InMemoryLogExporter::default();
@@ -1,17 +1,24 @@
+use crate::span::span_context;
 use opentelemetry::{
-    logs::{AnyValue, LogRecord, Logger, LoggerProvider, Severity},
+    logs::{AnyValue, LevelControl, LogRecord, Logger, LoggerProvider, Severity},
     InstrumentationScope, Key,
 };
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use tracing_core::span::{Attributes, Id, Record};
 use tracing_core::Level;
 #[cfg(feature = "experimental_metadata_attributes")]
 use tracing_core::Metadata;
 #[cfg(feature = "experimental_metadata_attributes")]
 use tracing_log::NormalizeEvent;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::Layer;
 
 const INSTRUMENTATION_LIBRARY_NAME: &str = "opentelemetry-appender-tracing";
 
+/// Attribute key of the span name path recorded by [`SpanFields::with_span_path`].
+pub const SPAN_PATH_KEY: &str = "span.path";
+
 /// Visitor to record the fields from the event record.
 struct EventVisitor<'a, LR: LogRecord> {
     log_record: &'a mut LR,
@@ -118,16 +125,401 @@ impl<'a, LR: LogRecord> tracing::field::Visit for EventVisitor<'a, LR> {
             .add_attribute(Key::new(field.name()), AnyValue::from(value));
     }
 
-    // TODO: Remaining field types from AnyValue : Bytes, ListAny, Boolean
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        #[cfg(feature = "experimental_metadata_attributes")]
+        if is_duplicated_metadata(field.name()) {
+            return;
+        }
+        self.log_record
+            .add_attribute(Key::new(field.name()), int_or_string(value));
+    }
+
+    fn record_i128(&mut self, field: &tracing::field::Field, value: i128) {
+        #[cfg(feature = "experimental_metadata_attributes")]
+        if is_duplicated_metadata(field.name()) {
+            return;
+        }
+        self.log_record
+            .add_attribute(Key::new(field.name()), int_or_string(value));
+    }
+
+    fn record_u128(&mut self, field: &tracing::field::Field, value: u128) {
+        #[cfg(feature = "experimental_metadata_attributes")]
+        if is_duplicated_metadata(field.name()) {
+            return;
+        }
+        self.log_record
+            .add_attribute(Key::new(field.name()), int_or_string(value));
+    }
+
+    fn record_bytes(&mut self, field: &tracing::field::Field, value: &[u8]) {
+        #[cfg(feature = "experimental_metadata_attributes")]
+        if is_duplicated_metadata(field.name()) {
+            return;
+        }
+        self.log_record.add_attribute(
+            Key::new(field.name()),
+            AnyValue::Bytes(Box::new(value.to_owned())),
+        );
+    }
+
+    #[cfg(all(tracing_unstable, feature = "valuable"))]
+    fn record_value(&mut self, field: &tracing::field::Field, value: valuable::Value<'_>) {
+        if let Some(value) = structured::any_value(value) {
+            self.log_record.add_attribute(Key::new(field.name()), value);
+        }
+    }
+}
+
+/// Converts integers to [`AnyValue::Int`], or to their decimal string if they
+/// do not fit in an `i64`.
+fn int_or_string<T>(value: T) -> AnyValue
+where
+    T: Copy + TryInto<i64> + ToString,
+{
+    match value.try_into() {
+        Ok(value) => AnyValue::Int(value),
+        Err(_) => AnyValue::from(value.to_string()),
+    }
+}
+
+/// Conversion of [`valuable`] structured values to [`AnyValue`]s.
+#[cfg(all(tracing_unstable, feature = "valuable"))]
+mod structured {
+    use super::int_or_string;
+    use opentelemetry::{logs::AnyValue, Key};
+    use std::collections::HashMap;
+    use valuable::{NamedValues, Valuable, Value, Visit};
+
+    /// Converts `value` to an [`AnyValue`]: lists and tuples become
+    /// [`AnyValue::ListAny`], maps and structs [`AnyValue::Map`], and enum
+    /// variants with fields a map from the variant name to its fields.
+    ///
+    /// Returns `None` for the unit value.
+    pub(super) fn any_value(value: Value<'_>) -> Option<AnyValue> {
+        Some(match value {
+            Value::Bool(v) => AnyValue::Boolean(v),
+            Value::Char(v) => AnyValue::from(v.to_string()),
+            Value::F32(v) => AnyValue::Double(v.into()),
+            Value::F64(v) => AnyValue::Double(v),
+            Value::I8(v) => AnyValue::Int(v.into()),
+            Value::I16(v) => AnyValue::Int(v.into()),
+            Value::I32(v) => AnyValue::Int(v.into()),
+            Value::I64(v) => AnyValue::Int(v),
+            Value::I128(v) => int_or_string(v),
+            Value::Isize(v) => int_or_string(v),
+            Value::U8(v) => AnyValue::Int(v.into()),
+            Value::U16(v) => AnyValue::Int(v.into()),
+            Value::U32(v) => AnyValue::Int(v.into()),
+            Value::U64(v) => int_or_string(v),
+            Value::U128(v) => int_or_string(v),
+            Value::Usize(v) => int_or_string(v),
+            Value::String(v) => AnyValue::from(v.to_owned()),
+            Value::Path(v) => AnyValue::from(v.display().to_string()),
+            Value::Error(v) => AnyValue::from(v.to_string()),
+            Value::Listable(v) => collect(v).into_list(),
+            Value::Mappable(v) => collect(v).into_map(),
+            Value::Tuplable(v) => collect(v).into_list(),
+            Value::Structable(v) => {
+                let fields = collect(v);
+                if fields.list.is_empty() {
+                    fields.into_map()
+                } else {
+                    fields.into_list()
+                }
+            }
+            Value::Enumerable(v) => {
+                let variant = v.variant().name().to_owned();
+                match collect(v).into_fields() {
+                    Some(fields) => {
+                        AnyValue::Map(Box::new(HashMap::from([(Key::from(variant), fields)])))
+                    }
+                    None => AnyValue::from(variant),
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    fn collect(value: &dyn Valuable) -> Collector {
+        let mut collector = Collector::default();
+        value.visit(&mut collector);
+        collector
+    }
+
+    #[derive(Default)]
+    struct Collector {
+        list: Vec<AnyValue>,
+        map: HashMap<Key, AnyValue>,
+    }
+
+    impl Collector {
+        fn into_list(self) -> AnyValue {
+            AnyValue::ListAny(Box::new(self.list))
+        }
+
+        fn into_map(self) -> AnyValue {
+            AnyValue::Map(Box::new(self.map))
+        }
+
+        fn into_fields(self) -> Option<AnyValue> {
+            if !self.map.is_empty() {
+                Some(self.into_map())
+            } else if !self.list.is_empty() {
+                Some(self.into_list())
+            } else {
+                None
+            }
+        }
+    }
+
+    impl Visit for Collector {
+        fn visit_value(&mut self, value: Value<'_>) {
+            if let Some(value) = any_value(value) {
+                self.list.push(value);
+            }
+        }
+
+        fn visit_named_fields(&mut self, named_values: &NamedValues<'_>) {
+            for (field, value) in named_values {
+                if let Some(value) = any_value(*value) {
+                    self.map.insert(Key::from(field.name().to_owned()), value);
+                }
+            }
+        }
+
+        fn visit_unnamed_fields(&mut self, values: &[Value<'_>]) {
+            for value in values {
+                self.visit_value(*value);
+            }
+        }
+
+        fn visit_entry(&mut self, key: Value<'_>, value: Value<'_>) {
+            let key = match key {
+                Value::String(key) => key.to_owned(),
+                key => format!("{key:?}"),
+            };
+            if let Some(value) = any_value(value) {
+                self.map.insert(Key::from(key), value);
+            }
+        }
+    }
+}
+
+/// How a span field is recorded when a field of the same name is already part
+/// of the log record, either from the event itself or from a span closer to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldCollision {
+    /// The span field is not recorded. Event fields win over span fields, and
+    /// fields of inner spans win over fields of outer spans.
+    #[default]
+    Skip,
+    /// The span field is recorded with the span name as prefix, e.g.
+    /// `request.user_id`.
+    Prefix,
+    /// The span field is recorded as well, leaving duplicated keys in the
+    /// log record.
+    Keep,
+}
+
+#[derive(Clone, Debug, Default)]
+enum FieldSelection {
+    #[default]
+    None,
+    All,
+    Only(HashSet<Cow<'static, str>>),
+}
+
+/// Configures which fields of the enclosing `tracing` spans are copied into the
+/// attributes of the log records emitted by [`OpenTelemetryTracingBridge`].
+///
+/// Span fields are not copied by default.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_appender_tracing::layer::{FieldCollision, SpanFields};
+///
+/// let span_fields = SpanFields::only(["request_id", "user_id"])
+///     .with_field_collision("user_id", FieldCollision::Prefix)
+///     .with_span_path();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SpanFields {
+    selection: FieldSelection,
+    collision: FieldCollision,
+    field_collisions: HashMap<Cow<'static, str>, FieldCollision>,
+    span_path: bool,
+}
+
+impl SpanFields {
+    /// Copies all fields of the enclosing spans.
+    pub fn all() -> Self {
+        SpanFields {
+            selection: FieldSelection::All,
+            ..Default::default()
+        }
+    }
+
+    /// Copies the fields with the given names of the enclosing spans.
+    pub fn only<I, N>(names: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<Cow<'static, str>>,
+    {
+        SpanFields {
+            selection: FieldSelection::Only(names.into_iter().map(Into::into).collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets how span fields colliding with an already recorded field are
+    /// handled.
+    ///
+    /// Default is [`FieldCollision::Skip`].
+    pub fn with_collision(mut self, collision: FieldCollision) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    /// Sets how the span field `name` is handled when it collides with an
+    /// already recorded field, overriding [`SpanFields::with_collision`].
+    pub fn with_field_collision(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        collision: FieldCollision,
+    ) -> Self {
+        self.field_collisions.insert(name.into(), collision);
+        self
+    }
+
+    /// Records the names of the enclosing spans, from the root span to the
+    /// innermost one separated by `:`, as the [`SPAN_PATH_KEY`] attribute.
+    pub fn with_span_path(mut self) -> Self {
+        self.span_path = true;
+        self
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.span_path || self.captures_fields()
+    }
+
+    fn captures_fields(&self) -> bool {
+        !matches!(self.selection, FieldSelection::None)
+    }
+
+    fn includes(&self, name: &str) -> bool {
+        match &self.selection {
+            FieldSelection::None => false,
+            FieldSelection::All => true,
+            FieldSelection::Only(names) => names.contains(name),
+        }
+    }
+
+    fn collision(&self, name: &str) -> FieldCollision {
+        self.field_collisions
+            .get(name)
+            .copied()
+            .unwrap_or(self.collision)
+    }
+}
+
+/// The selected fields of a span, stored in the span's extensions.
+#[derive(Debug, Default)]
+struct SpanFieldValues(Vec<(&'static str, AnyValue)>);
+
+impl SpanFieldValues {
+    fn set(&mut self, name: &'static str, value: AnyValue) {
+        match self.0.iter_mut().find(|(field, _)| *field == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((name, value)),
+        }
+    }
+}
+
+/// Visitor to record the selected fields of a span.
+struct SpanFieldVisitor<'a> {
+    span_fields: &'a SpanFields,
+    values: &'a mut SpanFieldValues,
+}
+
+impl SpanFieldVisitor<'_> {
+    fn record(&mut self, field: &tracing::field::Field, value: impl FnOnce() -> AnyValue) {
+        #[cfg(feature = "experimental_metadata_attributes")]
+        if is_duplicated_metadata(field.name()) {
+            return;
+        }
+        if self.span_fields.includes(field.name()) {
+            self.values.set(field.name(), value());
+        }
+    }
+}
+
+impl tracing::field::Visit for SpanFieldVisitor<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.record(field, || AnyValue::from(format!("{value:?}")));
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.record(field, || AnyValue::from(value.to_owned()));
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.record(field, || AnyValue::from(value));
+    }
+
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        self.record(field, || AnyValue::from(value));
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.record(field, || AnyValue::from(value));
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.record(field, || int_or_string(value));
+    }
+
+    fn record_i128(&mut self, field: &tracing::field::Field, value: i128) {
+        self.record(field, || int_or_string(value));
+    }
+
+    fn record_u128(&mut self, field: &tracing::field::Field, value: u128) {
+        self.record(field, || int_or_string(value));
+    }
+
+    fn record_bytes(&mut self, field: &tracing::field::Field, value: &[u8]) {
+        self.record(field, || AnyValue::Bytes(Box::new(value.to_owned())));
+    }
+
+    #[cfg(all(tracing_unstable, feature = "valuable"))]
+    fn record_value(&mut self, field: &tracing::field::Field, value: valuable::Value<'_>) {
+        if let Some(value) = structured::any_value(value) {
+            self.record(field, || value);
+        }
+    }
 }
 
-pub struct OpenTelemetryTracingBridge<P, L>
+/// Marks an [`OpenTelemetryTracingBridge`] that does not look up the spans
+/// enclosing events. Such a bridge works with any `tracing` subscriber.
+#[derive(Debug)]
+pub struct NoSpanLookup;
+
+/// Marks an [`OpenTelemetryTracingBridge`] that looks up the spans enclosing
+/// events, for their fields and trace context. Such a bridge requires a
+/// subscriber implementing [`LookupSpan`], such as `tracing_subscriber::Registry`.
+#[derive(Debug)]
+pub struct SpanLookup;
+
+pub struct OpenTelemetryTracingBridge<P, L, M = NoSpanLookup>
 where
     P: LoggerProvider<Logger = L> + Send + Sync,
     L: Logger + Send + Sync,
 {
     logger: L,
-    _phantom: std::marker::PhantomData<P>, // P is not used.
+    span_fields: SpanFields,
+    level_control: Option<LevelControl>,
+    _phantom: std::marker::PhantomData<(P, M)>, // P and M are not used.
 }
 
 impl<P, L> OpenTelemetryTracingBridge<P, L>
@@ -142,22 +534,64 @@ where
 
         OpenTelemetryTracingBridge {
             logger: provider.logger_with_scope(scope),
+            span_fields: SpanFields::default(),
+            level_control: None,
             _phantom: Default::default(),
         }
     }
 }
 
-impl<S, P, L> Layer<S> for OpenTelemetryTracingBridge<P, L>
+impl<P, L, M> OpenTelemetryTracingBridge<P, L, M>
 where
-    S: tracing::Subscriber,
-    P: LoggerProvider<Logger = L> + Send + Sync + 'static,
-    L: Logger + Send + Sync + 'static,
+    P: LoggerProvider<Logger = L> + Send + Sync,
+    L: Logger + Send + Sync,
 {
-    fn on_event(
-        &self,
-        event: &tracing::Event<'_>,
-        _ctx: tracing_subscriber::layer::Context<'_, S>,
-    ) {
+    /// Skips the events disabled by `level_control` before creating an
+    /// OpenTelemetry log record for them.
+    ///
+    /// Unlike a `tracing_subscriber` filter, this does not disable the events
+    /// for the other layers. Clones of `level_control` can be shared with
+    /// other bridges and the `LoggerProvider`, and used to change the levels
+    /// at runtime.
+    pub fn with_level_control(mut self, level_control: LevelControl) -> Self {
+        self.level_control = Some(level_control);
+        self
+    }
+
+    /// Looks up the `tracing` spans enclosing an event, so that its log record
+    /// carries the trace context of the span created for them by
+    /// [`OpenTelemetrySpanBridge`], even if the span is not entered, e.g. for
+    /// events with an explicit parent.
+    ///
+    /// The bridge then requires a subscriber implementing [`LookupSpan`].
+    ///
+    /// [`OpenTelemetrySpanBridge`]: crate::span::OpenTelemetrySpanBridge
+    pub fn with_span_lookup(self) -> OpenTelemetryTracingBridge<P, L, SpanLookup> {
+        OpenTelemetryTracingBridge {
+            logger: self.logger,
+            span_fields: self.span_fields,
+            level_control: self.level_control,
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Copies the fields of the `tracing` spans enclosing an event into the
+    /// attributes of its log record, as configured by `span_fields`.
+    ///
+    /// Fields are read from the innermost span to the root span, so that with
+    /// [`FieldCollision::Skip`] the value closest to the event is recorded.
+    /// This enables [`Self::with_span_lookup`].
+    pub fn with_span_fields(
+        self,
+        span_fields: SpanFields,
+    ) -> OpenTelemetryTracingBridge<P, L, SpanLookup> {
+        let mut bridge = self.with_span_lookup();
+        bridge.span_fields = span_fields;
+        bridge
+    }
+
+    /// Creates the log record of `event`, unless disabled by the level control.
+    fn log_record(&self, event: &tracing::Event<'_>) -> Option<L::LogRecord> {
         #[cfg(feature = "experimental_metadata_attributes")]
         let normalized_meta = event.normalized_metadata();
 
@@ -167,6 +601,12 @@ where
         #[cfg(not(feature = "experimental_metadata_attributes"))]
         let meta = event.metadata();
 
+        if let Some(level_control) = &self.level_control {
+            if !level_control.is_enabled(severity_of_level(meta.level()), meta.target()) {
+                return None;
+            }
+        }
+
         let mut log_record = self.logger.create_log_record();
 
         // TODO: Fix heap allocation
@@ -180,6 +620,167 @@ where
         // Visit fields.
         event.record(&mut visitor);
 
+        Some(log_record)
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn is_event_enabled(&self, event: &tracing_core::Event<'_>) -> bool {
+        let severity = severity_of_level(event.metadata().level());
+        self.logger
+            .event_enabled(severity, event.metadata().target())
+    }
+}
+
+impl<P, L> OpenTelemetryTracingBridge<P, L, SpanLookup>
+where
+    P: LoggerProvider<Logger = L> + Send + Sync,
+    L: Logger + Send + Sync,
+{
+    fn record_span_fields<S>(
+        &self,
+        event: &tracing::Event<'_>,
+        ctx: &tracing_subscriber::layer::Context<'_, S>,
+        log_record: &mut L::LogRecord,
+    ) where
+        S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+
+        // "message" is recorded as the body, not as an attribute.
+        let mut recorded: HashSet<&'static str> = event
+            .fields()
+            .map(|field| field.name())
+            .filter(|name| *name != "message")
+            .collect();
+        let mut path = Vec::new();
+
+        for span in scope {
+            if self.span_fields.span_path {
+                path.push(span.name());
+            }
+            let extensions = span.extensions();
+            let Some(values) = extensions.get::<SpanFieldValues>() else {
+                continue;
+            };
+            for (name, value) in &values.0 {
+                if recorded.insert(name) {
+                    log_record.add_attribute(Key::new(*name), value.clone());
+                    continue;
+                }
+                match self.span_fields.collision(name) {
+                    FieldCollision::Skip => {}
+                    FieldCollision::Prefix => log_record.add_attribute(
+                        Key::new(format!("{}.{}", span.name(), name)),
+                        value.clone(),
+                    ),
+                    FieldCollision::Keep => {
+                        log_record.add_attribute(Key::new(*name), value.clone())
+                    }
+                }
+            }
+        }
+
+        if !path.is_empty() {
+            path.reverse();
+            log_record.add_attribute(Key::new(SPAN_PATH_KEY), AnyValue::from(path.join(":")));
+        }
+    }
+}
+
+impl<S, P, L> Layer<S> for OpenTelemetryTracingBridge<P, L, NoSpanLookup>
+where
+    S: tracing::Subscriber,
+    P: LoggerProvider<Logger = L> + Send + Sync + 'static,
+    L: Logger + Send + Sync + 'static,
+{
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if let Some(log_record) = self.log_record(event) {
+            //emit record
+            self.logger.emit(log_record);
+        }
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(
+        &self,
+        _event: &tracing_core::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) -> bool {
+        self.is_event_enabled(_event)
+    }
+}
+
+impl<S, P, L> Layer<S> for OpenTelemetryTracingBridge<P, L, SpanLookup>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    P: LoggerProvider<Logger = L> + Send + Sync + 'static,
+    L: Logger + Send + Sync + 'static,
+{
+    fn on_new_span(
+        &self,
+        attrs: &Attributes<'_>,
+        id: &Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if !self.span_fields.captures_fields() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut values = SpanFieldValues::default();
+        attrs.record(&mut SpanFieldVisitor {
+            span_fields: &self.span_fields,
+            values: &mut values,
+        });
+        span.extensions_mut().insert(values);
+    }
+
+    fn on_record(
+        &self,
+        id: &Id,
+        values: &Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if !self.span_fields.captures_fields() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(span_values) = extensions.get_mut::<SpanFieldValues>() {
+            values.record(&mut SpanFieldVisitor {
+                span_fields: &self.span_fields,
+                values: span_values,
+            });
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let Some(mut log_record) = self.log_record(event) else {
+            return;
+        };
+
+        // Correlate with the OpenTelemetry span created by the span bridge.
+        if let Some(span_context) = ctx.event_span(event).and_then(span_context) {
+            log_record.set_trace_context(
+                span_context.trace_id(),
+                span_context.span_id(),
+                Some(span_context.trace_flags()),
+            );
+        }
+
+        if self.span_fields.is_enabled() {
+            self.record_span_fields(event, &ctx, &mut log_record);
+        }
+
         //emit record
         self.logger.emit(log_record);
     }
@@ -190,9 +791,7 @@ where
         _event: &tracing_core::Event<'_>,
         _ctx: tracing_subscriber::layer::Context<'_, S>,
     ) -> bool {
-        let severity = severity_of_level(_event.metadata().level());
-        self.logger
-            .event_enabled(severity, _event.metadata().target())
+        self.is_event_enabled(_event)
     }
 }
 
@@ -210,7 +809,7 @@ const fn severity_of_level(level: &Level) -> Severity {
 mod tests {
     use crate::layer;
     use async_trait::async_trait;
-    use opentelemetry::logs::Severity;
+    use opentelemetry::logs::{LevelControl, Severity};
     use opentelemetry::trace::TracerProvider as _;
     use opentelemetry::trace::{TraceContextExt, TraceFlags, Tracer};
     use opentelemetry::{logs::AnyValue, Key};
@@ -218,7 +817,7 @@ mod tests {
     use opentelemetry_sdk::logs::{LogRecord, LogResult, LoggerProvider};
     use opentelemetry_sdk::testing::logs::InMemoryLogExporter;
     use opentelemetry_sdk::trace::{Sampler, TracerProvider};
-    use tracing::{error, warn};
+    use tracing::{error, info_span, warn};
     use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
     use tracing_subscriber::util::SubscriberInitExt;
     use tracing_subscriber::{EnvFilter, Layer};
@@ -305,6 +904,79 @@ mod tests {
         warn!(name: "my-event-name", target: "my-system", event_id = 20, user_name = "otel", user_email = "otel@opentelemetry.io");
     }
 
+    #[test]
+    fn tracing_appender_level_control() {
+        // Arrange
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+
+        let level_control = LevelControl::new("error,my_crate::db=warn").unwrap();
+        let layer = layer::OpenTelemetryTracingBridge::new(&logger_provider)
+            .with_level_control(level_control.clone());
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+        // Act
+        warn!(target: "my_crate::db::pool", "kept");
+        warn!(target: "my_crate::http", "skipped");
+        level_control.set_directives("warn").unwrap();
+        warn!(target: "my_crate::http", "kept");
+        logger_provider.force_flush();
+
+        // Assert
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        let targets: Vec<_> = exported_logs
+            .iter()
+            .map(|log| log.record.target.as_deref().unwrap())
+            .collect();
+        assert_eq!(targets, vec!["my_crate::db::pool", "my_crate::http"]);
+    }
+
+    /// A subscriber that does not implement `LookupSpan`.
+    struct MinimalSubscriber;
+
+    impl tracing::Subscriber for MinimalSubscriber {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &tracing_core::span::Attributes<'_>) -> tracing::Id {
+            tracing::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &tracing::Id, _values: &tracing_core::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &tracing::Id, _follows: &tracing::Id) {}
+
+        fn event(&self, _event: &tracing::Event<'_>) {}
+
+        fn enter(&self, _span: &tracing::Id) {}
+
+        fn exit(&self, _span: &tracing::Id) {}
+    }
+
+    #[test]
+    fn tracing_appender_without_span_lookup() {
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let layer = layer::OpenTelemetryTracingBridge::new(&logger_provider);
+
+        tracing::subscriber::with_default(layer.with_subscriber(MinimalSubscriber), || {
+            error!(name: "my-event-name", target: "my-system", "request failed");
+        });
+
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        assert_eq!(exported_logs.len(), 1);
+        assert_eq!(exported_logs[0].record.target.as_deref(), Some("my-system"));
+    }
+
     #[test]
     fn tracing_appender_standalone() {
         // Arrange
@@ -656,4 +1328,196 @@ mod tests {
             assert!(!attributes_key.contains(&Key::new("log.target")));
         }
     }
+
+    fn emit_in_spans(span_fields: layer::SpanFields) -> LogRecord {
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let layer =
+            layer::OpenTelemetryTracingBridge::new(&logger_provider).with_span_fields(span_fields);
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+        let outer = info_span!("request", request_id = 7, user_id = "outer", method = "GET");
+        let _outer = outer.enter();
+        let inner = info_span!("db", user_id = "inner", rows = tracing::field::Empty);
+        let _inner = inner.enter();
+        inner.record("rows", 3);
+        error!(method = "POST", "query failed");
+
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        assert_eq!(exported_logs.len(), 1);
+        exported_logs[0].record.clone()
+    }
+
+    fn attribute_values(log_record: &LogRecord, key: &str) -> Vec<AnyValue> {
+        log_record
+            .attributes_iter()
+            .filter(|(k, _)| k.as_str() == key)
+            .map(|(_, v)| v.clone())
+            .collect()
+    }
+
+    #[test]
+    fn tracing_appender_ignores_span_fields_by_default() {
+        let record = emit_in_spans(layer::SpanFields::default());
+
+        #[cfg(not(feature = "experimental_metadata_attributes"))]
+        assert_eq!(record.attributes_iter().count(), 1);
+        #[cfg(feature = "experimental_metadata_attributes")]
+        assert_eq!(record.attributes_iter().count(), 5);
+        assert!(attribute_values(&record, "request_id").is_empty());
+        assert!(attribute_values(&record, layer::SPAN_PATH_KEY).is_empty());
+    }
+
+    #[test]
+    fn tracing_appender_with_all_span_fields() {
+        let record = emit_in_spans(layer::SpanFields::all());
+
+        assert_eq!(
+            attribute_values(&record, "method"),
+            [AnyValue::String("POST".into())]
+        );
+        assert_eq!(
+            attribute_values(&record, "user_id"),
+            [AnyValue::String("inner".into())]
+        );
+        assert_eq!(attribute_values(&record, "rows"), [AnyValue::Int(3)]);
+        assert_eq!(attribute_values(&record, "request_id"), [AnyValue::Int(7)]);
+        #[cfg(not(feature = "experimental_metadata_attributes"))]
+        assert_eq!(record.attributes_iter().count(), 4);
+        #[cfg(feature = "experimental_metadata_attributes")]
+        assert_eq!(record.attributes_iter().count(), 8);
+    }
+
+    #[test]
+    fn tracing_appender_with_selected_span_fields_and_collisions() {
+        let record = emit_in_spans(
+            layer::SpanFields::only(["user_id", "method"])
+                .with_collision(layer::FieldCollision::Prefix)
+                .with_field_collision("method", layer::FieldCollision::Keep)
+                .with_span_path(),
+        );
+
+        assert_eq!(
+            attribute_values(&record, "user_id"),
+            [AnyValue::String("inner".into())]
+        );
+        assert_eq!(
+            attribute_values(&record, "request.user_id"),
+            [AnyValue::String("outer".into())]
+        );
+        assert_eq!(
+            attribute_values(&record, "method"),
+            [
+                AnyValue::String("POST".into()),
+                AnyValue::String("GET".into())
+            ]
+        );
+        assert!(attribute_values(&record, "request_id").is_empty());
+        assert_eq!(
+            attribute_values(&record, layer::SPAN_PATH_KEY),
+            [AnyValue::String("request:db".into())]
+        );
+    }
+
+    #[test]
+    fn tracing_appender_keeps_value_types() {
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let layer = layer::OpenTelemetryTracingBridge::new(&logger_provider);
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+        error!(
+            small_u64 = 42u64,
+            big_u64 = u64::MAX,
+            small_i128 = -42i128,
+            big_i128 = i128::MIN,
+            big_u128 = u128::MAX,
+            bytes = &b"otel"[..],
+        );
+
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        let record = &exported_logs[0].record;
+        assert!(attributes_contains(
+            record,
+            &Key::new("small_u64"),
+            &AnyValue::Int(42)
+        ));
+        assert!(attributes_contains(
+            record,
+            &Key::new("big_u64"),
+            &AnyValue::String(u64::MAX.to_string().into())
+        ));
+        assert!(attributes_contains(
+            record,
+            &Key::new("small_i128"),
+            &AnyValue::Int(-42)
+        ));
+        assert!(attributes_contains(
+            record,
+            &Key::new("big_i128"),
+            &AnyValue::String(i128::MIN.to_string().into())
+        ));
+        assert!(attributes_contains(
+            record,
+            &Key::new("big_u128"),
+            &AnyValue::String(u128::MAX.to_string().into())
+        ));
+        assert!(attributes_contains(
+            record,
+            &Key::new("bytes"),
+            &AnyValue::Bytes(Box::new(b"otel".to_vec()))
+        ));
+    }
+
+    #[cfg(all(tracing_unstable, feature = "valuable"))]
+    #[test]
+    fn tracing_appender_maps_valuable_values() {
+        use std::collections::HashMap;
+
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let layer = layer::OpenTelemetryTracingBridge::new(&logger_provider);
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+        let list = vec![1u64, u64::MAX];
+        let map = HashMap::from([("retries".to_string(), (3i32, "ok"))]);
+        error!(
+            list = tracing::field::valuable(&list),
+            map = tracing::field::valuable(&map),
+        );
+
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        let record = &exported_logs[0].record;
+        assert!(attributes_contains(
+            record,
+            &Key::new("list"),
+            &AnyValue::ListAny(Box::new(vec![
+                AnyValue::Int(1),
+                AnyValue::String(u64::MAX.to_string().into())
+            ]))
+        ));
+        assert!(attributes_contains(
+            record,
+            &Key::new("map"),
+            &AnyValue::Map(Box::new(HashMap::from([(
+                Key::new("retries"),
+                AnyValue::ListAny(Box::new(vec![
+                    AnyValue::Int(3),
+                    AnyValue::String("ok".into())
+                ]))
+            )])))
+        ));
+    }
 }
@@ -1 +1,2 @@
 pub mod layer;
+pub mod span;
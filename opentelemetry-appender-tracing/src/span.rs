@@ -0,0 +1,611 @@
+use opentelemetry::{
+    trace::{
+        Span, SpanBuilder, SpanContext, SpanKind, Status, TraceContextExt, Tracer, TracerProvider,
+    },
+    Context, ContextGuard, InstrumentationScope, KeyValue, Value,
+};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::time::SystemTime;
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Field, Metadata};
+use tracing_subscriber::registry::{LookupSpan, SpanRef};
+use tracing_subscriber::Layer;
+
+const INSTRUMENTATION_LIBRARY_NAME: &str = "opentelemetry-appender-tracing";
+
+/// Field overriding the name of the OpenTelemetry span.
+const OTEL_NAME: &str = "otel.name";
+/// Field setting the [`SpanKind`] of the OpenTelemetry span, e.g. `"server"`.
+const OTEL_KIND: &str = "otel.kind";
+/// Field setting the [`Status`] of the OpenTelemetry span, `"ok"` or `"error"`.
+const OTEL_STATUS_CODE: &str = "otel.status_code";
+/// Field setting the description of an error [`Status`].
+const OTEL_STATUS_MESSAGE: &str = "otel.status_message";
+
+/// The span context of the OpenTelemetry span created for a `tracing` span,
+/// stored in the span's extensions so that the log bridge can correlate log
+/// records with it.
+#[derive(Debug)]
+pub(crate) struct TracingSpanContext(pub(crate) SpanContext);
+
+/// Returns the span context of the OpenTelemetry span created for `span` or,
+/// failing that, for its closest ancestor.
+pub(crate) fn span_context<'a, R>(span: SpanRef<'a, R>) -> Option<SpanContext>
+where
+    R: LookupSpan<'a>,
+{
+    span.scope()
+        .find_map(|span| Some(span.extensions().get::<TracingSpanContext>()?.0.clone()))
+}
+
+/// The OpenTelemetry context of a `tracing` span, holding the OpenTelemetry
+/// span created for it, which is ended when the `tracing` span closes.
+struct SpanState {
+    cx: Context,
+}
+
+thread_local! {
+    /// The guards of the contexts attached when entering `tracing` spans on
+    /// this thread, most recently entered last. `ContextGuard` is `!Send`, so
+    /// the guards cannot be kept in the extensions of the spans.
+    static ENTERED: RefCell<Vec<(Id, ContextGuard)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the context of the OpenTelemetry span created for `span` or,
+/// failing that, for its closest ancestor.
+fn span_cx<'a, R>(span: SpanRef<'a, R>) -> Option<Context>
+where
+    R: LookupSpan<'a>,
+{
+    span.scope()
+        .find_map(|span| Some(span.extensions().get::<SpanState>()?.cx.clone()))
+}
+
+/// The parts of an OpenTelemetry span collected from `tracing` fields.
+#[derive(Default)]
+struct OtelFields {
+    name: Option<Cow<'static, str>>,
+    kind: Option<SpanKind>,
+    status_code: Option<bool>,
+    status_message: Option<String>,
+    attributes: Vec<KeyValue>,
+}
+
+impl OtelFields {
+    fn status(&mut self) -> Option<Status> {
+        match self.status_code? {
+            true => Some(Status::Ok),
+            false => Some(Status::error(
+                self.status_message.take().unwrap_or_default(),
+            )),
+        }
+    }
+}
+
+fn span_kind(kind: &str) -> Option<SpanKind> {
+    match kind {
+        _ if kind.eq_ignore_ascii_case("client") => Some(SpanKind::Client),
+        _ if kind.eq_ignore_ascii_case("server") => Some(SpanKind::Server),
+        _ if kind.eq_ignore_ascii_case("producer") => Some(SpanKind::Producer),
+        _ if kind.eq_ignore_ascii_case("consumer") => Some(SpanKind::Consumer),
+        _ if kind.eq_ignore_ascii_case("internal") => Some(SpanKind::Internal),
+        _ => None,
+    }
+}
+
+/// Visitor to record the fields of a span.
+struct SpanVisitor<'a> {
+    fields: &'a mut OtelFields,
+}
+
+impl SpanVisitor<'_> {
+    fn record(&mut self, field: &Field, value: Value) {
+        self.fields
+            .attributes
+            .push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_string(&mut self, field: &Field, value: String) {
+        match field.name() {
+            OTEL_NAME => self.fields.name = Some(value.into()),
+            OTEL_KIND => self.fields.kind = span_kind(&value),
+            OTEL_STATUS_CODE => {
+                if value.eq_ignore_ascii_case("ok") {
+                    self.fields.status_code = Some(true);
+                } else if value.eq_ignore_ascii_case("error") {
+                    self.fields.status_code = Some(false);
+                }
+            }
+            OTEL_STATUS_MESSAGE => self.fields.status_message = Some(value),
+            _ => self.record(field, value.into()),
+        }
+    }
+}
+
+impl tracing::field::Visit for SpanVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_string(field, format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_string(field, value.to_owned());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.record(field, value.into()),
+            Err(_) => self.record(field, value.to_string().into()),
+        }
+    }
+}
+
+/// Visitor to record the fields of an event as a span event.
+struct EventVisitor {
+    name: Option<String>,
+    attributes: Vec<KeyValue>,
+}
+
+impl tracing::field::Visit for EventVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.name = Some(format!("{value:?}"));
+        } else {
+            self.attributes
+                .push(KeyValue::new(field.name(), format!("{value:?}")));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.name = Some(value.to_owned());
+        } else {
+            self.attributes
+                .push(KeyValue::new(field.name(), value.to_owned()));
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.attributes.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.attributes.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.attributes.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.attributes.push(KeyValue::new(field.name(), value)),
+            Err(_) => self
+                .attributes
+                .push(KeyValue::new(field.name(), value.to_string())),
+        }
+    }
+}
+
+/// The `level` and `code.*` attributes describing where a span or event was
+/// recorded.
+fn metadata_attributes(meta: &'static Metadata<'static>, attributes: &mut Vec<KeyValue>) {
+    attributes.push(KeyValue::new("level", meta.level().as_str()));
+    if let Some(module_path) = meta.module_path() {
+        attributes.push(KeyValue::new("code.namespace", module_path));
+    }
+    if let Some(filepath) = meta.file() {
+        attributes.push(KeyValue::new("code.filepath", filepath));
+    }
+    if let Some(line) = meta.line() {
+        attributes.push(KeyValue::new("code.lineno", i64::from(line)));
+    }
+}
+
+/// A [`Layer`] creating OpenTelemetry spans from `tracing` spans.
+///
+/// Every `tracing` span starts a span with the same name, and ends it when the
+/// `tracing` span closes:
+///
+/// * the span fields, the level and the source location become attributes.
+///   The `otel.name`, `otel.kind`, `otel.status_code` and `otel.status_message`
+///   fields instead set the span's name, kind and status,
+/// * events recorded inside the span become span events, named after their
+///   message,
+/// * `follows_from` relationships become links.
+///
+/// Spans are parented according to the `tracing` span hierarchy. Root
+/// `tracing` spans are children of the active OpenTelemetry [`Context`].
+///
+/// While a `tracing` span is entered, the context of its OpenTelemetry span is
+/// the current [`Context`], so log records emitted through any OpenTelemetry
+/// logger, OpenTelemetry spans started and propagators used inside the
+/// `tracing` span see its trace context. An [`OpenTelemetryTracingBridge`]
+/// looking up spans in the same subscriber also finds the trace context of
+/// records of `tracing` events emitted outside an entered span, e.g. with an
+/// explicit parent.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_appender_tracing::{layer::OpenTelemetryTracingBridge, span::OpenTelemetrySpanBridge};
+/// use opentelemetry_sdk::{logs::LoggerProvider, trace::TracerProvider};
+/// use tracing_subscriber::prelude::*;
+///
+/// let tracer_provider = TracerProvider::builder().build();
+/// let logger_provider = LoggerProvider::builder().build();
+///
+/// let subscriber = tracing_subscriber::registry()
+///     .with(OpenTelemetrySpanBridge::new(&tracer_provider))
+///     .with(OpenTelemetryTracingBridge::new(&logger_provider).with_span_lookup());
+/// ```
+///
+/// [`OpenTelemetryTracingBridge`]: crate::layer::OpenTelemetryTracingBridge
+pub struct OpenTelemetrySpanBridge<P, T>
+where
+    P: TracerProvider<Tracer = T> + Send + Sync,
+    T: Tracer + Send + Sync,
+{
+    tracer: T,
+    _phantom: std::marker::PhantomData<P>, // P is not used.
+}
+
+impl<P, T> OpenTelemetrySpanBridge<P, T>
+where
+    P: TracerProvider<Tracer = T> + Send + Sync,
+    T: Tracer + Send + Sync,
+{
+    /// Create a new `OpenTelemetrySpanBridge` creating spans with a tracer of
+    /// `provider`.
+    pub fn new(provider: &P) -> Self {
+        let scope = InstrumentationScope::builder(INSTRUMENTATION_LIBRARY_NAME)
+            .with_version(Cow::Borrowed(env!("CARGO_PKG_VERSION")))
+            .build();
+
+        OpenTelemetrySpanBridge {
+            tracer: provider.tracer_with_scope(scope),
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<S, P, T> Layer<S> for OpenTelemetrySpanBridge<P, T>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    P: TracerProvider<Tracer = T> + Send + Sync + 'static,
+    T: Tracer + Send + Sync + 'static,
+    T::Span: Send + Sync + 'static,
+{
+    fn on_new_span(
+        &self,
+        attrs: &Attributes<'_>,
+        id: &Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let parent_cx = if let Some(parent) = span.parent() {
+            span_cx(parent).unwrap_or_else(Context::current)
+        } else if attrs.is_root() {
+            Context::new()
+        } else {
+            Context::current()
+        };
+
+        let mut fields = OtelFields::default();
+        attrs.record(&mut SpanVisitor {
+            fields: &mut fields,
+        });
+        metadata_attributes(attrs.metadata(), &mut fields.attributes);
+
+        let name = fields
+            .name
+            .take()
+            .unwrap_or(Cow::Borrowed(attrs.metadata().name()));
+        let mut builder = SpanBuilder::from_name(name)
+            .with_kind(fields.kind.take().unwrap_or(SpanKind::Internal))
+            .with_start_time(SystemTime::now())
+            .with_attributes(std::mem::take(&mut fields.attributes));
+        if let Some(status) = fields.status() {
+            builder = builder.with_status(status);
+        }
+        let otel_span = self.tracer.build_with_context(builder, &parent_cx);
+
+        let mut extensions = span.extensions_mut();
+        extensions.insert(TracingSpanContext(otel_span.span_context().clone()));
+        extensions.insert(SpanState {
+            cx: parent_cx.with_span(otel_span),
+        });
+    }
+
+    fn on_record(
+        &self,
+        id: &Id,
+        values: &Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(state) = extensions.get::<SpanState>() else {
+            return;
+        };
+
+        let mut fields = OtelFields::default();
+        values.record(&mut SpanVisitor {
+            fields: &mut fields,
+        });
+        let otel_span = state.cx.span();
+        if let Some(name) = fields.name.take() {
+            otel_span.update_name(name);
+        }
+        if let Some(status) = fields.status() {
+            otel_span.set_status(status);
+        }
+        otel_span.set_attributes(fields.attributes);
+    }
+
+    fn on_follows_from(
+        &self,
+        id: &Id,
+        follows: &Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let (Some(span), Some(follows)) = (ctx.span(id), ctx.span(follows)) else {
+            return;
+        };
+        let Some(follows) = span_context(follows) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let Some(state) = extensions.get::<SpanState>() {
+            state.cx.span().add_link(follows, Vec::new());
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(state) = extensions.get::<SpanState>() else {
+            return;
+        };
+
+        let mut visitor = EventVisitor {
+            name: None,
+            attributes: Vec::new(),
+        };
+        event.record(&mut visitor);
+        metadata_attributes(event.metadata(), &mut visitor.attributes);
+
+        let name = visitor
+            .name
+            .unwrap_or_else(|| event.metadata().name().to_owned());
+        state.cx.span().add_event(name, visitor.attributes);
+    }
+
+    fn on_enter(&self, id: &Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(cx) = span
+            .extensions()
+            .get::<SpanState>()
+            .map(|state| state.cx.clone())
+        else {
+            return;
+        };
+        let guard = cx.attach();
+        ENTERED.with(|entered| entered.borrow_mut().push((id.clone(), guard)));
+    }
+
+    fn on_exit(&self, id: &Id, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let guard = ENTERED.with(|entered| {
+            let mut entered = entered.borrow_mut();
+            let position = entered.iter().rposition(|(entered, _)| entered == id)?;
+            Some(entered.remove(position).1)
+        });
+        // restores the context current before the span was entered
+        drop(guard);
+    }
+
+    fn on_close(&self, id: Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let state = span.extensions_mut().remove::<SpanState>();
+        if let Some(state) = state {
+            state.cx.span().end_with_timestamp(SystemTime::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layer::OpenTelemetryTracingBridge;
+    use crate::span::OpenTelemetrySpanBridge;
+    use opentelemetry::logs::{LogRecord as _, Logger as _, LoggerProvider as _};
+    use opentelemetry::trace::{SpanKind, Status, TraceContextExt, Tracer, TracerProvider as _};
+    use opentelemetry::{Context, KeyValue, Value};
+    use opentelemetry_sdk::export::trace::SpanData;
+    use opentelemetry_sdk::logs::LoggerProvider;
+    use opentelemetry_sdk::testing::logs::InMemoryLogExporter;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tracing::{error, info, info_span};
+    use tracing_subscriber::prelude::*;
+
+    fn attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| &kv.value)
+    }
+
+    fn finished_span<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+        spans
+            .iter()
+            .find(|span| span.name == name)
+            .expect("span is expected to be exported")
+    }
+
+    #[test]
+    fn span_bridge_creates_spans() {
+        let exporter = InMemorySpanExporter::default();
+        let tracer_provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber =
+            tracing_subscriber::registry().with(OpenTelemetrySpanBridge::new(&tracer_provider));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = info_span!(
+                "outer",
+                otel.name = "GET /books",
+                otel.kind = "server",
+                user_id = 42,
+                otel.status_code = tracing::field::Empty,
+            );
+            let _outer = outer.enter();
+            {
+                let inner = info_span!("inner", rows = tracing::field::Empty);
+                let _inner = inner.enter();
+                inner.record("rows", 3);
+                info!(book_id = 7, "book found");
+            }
+            outer.record("otel.status_code", "ok");
+        });
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 2);
+        let outer = finished_span(&spans, "GET /books");
+        let inner = finished_span(&spans, "inner");
+
+        assert_eq!(outer.span_kind, SpanKind::Server);
+        assert_eq!(outer.status, Status::Ok);
+        assert_eq!(attribute(outer, "user_id"), Some(&Value::I64(42)));
+        assert_eq!(attribute(outer, "level"), Some(&Value::from("INFO")));
+        assert!(attribute(outer, "otel.kind").is_none());
+
+        assert_eq!(inner.span_kind, SpanKind::Internal);
+        assert_eq!(inner.span_context.trace_id(), outer.span_context.trace_id());
+        assert_eq!(inner.parent_span_id, outer.span_context.span_id());
+        assert_eq!(attribute(inner, "rows"), Some(&Value::I64(3)));
+        assert_eq!(inner.events.len(), 1);
+        let event = &inner.events[0];
+        assert_eq!(event.name, "book found");
+        assert!(event.attributes.contains(&KeyValue::new("book_id", 7)));
+    }
+
+    #[test]
+    fn span_bridge_links_follows_from() {
+        let exporter = InMemorySpanExporter::default();
+        let tracer_provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber =
+            tracing_subscriber::registry().with(OpenTelemetrySpanBridge::new(&tracer_provider));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let cause = info_span!("cause");
+            let effect = info_span!(parent: None, "effect");
+            effect.follows_from(&cause);
+        });
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let cause = finished_span(&spans, "cause");
+        let effect = finished_span(&spans, "effect");
+        assert_ne!(
+            effect.span_context.trace_id(),
+            cause.span_context.trace_id()
+        );
+        assert_eq!(effect.links.len(), 1);
+        assert_eq!(effect.links[0].span_context, cause.span_context);
+    }
+
+    #[test]
+    fn span_bridge_context_is_current_while_entered() {
+        let span_exporter = InMemorySpanExporter::default();
+        let tracer_provider = TracerProvider::builder()
+            .with_simple_exporter(span_exporter.clone())
+            .build();
+        let log_exporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(log_exporter.clone())
+            .build();
+        let subscriber =
+            tracing_subscriber::registry().with(OpenTelemetrySpanBridge::new(&tracer_provider));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!("request");
+            {
+                let _span = span.enter();
+                let logger = logger_provider.logger("plain");
+                let mut record = logger.create_log_record();
+                record.set_body("request failed".into());
+                logger.emit(record);
+                tracer_provider.tracer("native").in_span("query", |_cx| {});
+            }
+            assert!(!Context::current().has_active_span());
+        });
+
+        let spans = span_exporter.get_finished_spans().unwrap();
+        let request = finished_span(&spans, "request");
+        let query = finished_span(&spans, "query");
+        assert_eq!(
+            query.span_context.trace_id(),
+            request.span_context.trace_id()
+        );
+        assert_eq!(query.parent_span_id, request.span_context.span_id());
+
+        let logs = log_exporter.get_emitted_logs().unwrap();
+        assert_eq!(logs.len(), 1);
+        let trace_context = logs[0].record.trace_context.as_ref().unwrap();
+        assert_eq!(trace_context.trace_id, request.span_context.trace_id());
+        assert_eq!(trace_context.span_id, request.span_context.span_id());
+    }
+
+    #[test]
+    fn log_records_carry_span_bridge_trace_context() {
+        let span_exporter = InMemorySpanExporter::default();
+        let tracer_provider = TracerProvider::builder()
+            .with_simple_exporter(span_exporter.clone())
+            .build();
+        let log_exporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(log_exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(OpenTelemetrySpanBridge::new(&tracer_provider))
+            .with(OpenTelemetryTracingBridge::new(&logger_provider).with_span_lookup());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!("request");
+            let _span = span.enter();
+            error!("request failed");
+        });
+
+        let spans = span_exporter.get_finished_spans().unwrap();
+        let logs = log_exporter.get_emitted_logs().unwrap();
+        assert_eq!(logs.len(), 1);
+        let trace_context = logs[0].record.trace_context.as_ref().unwrap();
+        assert_eq!(trace_context.trace_id, spans[0].span_context.trace_id());
+        assert_eq!(trace_context.span_id, spans[0].span_context.span_id());
+    }
+}
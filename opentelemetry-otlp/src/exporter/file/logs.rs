@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
+use opentelemetry_sdk::export::logs::{LogBatch, LogExporter};
+use opentelemetry_sdk::logs::LogResult;
+use std::time::Duration;
+
+use super::OtlpFileClient;
+
+#[async_trait]
+impl LogExporter for OtlpFileClient {
+    async fn export(&mut self, batch: LogBatch<'_>) -> LogResult<()> {
+        let resource_logs = group_logs_by_resource_and_scope(batch, &self.resource);
+        Ok(self.write(&ExportLogsServiceRequest { resource_logs })?)
+    }
+
+    fn force_flush(&mut self, _timeout: Duration) -> LogResult<()> {
+        Ok(self.flush()?)
+    }
+
+    fn shutdown(&mut self) {
+        let _ = OtlpFileClient::shutdown(self);
+    }
+
+    fn set_resource(&mut self, resource: &opentelemetry_sdk::Resource) {
+        self.resource = resource.into();
+    }
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::MetricResult;
+
+use crate::metric::MetricsClient;
+
+use super::OtlpFileClient;
+
+#[async_trait]
+impl MetricsClient for OtlpFileClient {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> MetricResult<()> {
+        let request: ExportMetricsServiceRequest = (&*metrics).into();
+        Ok(self.write(&request)?)
+    }
+
+    fn shutdown(&self) -> MetricResult<()> {
+        Ok(OtlpFileClient::shutdown(self)?)
+    }
+}
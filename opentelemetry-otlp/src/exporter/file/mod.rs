@@ -0,0 +1,440 @@
+//! Writes OTLP data to rotating files instead of sending it to a collector.
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+use prost::Message;
+use serde::Serialize;
+
+use crate::exporter::Compression;
+
+mod rotation;
+
+#[cfg(feature = "logs")]
+mod logs;
+
+#[cfg(feature = "metrics")]
+mod metrics;
+
+#[cfg(feature = "trace")]
+mod trace;
+
+use rotation::RotatingFile;
+
+/// The encoding of the files written by the file exporter.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FileFormat {
+    /// One OTLP JSON export request per line, as read by the collector's
+    /// `otlpjsonfile` receiver.
+    #[default]
+    Json,
+    /// Protobuf export requests, each prefixed with its length as a 4 bytes
+    /// big-endian integer, as written by the collector's `file` exporter.
+    Protobuf,
+}
+
+impl FileFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Json => "jsonl",
+            FileFormat::Protobuf => "binpb",
+        }
+    }
+}
+
+/// Configuration of the file exporter.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct FileConfig {
+    /// The file to write to. Defaults to `traces`, `logs` or `metrics` in the
+    /// current directory, with the extension of the format.
+    pub(crate) path: Option<PathBuf>,
+    /// The encoding of the export requests.
+    pub(crate) format: FileFormat,
+    /// Size in bytes after which the file is rolled over.
+    pub(crate) max_file_size: Option<u64>,
+    /// Age after which the file is rolled over.
+    pub(crate) rotation_interval: Option<Duration>,
+    /// Number of rolled files to keep.
+    pub(crate) max_files: Option<usize>,
+    /// Compression of the rolled files.
+    pub(crate) compression: Option<Compression>,
+}
+
+/// Configuration for the OTLP file exporter.
+///
+/// Each export is written as one OTLP export request to the file, which is
+/// rolled over to `<name>-<unix millis>.<extension>` once it exceeds the
+/// configured size or age.
+///
+/// ## Examples
+///
+/// ```no_run
+/// # #[cfg(feature="trace")]
+/// # {
+/// use opentelemetry_otlp::{FileFormat, WithFileConfig};
+/// use std::time::Duration;
+///
+/// let span_exporter = opentelemetry_otlp::SpanExporter::builder()
+///     .with_file()
+///     .with_path("/var/log/otel/traces.jsonl")
+///     .with_format(FileFormat::Json)
+///     .with_max_file_size(64 * 1024 * 1024)
+///     .with_rotation_interval(Duration::from_secs(3600))
+///     .with_max_files(24)
+///     .build()
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct FileExporterBuilder {
+    pub(crate) file_config: FileConfig,
+}
+
+impl FileExporterBuilder {
+    fn build_client(self, default_name: &str) -> Result<OtlpFileClient, crate::Error> {
+        let config = self.file_config;
+        match config.compression {
+            #[cfg(not(feature = "gzip-file"))]
+            Some(Compression::Gzip) => {
+                return Err(crate::Error::FeatureRequiredForCompressionAlgorithm(
+                    "gzip-file",
+                    Compression::Gzip,
+                ))
+            }
+            Some(Compression::Zstd) => {
+                return Err(crate::Error::UnsupportedCompressionAlgorithm(
+                    Compression::Zstd.to_string(),
+                ))
+            }
+            _ => {}
+        }
+
+        let path = config.path.clone().unwrap_or_else(|| {
+            PathBuf::from(format!("{}.{}", default_name, config.format.extension()))
+        });
+        let format = config.format;
+        let file = RotatingFile::open(path, config)?;
+        Ok(OtlpFileClient {
+            file: Mutex::new(Some(file)),
+            format,
+            #[cfg(any(feature = "trace", feature = "logs"))]
+            resource: ResourceAttributesWithSchema::default(),
+        })
+    }
+
+    /// Create a span exporter with the current configuration
+    #[cfg(feature = "trace")]
+    pub fn build_span_exporter(
+        self,
+    ) -> Result<crate::SpanExporter, opentelemetry::trace::TraceError> {
+        let client = self.build_client("traces")?;
+        Ok(crate::SpanExporter::new(client))
+    }
+
+    /// Create a log exporter with the current configuration
+    #[cfg(feature = "logs")]
+    pub fn build_log_exporter(self) -> opentelemetry_sdk::logs::LogResult<crate::LogExporter> {
+        let client = self.build_client("logs")?;
+        Ok(crate::LogExporter::new(client))
+    }
+
+    /// Create a metrics exporter with the current configuration
+    #[cfg(feature = "metrics")]
+    pub fn build_metrics_exporter(
+        self,
+        temporality: opentelemetry_sdk::metrics::Temporality,
+    ) -> opentelemetry_sdk::metrics::MetricResult<crate::MetricExporter> {
+        let client = self.build_client("metrics")?;
+        Ok(crate::MetricExporter::new(client, temporality))
+    }
+}
+
+#[derive(Debug)]
+struct OtlpFileClient {
+    /// `None` once shut down.
+    file: Mutex<Option<RotatingFile>>,
+    format: FileFormat,
+    /// Metrics carry their own resource.
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    resource: ResourceAttributesWithSchema,
+}
+
+impl OtlpFileClient {
+    /// Encodes `request` and appends it to the file.
+    fn write<M: Message + Serialize>(&self, request: &M) -> Result<(), crate::Error> {
+        let data = match self.format {
+            FileFormat::Json => {
+                let mut data = serde_json::to_vec(request)?;
+                data.push(b'\n');
+                data
+            }
+            FileFormat::Protobuf => {
+                let len = request.encoded_len();
+                let mut data = Vec::with_capacity(4 + len);
+                data.extend_from_slice(&(len as u32).to_be_bytes());
+                request.encode(&mut data)?;
+                data
+            }
+        };
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| crate::Error::PoisonedLock("file exporter"))?;
+        match file.as_mut() {
+            Some(file) => Ok(file.write(&data)?),
+            None => Err(crate::Error::AlreadyShutdown),
+        }
+    }
+
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    fn flush(&self) -> Result<(), crate::Error> {
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| crate::Error::PoisonedLock("file exporter"))?;
+        match file.as_mut() {
+            Some(file) => Ok(file.flush()?),
+            None => Ok(()),
+        }
+    }
+
+    fn shutdown(&self) -> Result<(), crate::Error> {
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| crate::Error::PoisonedLock("file exporter"))?;
+        match file.take() {
+            Some(mut file) => Ok(file.flush()?),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Expose interface for modifying builder config.
+pub trait HasFileConfig {
+    /// Return a mutable reference to the config within the exporter builders.
+    fn file_config(&mut self) -> &mut FileConfig;
+}
+
+/// Expose interface for modifying builder config.
+impl HasFileConfig for FileExporterBuilder {
+    fn file_config(&mut self) -> &mut FileConfig {
+        &mut self.file_config
+    }
+}
+
+/// This trait will be implemented for every struct that implemented [`HasFileConfig`] trait.
+///
+/// ## Examples
+/// ```
+/// # #[cfg(feature = "trace")]
+/// # {
+/// use crate::opentelemetry_otlp::WithFileConfig;
+/// let exporter_builder = opentelemetry_otlp::SpanExporter::builder()
+///     .with_file()
+///     .with_path("traces.jsonl");
+/// # }
+/// ```
+pub trait WithFileConfig {
+    /// Set the file to write to.
+    fn with_path<P: Into<PathBuf>>(self, path: P) -> Self;
+
+    /// Set the encoding of the export requests. Defaults to [`FileFormat::Json`].
+    fn with_format(self, format: FileFormat) -> Self;
+
+    /// Roll the file over once it would exceed `max_file_size` bytes.
+    fn with_max_file_size(self, max_file_size: u64) -> Self;
+
+    /// Roll the file over once it has been written to for `interval`.
+    fn with_rotation_interval(self, interval: Duration) -> Self;
+
+    /// Keep at most `max_files` rolled files, removing the oldest ones.
+    fn with_max_files(self, max_files: usize) -> Self;
+
+    /// Compress the rolled files. Only [`Compression::Gzip`] is supported,
+    /// with the `gzip-file` feature.
+    fn with_compression(self, compression: Compression) -> Self;
+}
+
+impl<B: HasFileConfig> WithFileConfig for B {
+    fn with_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.file_config().path = Some(path.into());
+        self
+    }
+
+    fn with_format(mut self, format: FileFormat) -> Self {
+        self.file_config().format = format;
+        self
+    }
+
+    fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.file_config().max_file_size = Some(max_file_size);
+        self
+    }
+
+    fn with_rotation_interval(mut self, interval: Duration) -> Self {
+        self.file_config().rotation_interval = Some(interval);
+        self
+    }
+
+    fn with_max_files(mut self, max_files: usize) -> Self {
+        self.file_config().max_files = Some(max_files);
+        self
+    }
+
+    fn with_compression(mut self, compression: Compression) -> Self {
+        self.file_config().compression = Some(compression);
+        self
+    }
+}
+
+#[cfg(all(test, feature = "trace", feature = "logs"))]
+mod tests {
+    use super::*;
+    use crate::{LogExporter, SpanExporter};
+    use opentelemetry::logs::{LogRecord as _, Logger as _, LoggerProvider as _};
+    use opentelemetry::trace::{Tracer as _, TracerProvider as _};
+    use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+    use std::fs;
+    use std::path::Path;
+
+    fn emit_spans(exporter: SpanExporter, names: &[&'static str]) {
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_simple_exporter(exporter)
+            .build();
+        let tracer = provider.tracer("test");
+        for name in names {
+            tracer.in_span(*name, |_| {});
+        }
+        let _ = provider.shutdown();
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn writes_one_json_request_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("otel").join("traces.jsonl");
+        let exporter = SpanExporter::builder()
+            .with_file()
+            .with_path(&path)
+            .build()
+            .unwrap();
+
+        emit_spans(exporter, &["first", "second"]);
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(r#"{"resourceSpans":[{"resource":"#));
+        assert!(lines[0].contains(r#""name":"first""#));
+        assert!(lines[1].contains(r#""name":"second""#));
+    }
+
+    #[test]
+    fn writes_length_delimited_protobuf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.binpb");
+        let exporter = LogExporter::builder()
+            .with_file()
+            .with_path(&path)
+            .with_format(FileFormat::Protobuf)
+            .build()
+            .unwrap();
+        let provider = opentelemetry_sdk::logs::LoggerProvider::builder()
+            .with_simple_exporter(exporter)
+            .build();
+        let logger = provider.logger("test");
+        let mut record = logger.create_log_record();
+        record.set_body("hello".into());
+        logger.emit(record);
+        let _ = provider.shutdown();
+
+        let data = fs::read(&path).unwrap();
+        let len = u32::from_be_bytes(data[..4].try_into().unwrap()) as usize;
+        assert_eq!(data.len(), 4 + len);
+        let request = ExportLogsServiceRequest::decode(&data[4..]).unwrap();
+        let records = &request.resource_logs[0].scope_logs[0].log_records;
+        assert_eq!(records.len(), 1);
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("traces.jsonl");
+        let exporter = SpanExporter::builder()
+            .with_file()
+            .with_path(&path)
+            .with_max_file_size(1)
+            .with_max_files(2)
+            .build()
+            .unwrap();
+
+        emit_spans(exporter, &["a", "b", "c", "d", "e"]);
+
+        let names = file_names(dir.path());
+        assert_eq!(names.len(), 3, "{names:?}");
+        assert!(names.contains(&"traces.jsonl".to_string()));
+        assert!(names
+            .iter()
+            .filter(|name| name.as_str() != "traces.jsonl")
+            .all(|name| name.starts_with("traces-") && name.ends_with(".jsonl")));
+        // the current file only holds the last request
+        let current = fs::read_to_string(&path).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert!(current.contains(r#""name":"e""#));
+    }
+
+    #[test]
+    fn rejects_unsupported_compression() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = SpanExporter::builder()
+            .with_file()
+            .with_path(dir.path().join("traces.jsonl"))
+            .with_compression(Compression::Zstd)
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "gzip-file")]
+    #[test]
+    fn compresses_rolled_files() {
+        use std::io::Read;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("traces.jsonl");
+        let exporter = SpanExporter::builder()
+            .with_file()
+            .with_path(&path)
+            .with_max_file_size(1)
+            .with_compression(Compression::Gzip)
+            .build()
+            .unwrap();
+
+        emit_spans(exporter, &["a", "b"]);
+
+        let rolled: Vec<String> = file_names(dir.path())
+            .into_iter()
+            .filter(|name| name.as_str() != "traces.jsonl")
+            .collect();
+        assert_eq!(rolled.len(), 1);
+        assert!(rolled[0].ends_with(".jsonl.gz"));
+
+        let mut contents = String::new();
+        flate2::read::GzDecoder::new(fs::File::open(dir.path().join(&rolled[0])).unwrap())
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert!(contents.contains(r#""name":"a""#));
+    }
+}
@@ -0,0 +1,170 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use super::FileConfig;
+
+/// Appends to the file at `config.path`, rolling it over to a timestamped
+/// file when it is too large or too old.
+#[derive(Debug)]
+pub(crate) struct RotatingFile {
+    config: FileConfig,
+    path: PathBuf,
+    file: File,
+    size: u64,
+    opened_at: Instant,
+}
+
+impl RotatingFile {
+    pub(crate) fn open(path: PathBuf, config: FileConfig) -> io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFile {
+            config,
+            path,
+            file,
+            size,
+            opened_at: Instant::now(),
+        })
+    }
+
+    /// Writes `data` at once, rolling the file over first if `data` would
+    /// make it exceed the size limit, or if it is older than the rotation
+    /// interval.
+    pub(crate) fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        let too_large = self
+            .config
+            .max_file_size
+            .is_some_and(|max| self.size > 0 && self.size + data.len() as u64 > max);
+        let too_old = self
+            .config
+            .rotation_interval
+            .is_some_and(|interval| self.size > 0 && self.opened_at.elapsed() >= interval);
+        if too_large || too_old {
+            self.rotate()?;
+        }
+
+        self.file.write_all(data)?;
+        self.size += data.len() as u64;
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file.sync_data()
+    }
+
+    /// Renames the current file to `<stem>-<unix millis>.<extension>`,
+    /// compresses it if configured, removes the rolled files exceeding the
+    /// retention count and starts a new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.flush()?;
+        let rolled = self.rolled_path();
+        fs::rename(&self.path, &rolled)?;
+
+        if let Some(compression) = self.config.compression {
+            compress(&rolled, compression)?;
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.opened_at = Instant::now();
+
+        if let Some(max_files) = self.config.max_files {
+            self.remove_old_files(max_files)?;
+        }
+        Ok(())
+    }
+
+    fn rolled_path(&self) -> PathBuf {
+        let (stem, extension) = split_file_name(&self.path);
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut suffix = String::new();
+        let mut attempt = 0;
+        loop {
+            let rolled = self
+                .path
+                .with_file_name(format!("{stem}-{millis}{suffix}{extension}"));
+            let compressed = PathBuf::from(format!("{}.gz", rolled.display()));
+            if !rolled.exists() && !compressed.exists() {
+                return rolled;
+            }
+            attempt += 1;
+            suffix = format!("-{attempt}");
+        }
+    }
+
+    /// Removes the oldest rolled files so that at most `max_files` remain.
+    fn remove_old_files(&self, max_files: usize) -> io::Result<()> {
+        let (stem, extension) = split_file_name(&self.path);
+        let prefix = format!("{stem}-");
+        let dir = match self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            Some(dir) => dir.to_path_buf(),
+            None => PathBuf::from("."),
+        };
+
+        let mut rolled: Vec<(u128, PathBuf)> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let rest = name.strip_prefix(&prefix)?;
+                let rest = rest.strip_suffix(".gz").unwrap_or(rest);
+                let rest = rest.strip_suffix(extension.as_str())?;
+                // `<unix millis>` optionally followed by `-<attempt>`
+                let millis = rest.split('-').next()?.parse().ok()?;
+                Some((millis, entry.path()))
+            })
+            .collect();
+        if rolled.len() <= max_files {
+            return Ok(());
+        }
+
+        rolled.sort();
+        let excess = rolled.len() - max_files;
+        for (_, path) in rolled.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits `traces.jsonl` into `traces` and `.jsonl`.
+fn split_file_name(path: &Path) -> (String, String) {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (stem, extension)
+}
+
+#[cfg(feature = "gzip-file")]
+fn compress(path: &Path, compression: crate::Compression) -> io::Result<()> {
+    use flate2::{write::GzEncoder, Compression as Level};
+
+    debug_assert_eq!(compression, crate::Compression::Gzip);
+    let compressed = PathBuf::from(format!("{}.gz", path.display()));
+    let mut encoder = GzEncoder::new(File::create(&compressed)?, Level::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    fs::remove_file(path)
+}
+
+#[cfg(not(feature = "gzip-file"))]
+fn compress(_path: &Path, _compression: crate::Compression) -> io::Result<()> {
+    // rejected when building the exporter
+    Ok(())
+}
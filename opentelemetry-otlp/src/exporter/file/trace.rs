@@ -0,0 +1,28 @@
+use futures_core::future::BoxFuture;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+
+use super::OtlpFileClient;
+
+impl SpanExporter for OtlpFileClient {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        let resource_spans = group_spans_by_resource_and_scope(batch, &self.resource);
+        let result = self
+            .write(&ExportTraceServiceRequest { resource_spans })
+            .map_err(Into::into);
+        Box::pin(std::future::ready(result))
+    }
+
+    fn shutdown(&mut self) {
+        let _ = OtlpFileClient::shutdown(self);
+    }
+
+    fn force_flush(&mut self) -> BoxFuture<'static, ExportResult> {
+        Box::pin(std::future::ready(self.flush().map_err(Into::into)))
+    }
+
+    fn set_resource(&mut self, resource: &opentelemetry_sdk::Resource) {
+        self.resource = resource.into();
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use http::{header::CONTENT_TYPE, Method};
@@ -47,6 +48,15 @@ impl LogExporter for OtlpHttpClient {
         Ok(())
     }
 
+    fn force_flush(&mut self, _timeout: Duration) -> LogResult<()> {
+        // records are sent on export, there is nothing buffered to flush
+        match self.client.lock() {
+            Ok(client) if client.is_some() => Ok(()),
+            Ok(_) => Err(LogError::Other("exporter is already shut down".into())),
+            Err(e) => Err(LogError::Other(e.to_string().into())),
+        }
+    }
+
     fn shutdown(&mut self) {
         let _ = self.client.lock().map(|mut c| c.take());
     }
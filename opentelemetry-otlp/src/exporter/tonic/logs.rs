@@ -5,6 +5,7 @@ use opentelemetry_proto::tonic::collector::logs::v1::{
 };
 use opentelemetry_sdk::export::logs::{LogBatch, LogExporter};
 use opentelemetry_sdk::logs::{LogError, LogResult};
+use std::time::Duration;
 use tonic::{codegen::CompressionEncoding, service::Interceptor, transport::Channel, Request};
 
 use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
@@ -81,6 +82,14 @@ impl LogExporter for TonicLogsClient {
         Ok(())
     }
 
+    fn force_flush(&mut self, _timeout: Duration) -> LogResult<()> {
+        // records are sent on export, there is nothing buffered to flush
+        match &self.inner {
+            Some(_) => Ok(()),
+            None => Err(LogError::Other("exporter is already shut down".into())),
+        }
+    }
+
     fn shutdown(&mut self) {
         let _ = self.inner.take();
     }
@@ -99,7 +99,7 @@
 //! The following feature flags generate additional code and types:
 //! * `serialize`: Enables serialization support for type defined in this create via `serde`.
 //! * `populate-logs-event-name`: Enables sending `LogRecord::event_name` as an attribute
-//!    with the key `name`
+//!   with the key `name`
 //!
 //! The following feature flags offer additional configurations on gRPC:
 //!
@@ -118,6 +118,14 @@
 //! * `reqwest-rustls`: Use reqwest with TLS with system trust roots via `rustls-native-certs` crate.
 //! * `reqwest-rustls-webkpi-roots`: Use reqwest with TLS with Mozilla's trust roots via `webkpi-roots` crate.
 //!
+//! The following feature flags enable writing to rotating files instead, e.g. to upload them in bulk
+//! from air-gapped environments:
+//!
+//! * `file`: Write OTLP JSON lines, readable by the collector's `otlpjsonfile` receiver, or
+//!   length-delimited protobuf to files rotated by size and age. See [FileExporterBuilder].
+//!   Enables the `trace`, `metrics` and `logs` features.
+//! * `gzip-file`: Use gzip compression for the rolled files.
+//!
 //! # Kitchen Sink Full Configuration
 //!
 //! Example showing how to override all configuration options.
@@ -219,33 +227,63 @@
 
 mod exporter;
 #[cfg(feature = "logs")]
-#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+#[cfg(any(
+    feature = "http-proto",
+    feature = "http-json",
+    feature = "grpc-tonic",
+    feature = "file"
+))]
 mod logs;
 #[cfg(feature = "metrics")]
-#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+#[cfg(any(
+    feature = "http-proto",
+    feature = "http-json",
+    feature = "grpc-tonic",
+    feature = "file"
+))]
 mod metric;
 #[cfg(feature = "trace")]
-#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+#[cfg(any(
+    feature = "http-proto",
+    feature = "http-json",
+    feature = "grpc-tonic",
+    feature = "file"
+))]
 mod span;
 
 pub use crate::exporter::Compression;
 pub use crate::exporter::ExportConfig;
 #[cfg(feature = "trace")]
-#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+#[cfg(any(
+    feature = "http-proto",
+    feature = "http-json",
+    feature = "grpc-tonic",
+    feature = "file"
+))]
 pub use crate::span::{
     SpanExporter, OTEL_EXPORTER_OTLP_TRACES_COMPRESSION, OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
     OTEL_EXPORTER_OTLP_TRACES_HEADERS, OTEL_EXPORTER_OTLP_TRACES_TIMEOUT,
 };
 
 #[cfg(feature = "metrics")]
-#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+#[cfg(any(
+    feature = "http-proto",
+    feature = "http-json",
+    feature = "grpc-tonic",
+    feature = "file"
+))]
 pub use crate::metric::{
     MetricExporter, OTEL_EXPORTER_OTLP_METRICS_COMPRESSION, OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
     OTEL_EXPORTER_OTLP_METRICS_HEADERS, OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
 };
 
 #[cfg(feature = "logs")]
-#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+#[cfg(any(
+    feature = "http-proto",
+    feature = "http-json",
+    feature = "grpc-tonic",
+    feature = "file"
+))]
 pub use crate::logs::{
     LogExporter, OTEL_EXPORTER_OTLP_LOGS_COMPRESSION, OTEL_EXPORTER_OTLP_LOGS_ENDPOINT,
     OTEL_EXPORTER_OTLP_LOGS_HEADERS, OTEL_EXPORTER_OTLP_LOGS_TIMEOUT,
@@ -257,6 +295,11 @@ pub use crate::exporter::http::{HasHttpConfig, WithHttpConfig};
 #[cfg(feature = "grpc-tonic")]
 pub use crate::exporter::tonic::{HasTonicConfig, WithTonicConfig};
 
+#[cfg(feature = "file")]
+pub use crate::exporter::file::{
+    FileConfig, FileExporterBuilder, FileFormat, HasFileConfig, WithFileConfig,
+};
+
 pub use crate::exporter::{
     HasExportConfig, WithExportConfig, OTEL_EXPORTER_OTLP_COMPRESSION, OTEL_EXPORTER_OTLP_ENDPOINT,
     OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT, OTEL_EXPORTER_OTLP_HEADERS, OTEL_EXPORTER_OTLP_PROTOCOL,
@@ -284,6 +327,13 @@ pub struct TonicExporterBuilderSet(TonicExporterBuilder);
 #[derive(Debug, Default)]
 pub struct HttpExporterBuilderSet(HttpExporterBuilder);
 
+/// Type to hold the [FileExporterBuilder] and indicate it has been set.
+///
+/// Allowing access to [FileExporterBuilder] specific configuration methods.
+#[cfg(feature = "file")]
+#[derive(Debug, Default)]
+pub struct FileExporterBuilderSet(FileExporterBuilder);
+
 #[cfg(any(feature = "http-proto", feature = "http-json"))]
 pub use crate::exporter::http::HttpExporterBuilder;
 
@@ -341,22 +391,42 @@ pub enum Error {
     /// Prost encode failed
     #[cfg(any(
         feature = "http-proto",
+        feature = "file",
         all(feature = "http-json", not(feature = "trace"))
     ))]
     #[error("prost encoding error {0}")]
     EncodeError(#[from] prost::EncodeError),
 
     /// The lock in exporters has been poisoned.
-    #[cfg(feature = "metrics")]
+    #[cfg(any(feature = "metrics", feature = "file"))]
     #[error("the lock of the {0} has been poisoned")]
     PoisonedLock(&'static str),
 
+    /// Writing to the exporter's file failed.
+    #[cfg(feature = "file")]
+    #[error("file error {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON encoding failed.
+    #[cfg(feature = "file")]
+    #[error("json encoding error {0}")]
+    JsonEncodeError(#[from] serde_json::Error),
+
+    /// The exporter has already been shut down.
+    #[cfg(feature = "file")]
+    #[error("exporter is already shut down")]
+    AlreadyShutdown,
+
     /// Unsupported compression algorithm.
     #[error("unsupported compression algorithm '{0}'")]
     UnsupportedCompressionAlgorithm(String),
 
     /// Feature required to use the specified compression algorithm.
-    #[cfg(any(not(feature = "gzip-tonic"), not(feature = "zstd-tonic")))]
+    #[cfg(any(
+        not(feature = "gzip-tonic"),
+        not(feature = "zstd-tonic"),
+        all(feature = "file", not(feature = "gzip-file"))
+    ))]
     #[error("feature '{0}' is required to use the compression algorithm '{1}'")]
     FeatureRequiredForCompressionAlgorithm(&'static str, Compression),
 }
@@ -4,12 +4,18 @@
 
 use async_trait::async_trait;
 use std::fmt::Debug;
+use std::time::Duration;
 
 use opentelemetry_sdk::logs::LogResult;
 
 use opentelemetry_sdk::export::logs::LogBatch;
 
-use crate::{HasExportConfig, NoExporterBuilderSet};
+#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+use crate::HasExportConfig;
+use crate::NoExporterBuilderSet;
+
+#[cfg(feature = "file")]
+use crate::{FileExporterBuilder, FileExporterBuilderSet, HasFileConfig};
 
 #[cfg(feature = "grpc-tonic")]
 use crate::{HasTonicConfig, TonicExporterBuilder, TonicExporterBuilderSet};
@@ -58,6 +64,14 @@ impl LogExporterBuilder<NoExporterBuilderSet> {
             endpoint: self.endpoint,
         }
     }
+
+    #[cfg(feature = "file")]
+    pub fn with_file(self) -> LogExporterBuilder<FileExporterBuilderSet> {
+        LogExporterBuilder {
+            client: FileExporterBuilderSet(FileExporterBuilder::default()),
+            endpoint: self.endpoint,
+        }
+    }
 }
 
 #[cfg(feature = "grpc-tonic")]
@@ -74,6 +88,13 @@ impl LogExporterBuilder<HttpExporterBuilderSet> {
     }
 }
 
+#[cfg(feature = "file")]
+impl LogExporterBuilder<FileExporterBuilderSet> {
+    pub fn build(self) -> Result<LogExporter, opentelemetry_sdk::logs::LogError> {
+        self.client.0.build_log_exporter()
+    }
+}
+
 #[cfg(feature = "grpc-tonic")]
 impl HasExportConfig for LogExporterBuilder<TonicExporterBuilderSet> {
     fn export_config(&mut self) -> &mut crate::ExportConfig {
@@ -102,6 +123,13 @@ impl HasHttpConfig for LogExporterBuilder<HttpExporterBuilderSet> {
     }
 }
 
+#[cfg(feature = "file")]
+impl HasFileConfig for LogExporterBuilder<FileExporterBuilderSet> {
+    fn file_config(&mut self) -> &mut crate::FileConfig {
+        &mut self.client.0.file_config
+    }
+}
+
 /// OTLP exporter that sends log data
 #[derive(Debug)]
 pub struct LogExporter {
@@ -128,6 +156,14 @@ impl opentelemetry_sdk::export::logs::LogExporter for LogExporter {
         self.client.export(batch).await
     }
 
+    fn force_flush(&mut self, timeout: Duration) -> LogResult<()> {
+        self.client.force_flush(timeout)
+    }
+
+    fn shutdown(&mut self) {
+        self.client.shutdown();
+    }
+
     fn set_resource(&mut self, resource: &opentelemetry_sdk::Resource) {
         self.client.set_resource(resource);
     }
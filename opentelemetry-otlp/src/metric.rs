@@ -12,6 +12,9 @@ use crate::{exporter::http::HttpExporterBuilder, HasHttpConfig, HttpExporterBuil
 #[cfg(feature = "grpc-tonic")]
 use crate::{exporter::tonic::TonicExporterBuilder, HasTonicConfig, TonicExporterBuilderSet};
 
+#[cfg(feature = "file")]
+use crate::{exporter::file::FileExporterBuilder, FileExporterBuilderSet, HasFileConfig};
+
 use crate::NoExporterBuilderSet;
 
 use async_trait::async_trait;
@@ -66,6 +69,14 @@ impl<C> MetricExporterBuilder<C> {
         }
     }
 
+    #[cfg(feature = "file")]
+    pub fn with_file(self) -> MetricExporterBuilder<FileExporterBuilderSet> {
+        MetricExporterBuilder {
+            client: FileExporterBuilderSet(FileExporterBuilder::default()),
+            temporality: self.temporality,
+        }
+    }
+
     pub fn with_temporality(self, temporality: Temporality) -> MetricExporterBuilder<C> {
         MetricExporterBuilder {
             client: self.client,
@@ -90,6 +101,13 @@ impl MetricExporterBuilder<HttpExporterBuilderSet> {
     }
 }
 
+#[cfg(feature = "file")]
+impl MetricExporterBuilder<FileExporterBuilderSet> {
+    pub fn build(self) -> MetricResult<MetricExporter> {
+        self.client.0.build_metrics_exporter(self.temporality)
+    }
+}
+
 #[cfg(feature = "grpc-tonic")]
 impl HasExportConfig for MetricExporterBuilder<TonicExporterBuilderSet> {
     fn export_config(&mut self) -> &mut crate::ExportConfig {
@@ -118,6 +136,13 @@ impl HasHttpConfig for MetricExporterBuilder<HttpExporterBuilderSet> {
     }
 }
 
+#[cfg(feature = "file")]
+impl HasFileConfig for MetricExporterBuilder<FileExporterBuilderSet> {
+    fn file_config(&mut self) -> &mut crate::FileConfig {
+        &mut self.client.0.file_config
+    }
+}
+
 /// An interface for OTLP metrics clients
 #[async_trait]
 pub trait MetricsClient: fmt::Debug + Send + Sync + 'static {
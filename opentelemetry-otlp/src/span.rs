@@ -19,7 +19,15 @@ use crate::{
     HttpExporterBuilderSet,
 };
 
-use crate::{exporter::HasExportConfig, NoExporterBuilderSet};
+#[cfg(feature = "file")]
+use crate::{
+    exporter::file::{FileExporterBuilder, HasFileConfig},
+    FileExporterBuilderSet,
+};
+
+#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+use crate::exporter::HasExportConfig;
+use crate::NoExporterBuilderSet;
 
 /// Target to which the exporter is going to send spans, defaults to https://localhost:4317/v1/traces.
 /// Learn about the relationship between this constant and default/metrics/logs at
@@ -58,6 +66,13 @@ impl SpanExporterBuilder<NoExporterBuilderSet> {
             client: HttpExporterBuilderSet(HttpExporterBuilder::default()),
         }
     }
+
+    #[cfg(feature = "file")]
+    pub fn with_file(self) -> SpanExporterBuilder<FileExporterBuilderSet> {
+        SpanExporterBuilder {
+            client: FileExporterBuilderSet(FileExporterBuilder::default()),
+        }
+    }
 }
 
 #[cfg(feature = "grpc-tonic")]
@@ -76,6 +91,13 @@ impl SpanExporterBuilder<HttpExporterBuilderSet> {
     }
 }
 
+#[cfg(feature = "file")]
+impl SpanExporterBuilder<FileExporterBuilderSet> {
+    pub fn build(self) -> Result<SpanExporter, opentelemetry::trace::TraceError> {
+        self.client.0.build_span_exporter()
+    }
+}
+
 #[cfg(feature = "grpc-tonic")]
 impl HasExportConfig for SpanExporterBuilder<TonicExporterBuilderSet> {
     fn export_config(&mut self) -> &mut crate::ExportConfig {
@@ -104,6 +126,13 @@ impl HasHttpConfig for SpanExporterBuilder<HttpExporterBuilderSet> {
     }
 }
 
+#[cfg(feature = "file")]
+impl HasFileConfig for SpanExporterBuilder<FileExporterBuilderSet> {
+    fn file_config(&mut self) -> &mut crate::FileConfig {
+        &mut self.client.0.file_config
+    }
+}
+
 /// OTLP exporter that sends tracing information
 #[derive(Debug)]
 pub struct SpanExporter(Box<dyn opentelemetry_sdk::export::trace::SpanExporter>);
@@ -125,6 +154,14 @@ impl opentelemetry_sdk::export::trace::SpanExporter for SpanExporter {
         self.0.export(batch)
     }
 
+    fn shutdown(&mut self) {
+        self.0.shutdown();
+    }
+
+    fn force_flush(&mut self) -> BoxFuture<'static, ExportResult> {
+        self.0.force_flush()
+    }
+
     fn set_resource(&mut self, resource: &opentelemetry_sdk::Resource) {
         self.0.set_resource(resource);
     }
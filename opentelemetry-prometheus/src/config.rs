@@ -1,11 +1,16 @@
 use core::fmt;
 use once_cell::sync::OnceCell;
 use opentelemetry::metrics::{MetricsError, Result};
-use opentelemetry_sdk::metrics::ManualReaderBuilder;
+use opentelemetry_sdk::metrics::{ManualReader, ManualReaderBuilder};
 use std::sync::{Arc, Mutex};
 
 use crate::{Collector, PrometheusExporter, ResourceSelector};
 
+#[cfg(feature = "remote-write")]
+use crate::RemoteWriteExporterBuilder;
+#[cfg(feature = "prometheus-encoding")]
+use crate::TextEncoder;
+
 /// [PrometheusExporter] configuration options
 #[derive(Default)]
 pub struct ExporterBuilder {
@@ -13,6 +18,7 @@ pub struct ExporterBuilder {
     disable_target_info: bool,
     without_units: bool,
     without_counter_suffixes: bool,
+    without_exponential_histogram_fallback: bool,
     namespace: Option<String>,
     disable_scope_info: bool,
     reader: ManualReaderBuilder,
@@ -26,6 +32,10 @@ impl fmt::Debug for ExporterBuilder {
             .field("disable_target_info", &self.disable_target_info)
             .field("without_units", &self.without_units)
             .field("without_counter_suffixes", &self.without_counter_suffixes)
+            .field(
+                "without_exponential_histogram_fallback",
+                &self.without_exponential_histogram_fallback,
+            )
             .field("namespace", &self.namespace)
             .field("disable_scope_info", &self.disable_scope_info)
             .finish()
@@ -56,6 +66,20 @@ impl ExporterBuilder {
         self
     }
 
+    /// Disables the conversion of exponential histograms to classic buckets.
+    ///
+    /// Exponential histograms are exported as Prometheus native histograms,
+    /// which are only part of the protobuf exposition format. By default, their
+    /// buckets are also converted to classic histogram buckets with the same
+    /// boundaries so that text format scrapes keep the bucket distribution.
+    ///
+    /// With this option set, text format scrapes only contain the `_sum`,
+    /// `_count` and `+Inf` bucket series of exponential histograms.
+    pub fn without_exponential_histogram_fallback(mut self) -> Self {
+        self.without_exponential_histogram_fallback = true;
+        self
+    }
+
     /// Configures the exporter to not export the resource `target_info` metric.
     ///
     /// If not specified, the exporter will create a `target_info` metric containing
@@ -94,9 +118,12 @@ impl ExporterBuilder {
         self
     }
 
-    /// Configures which [prometheus::Registry] the exporter will use.
+    /// Configures the [prometheus::Registry] the exporter registers its
+    /// collector on.
     ///
-    /// If no registry is specified, the prometheus default is used.
+    /// If no registry is specified, none is used, and the metrics are only
+    /// read through the encoder of [PrometheusExporter::text_encoder] with the
+    /// `prometheus-encoding` feature.
     pub fn with_registry(mut self, registry: prometheus::Registry) -> Self {
         self.registry = Some(registry);
         self
@@ -116,27 +143,55 @@ impl ExporterBuilder {
     }
 
     /// Creates a new [PrometheusExporter] from this configuration.
-    pub fn build(self) -> Result<PrometheusExporter> {
-        let reader = Arc::new(self.reader.build());
+    pub fn build(mut self) -> Result<PrometheusExporter> {
+        let reader = Arc::new(std::mem::take(&mut self.reader).build());
+
+        #[cfg(feature = "prometheus-encoding")]
+        let encoder = TextEncoder::new(self.collector(&reader));
+
+        if let Some(registry) = self.registry.take() {
+            registry
+                .register(Box::new(self.collector(&reader)))
+                .map_err(|e| MetricsError::Other(e.to_string()))?;
+        }
+
+        Ok(PrometheusExporter {
+            reader,
+            #[cfg(feature = "prometheus-encoding")]
+            encoder,
+        })
+    }
+
+    /// Creates a builder for a [RemoteWriteExporter] pushing metrics to
+    /// `endpoint`, named and labeled according to this configuration.
+    ///
+    /// The registry set with [ExporterBuilder::with_registry] is not used.
+    ///
+    /// [RemoteWriteExporter]: crate::RemoteWriteExporter
+    #[cfg(feature = "remote-write")]
+    pub fn remote_write(self, endpoint: impl Into<String>) -> RemoteWriteExporterBuilder {
+        RemoteWriteExporterBuilder::new(self, endpoint.into())
+    }
 
-        let collector = Collector {
-            reader: Arc::clone(&reader),
+    #[cfg(feature = "remote-write")]
+    pub(crate) fn build_collector(mut self) -> Collector {
+        let reader = Arc::new(std::mem::take(&mut self.reader).build());
+        self.collector(&reader)
+    }
+
+    fn collector(&self, reader: &Arc<ManualReader>) -> Collector {
+        Collector {
+            reader: Arc::clone(reader),
             disable_target_info: self.disable_target_info,
             without_units: self.without_units,
             without_counter_suffixes: self.without_counter_suffixes,
+            without_exponential_histogram_fallback: self.without_exponential_histogram_fallback,
             disable_scope_info: self.disable_scope_info,
             create_target_info_once: OnceCell::new(),
-            namespace: self.namespace,
+            namespace: self.namespace.clone(),
             inner: Mutex::new(Default::default()),
-            resource_selector: self.resource_selector,
+            resource_selector: self.resource_selector.clone(),
             resource_labels_once: OnceCell::new(),
-        };
-
-        let registry = self.registry.unwrap_or_default();
-        registry
-            .register(Box::new(collector))
-            .map_err(|e| MetricsError::Other(e.to_string()))?;
-
-        Ok(PrometheusExporter { reader })
+        }
     }
 }
@@ -0,0 +1,412 @@
+//! Groups collected metrics into Prometheus metric families.
+use std::{
+    borrow::Cow,
+    collections::{btree_map::Entry, BTreeMap},
+    time::SystemTime,
+};
+
+use opentelemetry::{global, metrics::MetricsError, KeyValue};
+use opentelemetry_sdk::{
+    metrics::data::{self, ResourceMetrics},
+    Scope,
+};
+use prometheus::proto::MetricType;
+
+use crate::{
+    native_histogram, sanitized_attrs, utils, Collector, Numeric, SCOPE_INFO_DESCRIPTION,
+    SCOPE_INFO_KEYS, SCOPE_INFO_METRIC_NAME, TARGET_INFO_DESCRIPTION, TARGET_INFO_NAME,
+};
+
+// OpenMetrics limits the combined length of the exemplar label names and values.
+const MAX_EXEMPLAR_LABELS_LENGTH: usize = 128;
+
+pub(crate) type Labels = Vec<(String, String)>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FamilyKind {
+    Counter,
+    Gauge,
+    Histogram,
+    Info,
+}
+
+/// The series of all metrics that share a Prometheus metric name.
+#[derive(Debug)]
+pub(crate) struct Family {
+    pub(crate) kind: FamilyKind,
+    pub(crate) help: String,
+    /// The unit suffix of the metric name, if one was added.
+    pub(crate) unit: Option<Cow<'static, str>>,
+    /// The series of the family, sorted by their labels.
+    pub(crate) series: Vec<Series>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Series {
+    pub(crate) labels: Labels,
+    pub(crate) created: Option<SystemTime>,
+    pub(crate) value: SeriesValue,
+}
+
+#[derive(Debug)]
+pub(crate) enum SeriesValue {
+    Counter {
+        value: f64,
+        exemplar: Option<Exemplar>,
+    },
+    Gauge(f64),
+    Histogram {
+        /// Cumulative buckets, the last one has an infinite upper bound.
+        buckets: Vec<Bucket>,
+        sum: f64,
+        count: u64,
+    },
+    Info,
+}
+
+#[derive(Debug)]
+pub(crate) struct Bucket {
+    pub(crate) upper_bound: f64,
+    pub(crate) cumulative_count: u64,
+    pub(crate) exemplar: Option<Exemplar>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Exemplar {
+    pub(crate) labels: Labels,
+    pub(crate) value: f64,
+    pub(crate) time: SystemTime,
+}
+
+/// Groups the collected metrics into families keyed by their Prometheus name.
+///
+/// Naming, label and info metric conventions follow the registry based
+/// [Collector], including the handling of conflicting metric definitions.
+pub(crate) fn collect_families(
+    collector: &Collector,
+    metrics: &ResourceMetrics,
+) -> BTreeMap<String, Family> {
+    let mut families = BTreeMap::new();
+
+    if !collector.disable_target_info && !metrics.resource.is_empty() {
+        let series = Series {
+            labels: sanitized_attrs(&mut metrics.resource.iter()),
+            created: None,
+            value: SeriesValue::Info,
+        };
+        add_family(
+            &mut families,
+            TARGET_INFO_NAME.into(),
+            FamilyKind::Info,
+            TARGET_INFO_DESCRIPTION,
+            None,
+            vec![series],
+        );
+    }
+
+    let resource_labels: Labels = collector
+        .resource_selector
+        .select(&metrics.resource)
+        .iter()
+        .map(|lp| (lp.get_name().to_string(), lp.get_value().to_string()))
+        .collect();
+
+    for scope_metrics in &metrics.scope_metrics {
+        let scope_labels = if !collector.disable_scope_info {
+            let mut labels = scope_info_labels(&scope_metrics.scope);
+            if !scope_metrics.scope.attributes.is_empty() {
+                let series = Series {
+                    labels: labels.clone(),
+                    created: None,
+                    value: SeriesValue::Info,
+                };
+                add_family(
+                    &mut families,
+                    SCOPE_INFO_METRIC_NAME.into(),
+                    FamilyKind::Info,
+                    SCOPE_INFO_DESCRIPTION,
+                    None,
+                    vec![series],
+                );
+            }
+
+            labels.extend(resource_labels.iter().cloned());
+            labels
+        } else {
+            Vec::new()
+        };
+
+        for metric in &scope_metrics.metrics {
+            let (metric_type, name) = match collector.metric_type_and_name(metric) {
+                Some((metric_type, name)) => (metric_type, name),
+                _ => continue,
+            };
+            let kind = match metric_type {
+                MetricType::COUNTER => FamilyKind::Counter,
+                MetricType::HISTOGRAM => FamilyKind::Histogram,
+                _ => FamilyKind::Gauge,
+            };
+            let unit = if collector.without_units {
+                None
+            } else {
+                utils::get_unit_suffixes(&metric.unit)
+            };
+
+            let fallback = !collector.without_exponential_histogram_fallback;
+            let data = metric.data.as_any();
+            let series = if let Some(hist) = data.downcast_ref::<data::Histogram<i64>>() {
+                histogram_series(hist, &scope_labels)
+            } else if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
+                histogram_series(hist, &scope_labels)
+            } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
+                histogram_series(hist, &scope_labels)
+            } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<i64>>() {
+                exponential_histogram_series(hist, &scope_labels, fallback)
+            } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
+                exponential_histogram_series(hist, &scope_labels, fallback)
+            } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
+                exponential_histogram_series(hist, &scope_labels, fallback)
+            } else if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
+                sum_series(sum, &scope_labels)
+            } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
+                sum_series(sum, &scope_labels)
+            } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
+                sum_series(sum, &scope_labels)
+            } else if let Some(g) = data.downcast_ref::<data::Gauge<u64>>() {
+                gauge_series(g, &scope_labels)
+            } else if let Some(g) = data.downcast_ref::<data::Gauge<i64>>() {
+                gauge_series(g, &scope_labels)
+            } else if let Some(g) = data.downcast_ref::<data::Gauge<f64>>() {
+                gauge_series(g, &scope_labels)
+            } else {
+                continue;
+            };
+
+            add_family(
+                &mut families,
+                name.into_owned(),
+                kind,
+                &metric.description,
+                unit,
+                series,
+            );
+        }
+    }
+
+    for family in families.values_mut() {
+        family.series.sort_by(|a, b| a.labels.cmp(&b.labels));
+    }
+
+    families
+}
+
+fn add_family(
+    families: &mut BTreeMap<String, Family>,
+    name: String,
+    kind: FamilyKind,
+    help: &str,
+    unit: Option<Cow<'static, str>>,
+    series: Vec<Series>,
+) {
+    match families.entry(name) {
+        Entry::Occupied(mut entry) => {
+            let name = entry.key().clone();
+            let family = entry.get_mut();
+            if family.kind != kind {
+                global::handle_error(MetricsError::Other(format!("Instrument type conflict, using existing type definition. Instrument {name}, Existing: {:?}, dropped: {:?}", family.kind, kind)));
+                return;
+            }
+            if family.help != help {
+                global::handle_error(MetricsError::Other(format!("Instrument description conflict, using existing. Instrument {name}, Existing: {:?}, dropped: {:?}", family.help, help)));
+            }
+
+            for s in series {
+                // info metrics are reported once per scope and may repeat
+                if kind == FamilyKind::Info && family.series.iter().any(|e| e.labels == s.labels) {
+                    continue;
+                }
+                family.series.push(s);
+            }
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(Family {
+                kind,
+                help: help.to_string(),
+                unit,
+                series,
+            });
+        }
+    }
+}
+
+fn scope_info_labels(scope: &Scope) -> Labels {
+    let mut labels = Vec::with_capacity(1 + scope.version.is_some() as usize);
+    labels.push((SCOPE_INFO_KEYS[0].to_string(), scope.name.to_string()));
+    if let Some(version) = &scope.version {
+        labels.push((SCOPE_INFO_KEYS[1].to_string(), version.to_string()));
+    }
+    labels
+}
+
+fn series_labels(attributes: &[KeyValue], extra: &Labels) -> Labels {
+    let mut labels = sanitized_attrs(&mut attributes.iter().map(|kv| (&kv.key, &kv.value)));
+    labels.extend(extra.iter().cloned());
+    labels
+}
+
+fn sum_series<T: Numeric>(sum: &data::Sum<T>, extra: &Labels) -> Vec<Series> {
+    sum.data_points
+        .iter()
+        .map(|dp| {
+            let value = if sum.is_monotonic {
+                SeriesValue::Counter {
+                    value: dp.value.as_f64(),
+                    exemplar: dp.exemplars.iter().max_by_key(|e| e.time).map(exemplar),
+                }
+            } else {
+                SeriesValue::Gauge(dp.value.as_f64())
+            };
+
+            Series {
+                labels: series_labels(&dp.attributes, extra),
+                created: dp.start_time.filter(|_| sum.is_monotonic),
+                value,
+            }
+        })
+        .collect()
+}
+
+fn gauge_series<T: Numeric>(gauge: &data::Gauge<T>, extra: &Labels) -> Vec<Series> {
+    gauge
+        .data_points
+        .iter()
+        .map(|dp| Series {
+            labels: series_labels(&dp.attributes, extra),
+            created: None,
+            value: SeriesValue::Gauge(dp.value.as_f64()),
+        })
+        .collect()
+}
+
+fn histogram_series<T: Numeric>(histogram: &data::Histogram<T>, extra: &Labels) -> Vec<Series> {
+    histogram
+        .data_points
+        .iter()
+        .map(|dp| {
+            let mut count = 0;
+            let bounds = dp.bounds.iter().copied().chain(Some(f64::INFINITY));
+            let buckets = bounds
+                .zip(&dp.bucket_counts)
+                .map(|(upper_bound, bucket_count)| {
+                    count += bucket_count;
+                    Bucket {
+                        upper_bound,
+                        cumulative_count: count,
+                        exemplar: None,
+                    }
+                })
+                .collect();
+
+            Series {
+                labels: series_labels(&dp.attributes, extra),
+                created: Some(dp.start_time),
+                value: histogram_value(buckets, &dp.exemplars, dp.sum.as_f64(), dp.count),
+            }
+        })
+        .collect()
+}
+
+fn exponential_histogram_series<T: Numeric>(
+    histogram: &data::ExponentialHistogram<T>,
+    extra: &Labels,
+    classic_fallback: bool,
+) -> Vec<Series> {
+    histogram
+        .data_points
+        .iter()
+        .map(|dp| {
+            let mut buckets = Vec::new();
+            if classic_fallback {
+                buckets.extend(
+                    native_histogram::classic_buckets(dp)
+                        .iter()
+                        .map(|b| Bucket {
+                            upper_bound: b.get_upper_bound(),
+                            cumulative_count: b.get_cumulative_count(),
+                            exemplar: None,
+                        }),
+                );
+            }
+            buckets.push(Bucket {
+                upper_bound: f64::INFINITY,
+                cumulative_count: dp.count as u64,
+                exemplar: None,
+            });
+
+            Series {
+                labels: series_labels(&dp.attributes, extra),
+                created: Some(dp.start_time),
+                value: histogram_value(buckets, &dp.exemplars, dp.sum.as_f64(), dp.count as u64),
+            }
+        })
+        .collect()
+}
+
+/// Attaches every exemplar to the first bucket whose upper bound contains its
+/// value, keeping the most recent exemplar of each bucket.
+fn histogram_value<T: Numeric>(
+    mut buckets: Vec<Bucket>,
+    exemplars: &[data::Exemplar<T>],
+    sum: f64,
+    count: u64,
+) -> SeriesValue {
+    for e in exemplars {
+        let value = e.value.as_f64();
+        let bucket = buckets
+            .iter_mut()
+            .find(|b| value <= b.upper_bound)
+            .filter(|b| b.exemplar.as_ref().map_or(true, |prev| prev.time <= e.time));
+        if let Some(bucket) = bucket {
+            bucket.exemplar = Some(exemplar(e));
+        }
+    }
+
+    SeriesValue::Histogram {
+        buckets,
+        sum,
+        count,
+    }
+}
+
+fn exemplar<T: Numeric>(e: &data::Exemplar<T>) -> Exemplar {
+    let mut labels = Vec::with_capacity(2 + e.filtered_attributes.len());
+    if e.trace_id != [0; 16] {
+        labels.push(("trace_id".to_string(), hex(&e.trace_id)));
+    }
+    if e.span_id != [0; 8] {
+        labels.push(("span_id".to_string(), hex(&e.span_id)));
+    }
+
+    let attributes =
+        sanitized_attrs(&mut e.filtered_attributes.iter().map(|kv| (&kv.key, &kv.value)));
+    let length = |labels: &Labels| {
+        labels
+            .iter()
+            .map(|(k, v)| k.chars().count() + v.chars().count())
+            .sum::<usize>()
+    };
+    // the trace context is kept over the filtered attributes if they do not fit
+    if length(&labels) + length(&attributes) <= MAX_EXEMPLAR_LABELS_LENGTH {
+        labels.extend(attributes);
+    }
+
+    Exemplar {
+        labels,
+        value: e.value.as_f64(),
+        time: e.time,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,302 @@
+//! Text exposition of the collected metrics without the `prometheus` crate.
+use std::{fmt, io, sync::Arc};
+
+use opentelemetry::metrics::{MetricsError, Result};
+use opentelemetry_sdk::{
+    metrics::{data::ResourceMetrics, reader::MetricReader},
+    Resource,
+};
+
+use crate::Collector;
+
+pub(crate) mod family;
+pub(crate) mod text;
+
+use family::collect_families;
+
+const PROMETHEUS_TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// The text formats a [TextEncoder] renders metrics in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExpositionFormat {
+    /// The Prometheus text format, version 0.0.4.
+    #[default]
+    PrometheusText,
+    /// The OpenMetrics text format, version 1.0.0.
+    ///
+    /// In addition to the Prometheus text format, it carries the `# UNIT`
+    /// metadata, the `_created` series of counters and histograms, and the
+    /// exemplars of counters and histogram buckets.
+    OpenMetrics,
+}
+
+impl ExpositionFormat {
+    /// The HTTP `Content-Type` of a response body in this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExpositionFormat::PrometheusText => PROMETHEUS_TEXT_CONTENT_TYPE,
+            ExpositionFormat::OpenMetrics => OPENMETRICS_CONTENT_TYPE,
+        }
+    }
+
+    /// Selects the format to respond with from the value of an HTTP `Accept`
+    /// header.
+    ///
+    /// Media ranges are weighted by their `q` parameter, the first one wins on
+    /// equal weights. OpenMetrics is only selected for
+    /// `application/openmetrics-text` without a version or with version
+    /// `1.0.0`. If no supported media range is accepted, the Prometheus text
+    /// format is used since every scraper understands it.
+    pub fn from_accept(accept: &str) -> Self {
+        negotiate(accept, |media_range| match media_range.essence {
+            "application/openmetrics-text" => match media_range.param("version") {
+                None | Some("1.0.0") => Some(ExpositionFormat::OpenMetrics),
+                _ => None,
+            },
+            "text/plain" => match media_range.param("version") {
+                None | Some("0.0.4") => Some(ExpositionFormat::PrometheusText),
+                _ => None,
+            },
+            "text/*" | "*/*" => Some(ExpositionFormat::PrometheusText),
+            _ => None,
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// An element of an HTTP `Accept` or `Accept-Encoding` header value.
+pub(crate) struct MediaRange<'a> {
+    pub(crate) essence: &'a str,
+    params: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> MediaRange<'a> {
+    pub(crate) fn param(&self, name: &str) -> Option<&'a str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Returns the value `select` maps the highest weighted element of an HTTP
+/// `Accept` or `Accept-Encoding` header value to.
+///
+/// Elements are weighted by their `q` parameter, the first one wins on equal
+/// weights, and elements with a weight of zero are never selected.
+pub(crate) fn negotiate<T>(
+    header: &str,
+    select: impl Fn(&MediaRange<'_>) -> Option<T>,
+) -> Option<T> {
+    let mut selected: Option<(T, f32)> = None;
+
+    for element in header.split(',') {
+        let mut params = element.split(';');
+        let essence = params.next().unwrap_or_default().trim();
+        let media_range = MediaRange {
+            essence,
+            params: params
+                .filter_map(|param| param.split_once('='))
+                .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+                .collect(),
+        };
+        let quality = media_range
+            .param("q")
+            .map_or(1.0, |q| q.parse().unwrap_or(0.0));
+
+        if quality > 0.0 && selected.as_ref().map_or(true, |(_, q)| quality > *q) {
+            if let Some(value) = select(&media_range) {
+                selected = Some((value, quality));
+            }
+        }
+    }
+
+    selected.map(|(value, _)| value)
+}
+
+/// Renders the metrics of a [PrometheusExporter] in the Prometheus and
+/// OpenMetrics text formats, without a [prometheus::Registry].
+///
+/// The metrics are collected from the exporter's reader on every call to
+/// [TextEncoder::encode], applying the same naming, unit, suffix and label
+/// conventions as the registry based collection.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::metrics::MeterProvider;
+/// use opentelemetry_prometheus::ExpositionFormat;
+/// use opentelemetry_sdk::metrics::SdkMeterProvider;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let exporter = opentelemetry_prometheus::exporter().build()?;
+/// let encoder = exporter.text_encoder();
+/// let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+///
+/// let counter = provider.meter("my-app").u64_counter("a.counter").init();
+/// counter.add(100, &[]);
+///
+/// // usually the `Accept` header of the scrape request
+/// let format = ExpositionFormat::from_accept("application/openmetrics-text; version=1.0.0");
+/// let mut body = Vec::new();
+/// encoder.encode(format, &mut body)?;
+/// // respond with `body` and a `Content-Type` of `format.content_type()`
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [PrometheusExporter]: crate::PrometheusExporter
+#[derive(Clone)]
+pub struct TextEncoder {
+    collector: Arc<Collector>,
+}
+
+impl fmt::Debug for TextEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextEncoder").finish()
+    }
+}
+
+impl TextEncoder {
+    pub(crate) fn new(collector: Collector) -> Self {
+        TextEncoder {
+            collector: Arc::new(collector),
+        }
+    }
+
+    /// Collects the current metrics and writes them to `writer` in `format`.
+    pub fn encode<W: io::Write>(&self, format: ExpositionFormat, writer: &mut W) -> Result<()> {
+        let mut metrics = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: vec![],
+        };
+        self.collector.reader.collect(&mut metrics)?;
+
+        self.encode_metrics(&metrics, format, writer)
+    }
+
+    fn encode_metrics(
+        &self,
+        metrics: &ResourceMetrics,
+        format: ExpositionFormat,
+        writer: &mut dyn io::Write,
+    ) -> Result<()> {
+        let families = collect_families(&self.collector, metrics);
+        text::write_families(writer, format, &families)
+            .map_err(|err| MetricsError::Other(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExporterBuilder;
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::{
+        metrics::data::{
+            DataPoint, Exemplar, Histogram, HistogramDataPoint, Metric, ScopeMetrics, Sum,
+            Temporality,
+        },
+        Scope,
+    };
+    use std::time::{Duration, SystemTime};
+
+    fn encode(metric: Metric) -> String {
+        let encoder = ExporterBuilder::default()
+            .without_scope_info()
+            .without_target_info()
+            .build()
+            .unwrap()
+            .text_encoder();
+        let metrics = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: vec![ScopeMetrics {
+                scope: Scope::default(),
+                metrics: vec![metric],
+            }],
+        };
+
+        let mut output = Vec::new();
+        encoder
+            .encode_metrics(&metrics, ExpositionFormat::OpenMetrics, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn exemplar(value: f64, secs: u64) -> Exemplar<f64> {
+        Exemplar {
+            filtered_attributes: vec![KeyValue::new("user.id", "42")],
+            time: SystemTime::UNIX_EPOCH + Duration::from_millis(secs * 1000 + 500),
+            value,
+            span_id: [1; 8],
+            trace_id: [2; 16],
+        }
+    }
+
+    #[test]
+    fn counter_exemplar() {
+        let output = encode(Metric {
+            name: "requests".into(),
+            description: "".into(),
+            unit: "".into(),
+            data: Box::new(Sum {
+                data_points: vec![DataPoint {
+                    attributes: vec![],
+                    start_time: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(10)),
+                    time: None,
+                    value: 3.0,
+                    exemplars: vec![exemplar(1.0, 20), exemplar(2.0, 30)],
+                }],
+                temporality: Temporality::Cumulative,
+                is_monotonic: true,
+            }),
+        });
+
+        assert_eq!(
+            output,
+            "# TYPE requests counter\n\
+             requests_total 3.0 # {trace_id=\"02020202020202020202020202020202\",span_id=\"0101010101010101\",user_id=\"42\"} 2.0 30.500\n\
+             requests_created 10.000\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn histogram_exemplars() {
+        let output = encode(Metric {
+            name: "latency".into(),
+            description: "".into(),
+            unit: "".into(),
+            data: Box::new(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    attributes: vec![],
+                    start_time: SystemTime::UNIX_EPOCH,
+                    time: SystemTime::UNIX_EPOCH,
+                    count: 3,
+                    bounds: vec![1.0, 10.0],
+                    bucket_counts: vec![1, 0, 2],
+                    min: None,
+                    max: None,
+                    sum: 50.5,
+                    exemplars: vec![exemplar(0.5, 1), exemplar(30.0, 2)],
+                }],
+                temporality: Temporality::Cumulative,
+            }),
+        });
+
+        let buckets = output
+            .lines()
+            .filter(|line| line.starts_with("latency_bucket"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![
+                "latency_bucket{le=\"1.0\"} 1 # {trace_id=\"02020202020202020202020202020202\",span_id=\"0101010101010101\",user_id=\"42\"} 0.5 1.500",
+                "latency_bucket{le=\"10.0\"} 1",
+                "latency_bucket{le=\"+Inf\"} 3 # {trace_id=\"02020202020202020202020202020202\",span_id=\"0101010101010101\",user_id=\"42\"} 30.0 2.500",
+            ]
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Rendering of metric families in the Prometheus and OpenMetrics text formats.
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{self, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    family::{Exemplar, Family, FamilyKind, Labels, SeriesValue},
+    ExpositionFormat,
+};
+use crate::COUNTER_SUFFIX;
+
+const INFO_SUFFIX: &str = "_info";
+
+pub(crate) fn write_families(
+    w: &mut dyn Write,
+    format: ExpositionFormat,
+    families: &BTreeMap<String, Family>,
+) -> io::Result<()> {
+    for (name, family) in families {
+        match format {
+            ExpositionFormat::PrometheusText => write_prometheus_family(w, name, family)?,
+            ExpositionFormat::OpenMetrics => write_openmetrics_family(w, name, family)?,
+        }
+    }
+
+    if format == ExpositionFormat::OpenMetrics {
+        w.write_all(b"# EOF\n")?;
+    }
+
+    Ok(())
+}
+
+fn write_prometheus_family(w: &mut dyn Write, name: &str, family: &Family) -> io::Result<()> {
+    if !family.help.is_empty() {
+        writeln!(w, "# HELP {name} {}", escape(&family.help, false))?;
+    }
+    let kind = match family.kind {
+        FamilyKind::Counter => "counter",
+        FamilyKind::Gauge | FamilyKind::Info => "gauge",
+        FamilyKind::Histogram => "histogram",
+    };
+    writeln!(w, "# TYPE {name} {kind}")?;
+
+    for series in &family.series {
+        let labels = &series.labels;
+        match &series.value {
+            SeriesValue::Counter { value, .. } | SeriesValue::Gauge(value) => {
+                write_sample(w, name, "", labels, None, &prometheus_float(*value), None)?;
+            }
+            SeriesValue::Histogram {
+                buckets,
+                sum,
+                count,
+            } => {
+                for bucket in buckets {
+                    let le = prometheus_float(bucket.upper_bound);
+                    let count = bucket.cumulative_count.to_string();
+                    write_sample(w, name, "_bucket", labels, Some(&le), &count, None)?;
+                }
+                write_sample(w, name, "_sum", labels, None, &prometheus_float(*sum), None)?;
+                write_sample(w, name, "_count", labels, None, &count.to_string(), None)?;
+            }
+            SeriesValue::Info => write_sample(w, name, "", labels, None, "1", None)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn write_openmetrics_family(w: &mut dyn Write, name: &str, family: &Family) -> io::Result<()> {
+    // counters and info metrics are named by their family name in metadata
+    let (name, kind) = match family.kind {
+        FamilyKind::Counter => (name.strip_suffix(COUNTER_SUFFIX).unwrap_or(name), "counter"),
+        FamilyKind::Gauge => (name, "gauge"),
+        FamilyKind::Histogram => (name, "histogram"),
+        FamilyKind::Info => (name.strip_suffix(INFO_SUFFIX).unwrap_or(name), "info"),
+    };
+    writeln!(w, "# TYPE {name} {kind}")?;
+    if let Some(unit) = &family.unit {
+        // the unit metadata must be a suffix of the family name
+        if name.ends_with(&format!("_{unit}")) {
+            writeln!(w, "# UNIT {name} {unit}")?;
+        }
+    }
+    if !family.help.is_empty() {
+        writeln!(w, "# HELP {name} {}", escape(&family.help, true))?;
+    }
+
+    for series in &family.series {
+        let labels = &series.labels;
+        match &series.value {
+            SeriesValue::Counter { value, exemplar } => {
+                let value = openmetrics_float(*value);
+                write_sample(w, name, "_total", labels, None, &value, exemplar.as_ref())?;
+            }
+            SeriesValue::Gauge(value) => {
+                write_sample(w, name, "", labels, None, &openmetrics_float(*value), None)?;
+            }
+            SeriesValue::Histogram {
+                buckets,
+                sum,
+                count,
+            } => {
+                for bucket in buckets {
+                    let le = openmetrics_float(bucket.upper_bound);
+                    let count = bucket.cumulative_count.to_string();
+                    let exemplar = bucket.exemplar.as_ref();
+                    write_sample(w, name, "_bucket", labels, Some(&le), &count, exemplar)?;
+                }
+                write_sample(w, name, "_count", labels, None, &count.to_string(), None)?;
+                // sums of histograms with negative observations are not counters
+                if *sum >= 0.0 {
+                    write_sample(
+                        w,
+                        name,
+                        "_sum",
+                        labels,
+                        None,
+                        &openmetrics_float(*sum),
+                        None,
+                    )?;
+                }
+            }
+            SeriesValue::Info => write_sample(w, name, INFO_SUFFIX, labels, None, "1", None)?,
+        }
+
+        if let Some(created) = series.created {
+            write_sample(w, name, "_created", labels, None, &timestamp(created), None)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_sample(
+    w: &mut dyn Write,
+    name: &str,
+    suffix: &str,
+    labels: &Labels,
+    le: Option<&str>,
+    value: &str,
+    exemplar: Option<&Exemplar>,
+) -> io::Result<()> {
+    write!(w, "{name}{suffix}")?;
+    if !labels.is_empty() || le.is_some() {
+        let le = le.map(|le| ("le", le));
+        write_labels(
+            w,
+            labels
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .chain(le),
+        )?;
+    }
+    write!(w, " {value}")?;
+
+    if let Some(exemplar) = exemplar {
+        w.write_all(b" # ")?;
+        let labels = exemplar.labels.iter();
+        write_labels(w, labels.map(|(k, v)| (k.as_str(), v.as_str())))?;
+        let value = openmetrics_float(exemplar.value);
+        write!(w, " {value} {}", timestamp(exemplar.time))?;
+    }
+
+    w.write_all(b"\n")
+}
+
+fn write_labels<'a>(
+    w: &mut dyn Write,
+    labels: impl Iterator<Item = (&'a str, &'a str)>,
+) -> io::Result<()> {
+    let mut separator = "{";
+    for (name, value) in labels {
+        write!(w, "{separator}{name}=\"{}\"", escape(value, true))?;
+        separator = ",";
+    }
+    if separator == "{" {
+        w.write_all(b"{")?;
+    }
+    w.write_all(b"}")
+}
+
+/// Escapes `\` and new lines, and `"` if `include_double_quote` is set.
+fn escape(v: &str, include_double_quote: bool) -> Cow<'_, str> {
+    let needs_escape = |c: char| c == '\\' || c == '\n' || (include_double_quote && c == '"');
+    if !v.contains(needs_escape) {
+        return Cow::Borrowed(v);
+    }
+
+    let mut escaped = String::with_capacity(v.len() + 8);
+    for c in v.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            c if needs_escape(c) => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+fn special_float(v: f64) -> Option<&'static str> {
+    if v.is_nan() {
+        Some("NaN")
+    } else if v == f64::INFINITY {
+        Some("+Inf")
+    } else if v == f64::NEG_INFINITY {
+        Some("-Inf")
+    } else {
+        None
+    }
+}
+
+pub(crate) fn prometheus_float(v: f64) -> String {
+    special_float(v).map_or_else(|| v.to_string(), str::to_string)
+}
+
+/// Formats floats canonically, always with a fractional part or exponent.
+fn openmetrics_float(v: f64) -> String {
+    special_float(v).map_or_else(|| format!("{v:?}"), str::to_string)
+}
+
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}.{:03}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_millis()
+    )
+}
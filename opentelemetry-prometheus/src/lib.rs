@@ -135,11 +135,24 @@ const SCOPE_INFO_KEYS: [&str; 2] = ["otel_scope_name", "otel_scope_version"];
 const COUNTER_SUFFIX: &str = "_total";
 
 mod config;
+#[cfg(feature = "prometheus-encoding")]
+mod encoding;
+mod native_histogram;
+#[cfg(feature = "remote-write")]
+mod remote_write;
 mod resource_selector;
+#[cfg(feature = "server")]
+mod server;
 mod utils;
 
 pub use config::ExporterBuilder;
+#[cfg(feature = "prometheus-encoding")]
+pub use encoding::{ExpositionFormat, TextEncoder};
+#[cfg(feature = "remote-write")]
+pub use remote_write::{RemoteWriteExporter, RemoteWriteExporterBuilder};
 pub use resource_selector::ResourceSelector;
+#[cfg(feature = "server")]
+pub use server::{ScrapeServer, ScrapeService};
 
 /// Creates a builder to configure a [PrometheusExporter]
 pub fn exporter() -> ExporterBuilder {
@@ -150,6 +163,30 @@ pub fn exporter() -> ExporterBuilder {
 #[derive(Debug)]
 pub struct PrometheusExporter {
     reader: Arc<ManualReader>,
+    #[cfg(feature = "prometheus-encoding")]
+    encoder: TextEncoder,
+}
+
+#[cfg(feature = "prometheus-encoding")]
+impl PrometheusExporter {
+    /// Returns a [TextEncoder] that renders the metrics collected by this
+    /// exporter without going through a [prometheus::Registry].
+    ///
+    /// The encoder shares the exporter's reader, so it must be obtained before
+    /// the exporter is handed over to the meter provider.
+    pub fn text_encoder(&self) -> TextEncoder {
+        self.encoder.clone()
+    }
+
+    /// Returns a [ScrapeService] serving the metrics collected by this
+    /// exporter over HTTP.
+    ///
+    /// Like [PrometheusExporter::text_encoder], it must be obtained before the
+    /// exporter is handed over to the meter provider.
+    #[cfg(feature = "server")]
+    pub fn scrape_service(&self) -> ScrapeService {
+        ScrapeService::new(self.text_encoder())
+    }
 }
 
 impl TemporalitySelector for PrometheusExporter {
@@ -183,6 +220,7 @@ struct Collector {
     disable_target_info: bool,
     without_units: bool,
     without_counter_suffixes: bool,
+    without_exponential_histogram_fallback: bool,
     disable_scope_info: bool,
     create_target_info_once: OnceCell<MetricFamily>,
     resource_labels_once: OnceCell<Vec<LabelPair>>,
@@ -206,6 +244,13 @@ static HISTOGRAM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
         TypeId::of::<data::Histogram<f64>>(),
     ]
 });
+static EXPONENTIAL_HISTOGRAM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
+    [
+        TypeId::of::<data::ExponentialHistogram<i64>>(),
+        TypeId::of::<data::ExponentialHistogram<u64>>(),
+        TypeId::of::<data::ExponentialHistogram<f64>>(),
+    ]
+});
 static SUM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
     [
         TypeId::of::<data::Sum<i64>>(),
@@ -228,7 +273,7 @@ impl Collector {
         let data = m.data.as_any();
         let type_id = data.type_id();
 
-        if HISTOGRAM_TYPES.contains(&type_id) {
+        if HISTOGRAM_TYPES.contains(&type_id) || EXPONENTIAL_HISTOGRAM_TYPES.contains(&type_id) {
             Some((MetricType::HISTOGRAM, name))
         } else if GAUGE_TYPES.contains(&type_id) {
             Some((MetricType::GAUGE, name))
@@ -361,6 +406,33 @@ impl prometheus::core::Collector for Collector {
                     add_histogram_metric(&mut res, hist, description, &scope_labels, name);
                 } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
                     add_histogram_metric(&mut res, hist, description, &scope_labels, name);
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<i64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        !self.without_exponential_histogram_fallback,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        !self.without_exponential_histogram_fallback,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        !self.without_exponential_histogram_fallback,
+                    );
                 } else if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
                     add_sum_metric(&mut res, sum, description, &scope_labels, name);
                 } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
@@ -386,23 +458,13 @@ impl prometheus::core::Collector for Collector {
 /// It sanitizes invalid characters and handles duplicate keys (due to
 /// sanitization) by sorting and concatenating the values following the spec.
 fn get_attrs(kvs: &mut dyn Iterator<Item = (&Key, &Value)>, extra: &[LabelPair]) -> Vec<LabelPair> {
-    let mut keys_map = BTreeMap::<String, Vec<String>>::new();
-    for (key, value) in kvs {
-        let key = utils::sanitize_prom_kv(key.as_str());
-        keys_map
-            .entry(key)
-            .and_modify(|v| v.push(value.to_string()))
-            .or_insert_with(|| vec![value.to_string()]);
-    }
-
+    let keys_map = sanitized_attrs(kvs);
     let mut res = Vec::with_capacity(keys_map.len() + extra.len());
 
-    for (key, mut values) in keys_map.into_iter() {
-        values.sort_unstable();
-
+    for (key, value) in keys_map {
         let mut lp = LabelPair::new();
         lp.set_name(key);
-        lp.set_value(values.join(";"));
+        lp.set_value(value);
         res.push(lp);
     }
 
@@ -413,6 +475,29 @@ fn get_attrs(kvs: &mut dyn Iterator<Item = (&Key, &Value)>, extra: &[LabelPair])
     res
 }
 
+/// Sanitizes attribute keys into label names, ordered by name.
+///
+/// Values of keys that are duplicated due to sanitization are sorted and
+/// concatenated.
+fn sanitized_attrs(kvs: &mut dyn Iterator<Item = (&Key, &Value)>) -> Vec<(String, String)> {
+    let mut keys_map = BTreeMap::<String, Vec<String>>::new();
+    for (key, value) in kvs {
+        let key = utils::sanitize_prom_kv(key.as_str());
+        keys_map
+            .entry(key)
+            .and_modify(|v| v.push(value.to_string()))
+            .or_insert_with(|| vec![value.to_string()]);
+    }
+
+    keys_map
+        .into_iter()
+        .map(|(key, mut values)| {
+            values.sort_unstable();
+            (key, values.join(";"))
+        })
+        .collect()
+}
+
 fn validate_metrics(
     name: &str,
     description: &str,
@@ -486,6 +571,43 @@ fn add_histogram_metric<T: Numeric>(
     }
 }
 
+fn add_exponential_histogram_metric<T: Numeric>(
+    res: &mut Vec<MetricFamily>,
+    histogram: &data::ExponentialHistogram<T>,
+    description: String,
+    extra: &[LabelPair],
+    name: Cow<'static, str>,
+    classic_fallback: bool,
+) {
+    for dp in &histogram.data_points {
+        let kvs = get_attrs(
+            &mut dp.attributes.iter().map(|kv| (&kv.key, &kv.value)),
+            extra,
+        );
+
+        let mut h = prometheus::proto::Histogram::default();
+        h.set_sample_sum(dp.sum.as_f64());
+        h.set_sample_count(dp.count as u64);
+        if classic_fallback {
+            h.set_bucket(protobuf::RepeatedField::from_vec(
+                native_histogram::classic_buckets(dp),
+            ));
+        }
+        native_histogram::set_native_fields(&mut h, dp);
+
+        let mut pm = prometheus::proto::Metric::default();
+        pm.set_label(protobuf::RepeatedField::from_vec(kvs));
+        pm.set_histogram(h);
+
+        let mut mf = prometheus::proto::MetricFamily::default();
+        mf.set_name(name.to_string());
+        mf.set_help(description.clone());
+        mf.set_field_type(prometheus::proto::MetricType::HISTOGRAM);
+        mf.set_metric(protobuf::RepeatedField::from_vec(vec![pm]));
+        res.push(mf);
+    }
+}
+
 fn add_sum_metric<T: Numeric>(
     res: &mut Vec<MetricFamily>,
     sum: &data::Sum<T>,
@@ -0,0 +1,281 @@
+//! Conversion of exponential histogram data points to Prometheus histograms.
+//!
+//! The `prometheus` crate predates native histograms, so the native histogram
+//! fields are written as unknown fields of the `Histogram` message, using the
+//! field numbers of the upstream `io.prometheus.client` protobuf definition.
+//! They are serialized by the protobuf encoder and ignored by the text encoder,
+//! which only renders the classic buckets.
+use opentelemetry_sdk::metrics::data::{ExponentialBucket, ExponentialHistogramDataPoint};
+use prometheus::proto::{Bucket, Histogram};
+use protobuf::{CodedOutputStream, Message, UnknownFields, UnknownValue};
+
+const SCHEMA_FIELD: u32 = 5;
+const ZERO_THRESHOLD_FIELD: u32 = 6;
+const ZERO_COUNT_FIELD: u32 = 7;
+const NEGATIVE_SPAN_FIELD: u32 = 9;
+const NEGATIVE_DELTA_FIELD: u32 = 10;
+const POSITIVE_SPAN_FIELD: u32 = 12;
+const POSITIVE_DELTA_FIELD: u32 = 13;
+
+const BUCKET_SPAN_OFFSET_FIELD: u32 = 1;
+const BUCKET_SPAN_LENGTH_FIELD: u32 = 2;
+
+// Prometheus only accepts native histogram schemas within this range.
+const MIN_SCHEMA: i8 = -4;
+const MAX_SCHEMA: i8 = 8;
+
+/// Adds the native histogram representation of `dp` to `h`.
+///
+/// Scales above the maximum Prometheus schema are downscaled by merging
+/// adjacent buckets. Scales below the minimum schema cannot be represented, in
+/// which case no native histogram fields are added.
+pub(crate) fn set_native_fields<T>(h: &mut Histogram, dp: &ExponentialHistogramDataPoint<T>) {
+    if dp.scale < MIN_SCHEMA {
+        return;
+    }
+    let schema = dp.scale.min(MAX_SCHEMA);
+    let downscale = (dp.scale - schema) as u32;
+
+    let fields = h.mut_unknown_fields();
+    fields.add_value(SCHEMA_FIELD, UnknownValue::sint32(schema.into()));
+    fields.add_fixed64(ZERO_THRESHOLD_FIELD, dp.zero_threshold.to_bits());
+    fields.add_varint(ZERO_COUNT_FIELD, dp.zero_count);
+
+    let has_negative = add_spans(
+        fields,
+        NEGATIVE_SPAN_FIELD,
+        NEGATIVE_DELTA_FIELD,
+        &dp.negative_bucket,
+        downscale,
+    );
+    let has_positive = add_spans(
+        fields,
+        POSITIVE_SPAN_FIELD,
+        POSITIVE_DELTA_FIELD,
+        &dp.positive_bucket,
+        downscale,
+    );
+
+    // Prometheus only recognizes a histogram as native if it has a span, a zero
+    // count or a zero threshold, so empty histograms carry an empty span.
+    if !has_negative && !has_positive && dp.zero_count == 0 && dp.zero_threshold == 0.0 {
+        fields.add_length_delimited(POSITIVE_SPAN_FIELD, encode_span(0, 0));
+    }
+}
+
+/// Encodes the populated buckets as Prometheus spans and count deltas.
+///
+/// OpenTelemetry bucket `i` has the upper bound `base^(i+1)` whereas
+/// Prometheus bucket `i` has the upper bound `base^i`, so indexes are shifted
+/// by one. Returns `false` if no bucket is populated.
+fn add_spans(
+    fields: &mut UnknownFields,
+    span_field: u32,
+    delta_field: u32,
+    bucket: &ExponentialBucket,
+    downscale: u32,
+) -> bool {
+    let mut counts: Vec<(i32, u64)> = Vec::with_capacity(bucket.counts.len());
+    for (i, &count) in bucket.counts.iter().enumerate() {
+        let index = ((bucket.offset + i as i32) >> downscale) + 1;
+        match counts.last_mut() {
+            Some((last, merged)) if *last == index => *merged += count,
+            _ => counts.push((index, count)),
+        }
+    }
+
+    let mut spans: Vec<(i32, u32)> = Vec::new();
+    let mut prev: Option<(i32, u64)> = None;
+    for (index, count) in counts.into_iter().filter(|(_, count)| *count > 0) {
+        match prev {
+            Some((prev_index, _)) if index == prev_index + 1 => {
+                if let Some((_, length)) = spans.last_mut() {
+                    *length += 1;
+                }
+            }
+            Some((prev_index, _)) => spans.push((index - prev_index - 1, 1)),
+            None => spans.push((index, 1)),
+        }
+        let prev_count = prev.map_or(0, |(_, prev_count)| prev_count);
+        fields.add_value(
+            delta_field,
+            UnknownValue::sint64(count as i64 - prev_count as i64),
+        );
+        prev = Some((index, count));
+    }
+
+    for (offset, length) in &spans {
+        fields.add_length_delimited(span_field, encode_span(*offset, *length));
+    }
+
+    !spans.is_empty()
+}
+
+fn encode_span(offset: i32, length: u32) -> Vec<u8> {
+    let mut buf = Vec::new();
+    {
+        let mut os = CodedOutputStream::vec(&mut buf);
+        // writing to a `Vec` is infallible
+        let _ = os
+            .write_sint32(BUCKET_SPAN_OFFSET_FIELD, offset)
+            .and_then(|_| os.write_uint32(BUCKET_SPAN_LENGTH_FIELD, length))
+            .and_then(|_| os.flush());
+    }
+    buf
+}
+
+/// Converts the exponential buckets of `dp` to cumulative classic buckets.
+///
+/// Every exponential bucket becomes a classic bucket with the same upper
+/// bound, ordered from the most negative bucket to the most positive one, with
+/// the zero bucket bounded by the zero threshold in between.
+pub(crate) fn classic_buckets<T>(dp: &ExponentialHistogramDataPoint<T>) -> Vec<Bucket> {
+    let log2_base = (-f64::from(dp.scale)).exp2();
+    let bound = |index: i32| (f64::from(index) * log2_base).exp2();
+
+    let negative = &dp.negative_bucket;
+    let positive = &dp.positive_bucket;
+    let mut buckets = Vec::with_capacity(negative.counts.len() + positive.counts.len() + 1);
+    let mut cumulative = 0;
+
+    // negative bucket `i` covers [-base^(i+1), -base^i)
+    for (i, count) in negative.counts.iter().enumerate().rev() {
+        cumulative += count;
+        buckets.push(bucket(-bound(negative.offset + i as i32), cumulative));
+    }
+
+    cumulative += dp.zero_count;
+    buckets.push(bucket(dp.zero_threshold, cumulative));
+
+    // positive bucket `i` covers (base^i, base^(i+1)]
+    for (i, count) in positive.counts.iter().enumerate() {
+        cumulative += count;
+        buckets.push(bucket(bound(positive.offset + i as i32 + 1), cumulative));
+    }
+
+    buckets
+}
+
+fn bucket(upper_bound: f64, cumulative_count: u64) -> Bucket {
+    let mut b = Bucket::default();
+    b.set_upper_bound(upper_bound);
+    b.set_cumulative_count(cumulative_count);
+    b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn data_point(
+        scale: i8,
+        negative: ExponentialBucket,
+        positive: ExponentialBucket,
+    ) -> ExponentialHistogramDataPoint<f64> {
+        ExponentialHistogramDataPoint {
+            attributes: vec![],
+            start_time: SystemTime::UNIX_EPOCH,
+            time: SystemTime::UNIX_EPOCH,
+            count: (negative.counts.iter().sum::<u64>() + positive.counts.iter().sum::<u64>())
+                as usize,
+            min: None,
+            max: None,
+            sum: 0.0,
+            scale,
+            zero_count: 0,
+            positive_bucket: positive,
+            negative_bucket: negative,
+            zero_threshold: 0.0,
+            exemplars: vec![],
+        }
+    }
+
+    #[test]
+    fn classic_buckets_are_cumulative_from_negative_to_positive() {
+        let dp = data_point(
+            0,
+            ExponentialBucket {
+                offset: 0,
+                counts: vec![1, 2],
+            },
+            ExponentialBucket {
+                offset: 1,
+                counts: vec![3],
+            },
+        );
+
+        let buckets = classic_buckets(&dp)
+            .iter()
+            .map(|b| (b.get_upper_bound(), b.get_cumulative_count()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(buckets, vec![(-2.0, 2), (-1.0, 3), (0.0, 3), (4.0, 6)],);
+    }
+
+    #[test]
+    fn scale_above_max_schema_is_downscaled() {
+        // at scale 10, buckets 0..4 all fall into bucket 0 at schema 8
+        let dp = data_point(
+            10,
+            empty_bucket(),
+            ExponentialBucket {
+                offset: 0,
+                counts: vec![1, 1, 1, 1, 1],
+            },
+        );
+        let mut h = Histogram::default();
+        set_native_fields(&mut h, &dp);
+
+        let fields = h.get_unknown_fields();
+        assert_eq!(
+            fields.get(SCHEMA_FIELD).unwrap().varint,
+            vec![encode_sint64(8)]
+        );
+        // one span starting at Prometheus index 1 with two buckets: 4 and 1
+        assert_eq!(
+            fields.get(POSITIVE_SPAN_FIELD).unwrap().length_delimited,
+            vec![encode_span(1, 2)]
+        );
+        assert_eq!(
+            fields.get(POSITIVE_DELTA_FIELD).unwrap().varint,
+            vec![encode_sint64(4), encode_sint64(-3)]
+        );
+        assert!(fields.get(NEGATIVE_SPAN_FIELD).is_none());
+    }
+
+    #[test]
+    fn scale_below_min_schema_has_no_native_fields() {
+        let dp = data_point(-5, empty_bucket(), empty_bucket());
+        let mut h = Histogram::default();
+        set_native_fields(&mut h, &dp);
+
+        assert!(h.get_unknown_fields().get(SCHEMA_FIELD).is_none());
+    }
+
+    #[test]
+    fn empty_histogram_has_empty_span() {
+        let dp = data_point(0, empty_bucket(), empty_bucket());
+        let mut h = Histogram::default();
+        set_native_fields(&mut h, &dp);
+
+        assert_eq!(
+            h.get_unknown_fields()
+                .get(POSITIVE_SPAN_FIELD)
+                .unwrap()
+                .length_delimited,
+            vec![encode_span(0, 0)]
+        );
+    }
+
+    fn empty_bucket() -> ExponentialBucket {
+        ExponentialBucket {
+            offset: 0,
+            counts: vec![],
+        }
+    }
+
+    fn encode_sint64(v: i64) -> u64 {
+        ((v << 1) ^ (v >> 63)) as u64
+    }
+}
@@ -0,0 +1,253 @@
+//! Pushing metrics with the Prometheus remote write protocol.
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http::{
+    header::{AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, USER_AGENT},
+    HeaderValue, Method, Request, Uri,
+};
+use opentelemetry::metrics::{MetricsError, Result};
+use opentelemetry_http::HttpClient;
+use opentelemetry_sdk::metrics::{
+    data::{ResourceMetrics, Temporality},
+    exporter::PushMetricsExporter,
+    reader::TemporalitySelector,
+    InstrumentKind,
+};
+
+use crate::{encoding::family::collect_families, Collector, ExporterBuilder};
+
+mod proto;
+
+const REMOTE_WRITE_VERSION_HEADER: &str = "X-Prometheus-Remote-Write-Version";
+const REMOTE_WRITE_VERSION: &str = "0.1.0";
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+const SNAPPY_CONTENT_ENCODING: &str = "snappy";
+
+/// Configuration for a [RemoteWriteExporter], created with
+/// [ExporterBuilder::remote_write].
+pub struct RemoteWriteExporterBuilder {
+    config: ExporterBuilder,
+    endpoint: String,
+    client: Option<Arc<dyn HttpClient>>,
+    authorization: Option<Authorization>,
+}
+
+enum Authorization {
+    Basic { username: String, password: String },
+    Bearer(String),
+}
+
+impl Authorization {
+    fn header_value(self) -> Result<HeaderValue> {
+        let value = match self {
+            Authorization::Basic { username, password } => {
+                format!(
+                    "Basic {}",
+                    STANDARD.encode(format!("{username}:{password}"))
+                )
+            }
+            Authorization::Bearer(token) => format!("Bearer {token}"),
+        };
+        let mut value = HeaderValue::try_from(value)
+            .map_err(|_| MetricsError::Config("invalid remote write credentials".into()))?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
+}
+
+impl fmt::Debug for RemoteWriteExporterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteWriteExporterBuilder")
+            .field("config", &self.config)
+            .field("endpoint", &self.endpoint)
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
+impl RemoteWriteExporterBuilder {
+    pub(crate) fn new(config: ExporterBuilder, endpoint: String) -> Self {
+        RemoteWriteExporterBuilder {
+            config,
+            endpoint,
+            client: None,
+            authorization: None,
+        }
+    }
+
+    /// Sets the HTTP client the write requests are sent with.
+    pub fn with_http_client<T: HttpClient + 'static>(mut self, client: T) -> Self {
+        self.client = Some(Arc::new(client));
+        self
+    }
+
+    /// Authenticates write requests with HTTP basic authentication.
+    ///
+    /// Replaces a bearer token set with [Self::with_bearer_token].
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.authorization = Some(Authorization::Basic {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Authenticates write requests with a bearer token.
+    ///
+    /// Replaces the credentials set with [Self::with_basic_auth].
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.authorization = Some(Authorization::Bearer(token.into()));
+        self
+    }
+
+    /// Creates a new [RemoteWriteExporter] from this configuration.
+    ///
+    /// Returns an error if the endpoint is not a valid URI, the credentials are
+    /// not valid header values, or no HTTP client was set.
+    pub fn build(self) -> Result<RemoteWriteExporter> {
+        let endpoint = self
+            .endpoint
+            .parse::<Uri>()
+            .map_err(|err| MetricsError::Config(format!("invalid remote write endpoint: {err}")))?;
+        let client = self
+            .client
+            .ok_or_else(|| MetricsError::Config("no http client for remote write".into()))?;
+
+        let authorization = self
+            .authorization
+            .map(Authorization::header_value)
+            .transpose()?;
+
+        Ok(RemoteWriteExporter {
+            collector: self.config.build_collector(),
+            endpoint,
+            authorization,
+            client: Mutex::new(Some(client)),
+        })
+    }
+}
+
+/// Pushes metrics to a Prometheus remote write endpoint.
+///
+/// Metrics are named and labeled like the metrics of a [PrometheusExporter]
+/// with the same configuration, and sent as a snappy compressed protobuf
+/// `WriteRequest` with all samples timestamped at the time of the export.
+///
+/// Remote write receivers only accept cumulative data, so the exporter always
+/// selects [Temporality::Cumulative].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_http::HttpClient;
+///
+/// # fn example(client: impl HttpClient + 'static) -> Result<(), Box<dyn std::error::Error>> {
+/// let exporter = opentelemetry_prometheus::exporter()
+///     .remote_write("http://localhost:9090/api/v1/write")
+///     .with_http_client(client)
+///     .with_bearer_token("secret")
+///     .build()?;
+/// // export periodically by wrapping the exporter in a `PeriodicReader`
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [PrometheusExporter]: crate::PrometheusExporter
+pub struct RemoteWriteExporter {
+    collector: Collector,
+    endpoint: Uri,
+    authorization: Option<HeaderValue>,
+    client: Mutex<Option<Arc<dyn HttpClient>>>,
+}
+
+impl fmt::Debug for RemoteWriteExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteWriteExporter")
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+impl RemoteWriteExporter {
+    fn write_request(&self, metrics: &ResourceMetrics) -> Result<Request<Vec<u8>>> {
+        let families = collect_families(&self.collector, metrics);
+        let write_request = proto::encode_write_request(&families, SystemTime::now())
+            .map_err(|err| MetricsError::Other(err.to_string()))?;
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&write_request)
+            .map_err(|err| MetricsError::Other(err.to_string()))?;
+
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(self.endpoint.clone())
+            .header(CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)
+            .header(CONTENT_ENCODING, SNAPPY_CONTENT_ENCODING)
+            .header(REMOTE_WRITE_VERSION_HEADER, REMOTE_WRITE_VERSION)
+            .header(
+                USER_AGENT,
+                concat!("opentelemetry-prometheus/", env!("CARGO_PKG_VERSION")),
+            );
+        if let Some(authorization) = &self.authorization {
+            request = request.header(AUTHORIZATION, authorization.clone());
+        }
+
+        request
+            .body(body)
+            .map_err(|err| MetricsError::Other(err.to_string()))
+    }
+}
+
+impl TemporalitySelector for RemoteWriteExporter {
+    /// Note: Prometheus only supports cumulative temporality so this will always be
+    /// [Temporality::Cumulative].
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        self.collector.reader.temporality(kind)
+    }
+}
+
+#[async_trait]
+impl PushMetricsExporter for RemoteWriteExporter {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> Result<()> {
+        let client = self
+            .client
+            .lock()?
+            .clone()
+            .ok_or_else(|| MetricsError::Other("exporter is already shut down".into()))?;
+
+        let response = client
+            .send(self.write_request(metrics)?)
+            .await
+            .map_err(|err| MetricsError::Other(format!("remote write request failed: {err}")))?;
+
+        if !response.status().is_success() {
+            return Err(MetricsError::Other(format!(
+                "remote write request failed with status {}: {}",
+                response.status(),
+                String::from_utf8_lossy(response.body())
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn force_flush(&self) -> Result<()> {
+        // nothing is buffered
+        Ok(())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        let _ = self.client.lock()?.take();
+
+        Ok(())
+    }
+}
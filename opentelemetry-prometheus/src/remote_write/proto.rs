@@ -0,0 +1,210 @@
+//! Encoding of metric families as a Prometheus remote write `WriteRequest`.
+//!
+//! The messages are written field by field with the field numbers of the
+//! upstream `prometheus.WriteRequest` protobuf definition, as the `prometheus`
+//! crate does not include the remote write protocol.
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use protobuf::{CodedOutputStream, ProtobufResult};
+
+use crate::encoding::{
+    family::{Exemplar, Family, FamilyKind, Labels, SeriesValue},
+    text::prometheus_float,
+};
+
+const WRITE_REQUEST_TIMESERIES_FIELD: u32 = 1;
+const WRITE_REQUEST_METADATA_FIELD: u32 = 3;
+
+const TIMESERIES_LABELS_FIELD: u32 = 1;
+const TIMESERIES_SAMPLES_FIELD: u32 = 2;
+const TIMESERIES_EXEMPLARS_FIELD: u32 = 3;
+
+const LABEL_NAME_FIELD: u32 = 1;
+const LABEL_VALUE_FIELD: u32 = 2;
+
+const SAMPLE_VALUE_FIELD: u32 = 1;
+const SAMPLE_TIMESTAMP_FIELD: u32 = 2;
+
+const EXEMPLAR_LABELS_FIELD: u32 = 1;
+const EXEMPLAR_VALUE_FIELD: u32 = 2;
+const EXEMPLAR_TIMESTAMP_FIELD: u32 = 3;
+
+const METADATA_TYPE_FIELD: u32 = 1;
+const METADATA_FAMILY_NAME_FIELD: u32 = 2;
+const METADATA_HELP_FIELD: u32 = 4;
+const METADATA_UNIT_FIELD: u32 = 5;
+
+// `prometheus.MetricMetadata.MetricType` values
+const METRIC_TYPE_COUNTER: i32 = 1;
+const METRIC_TYPE_GAUGE: i32 = 2;
+const METRIC_TYPE_HISTOGRAM: i32 = 3;
+const METRIC_TYPE_INFO: i32 = 6;
+
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// Encodes `families` as an uncompressed `WriteRequest` with all samples taken
+/// at `time`.
+pub(crate) fn encode_write_request(
+    families: &BTreeMap<String, Family>,
+    time: SystemTime,
+) -> ProtobufResult<Vec<u8>> {
+    let timestamp = millis(time);
+    let mut buf = Vec::new();
+    {
+        let mut os = CodedOutputStream::vec(&mut buf);
+
+        for (name, family) in families {
+            for series in &family.series {
+                let labels = &series.labels;
+                match &series.value {
+                    SeriesValue::Counter { value, exemplar } => {
+                        let exemplar = exemplar.as_ref();
+                        let ts = time_series(name, labels, None, *value, timestamp, exemplar)?;
+                        os.write_bytes(WRITE_REQUEST_TIMESERIES_FIELD, &ts)?;
+                    }
+                    SeriesValue::Gauge(value) => {
+                        let ts = time_series(name, labels, None, *value, timestamp, None)?;
+                        os.write_bytes(WRITE_REQUEST_TIMESERIES_FIELD, &ts)?;
+                    }
+                    SeriesValue::Histogram {
+                        buckets,
+                        sum,
+                        count,
+                    } => {
+                        let bucket_name = format!("{name}_bucket");
+                        for bucket in buckets {
+                            let le = ("le", prometheus_float(bucket.upper_bound));
+                            let ts = time_series(
+                                &bucket_name,
+                                labels,
+                                Some(le),
+                                bucket.cumulative_count as f64,
+                                timestamp,
+                                bucket.exemplar.as_ref(),
+                            )?;
+                            os.write_bytes(WRITE_REQUEST_TIMESERIES_FIELD, &ts)?;
+                        }
+                        let sum_name = format!("{name}_sum");
+                        let ts = time_series(&sum_name, labels, None, *sum, timestamp, None)?;
+                        os.write_bytes(WRITE_REQUEST_TIMESERIES_FIELD, &ts)?;
+                        let count_name = format!("{name}_count");
+                        let count = *count as f64;
+                        let ts = time_series(&count_name, labels, None, count, timestamp, None)?;
+                        os.write_bytes(WRITE_REQUEST_TIMESERIES_FIELD, &ts)?;
+                    }
+                    SeriesValue::Info => {
+                        let ts = time_series(name, labels, None, 1.0, timestamp, None)?;
+                        os.write_bytes(WRITE_REQUEST_TIMESERIES_FIELD, &ts)?;
+                    }
+                }
+            }
+        }
+
+        for (name, family) in families {
+            os.write_bytes(WRITE_REQUEST_METADATA_FIELD, &metadata(name, family)?)?;
+        }
+
+        os.flush()?;
+    }
+    Ok(buf)
+}
+
+fn time_series(
+    name: &str,
+    labels: &Labels,
+    le: Option<(&str, String)>,
+    value: f64,
+    timestamp: i64,
+    exemplar: Option<&Exemplar>,
+) -> ProtobufResult<Vec<u8>> {
+    // remote write requires labels sorted by name
+    let mut sorted = labels
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .chain(std::iter::once((METRIC_NAME_LABEL, name)))
+        .chain(le.as_ref().map(|(k, v)| (*k, v.as_str())))
+        .collect::<Vec<_>>();
+    sorted.sort_unstable_by_key(|(k, _)| *k);
+
+    let mut buf = Vec::new();
+    {
+        let mut os = CodedOutputStream::vec(&mut buf);
+        for (k, v) in sorted {
+            os.write_bytes(TIMESERIES_LABELS_FIELD, &label(k, v)?)?;
+        }
+        os.write_bytes(TIMESERIES_SAMPLES_FIELD, &sample(value, timestamp)?)?;
+        if let Some(exemplar) = exemplar {
+            os.write_bytes(TIMESERIES_EXEMPLARS_FIELD, &encode_exemplar(exemplar)?)?;
+        }
+        os.flush()?;
+    }
+    Ok(buf)
+}
+
+fn label(name: &str, value: &str) -> ProtobufResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+        let mut os = CodedOutputStream::vec(&mut buf);
+        os.write_string(LABEL_NAME_FIELD, name)?;
+        os.write_string(LABEL_VALUE_FIELD, value)?;
+        os.flush()?;
+    }
+    Ok(buf)
+}
+
+fn sample(value: f64, timestamp: i64) -> ProtobufResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+        let mut os = CodedOutputStream::vec(&mut buf);
+        os.write_double(SAMPLE_VALUE_FIELD, value)?;
+        os.write_int64(SAMPLE_TIMESTAMP_FIELD, timestamp)?;
+        os.flush()?;
+    }
+    Ok(buf)
+}
+
+fn encode_exemplar(exemplar: &Exemplar) -> ProtobufResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+        let mut os = CodedOutputStream::vec(&mut buf);
+        for (k, v) in &exemplar.labels {
+            os.write_bytes(EXEMPLAR_LABELS_FIELD, &label(k, v)?)?;
+        }
+        os.write_double(EXEMPLAR_VALUE_FIELD, exemplar.value)?;
+        os.write_int64(EXEMPLAR_TIMESTAMP_FIELD, millis(exemplar.time))?;
+        os.flush()?;
+    }
+    Ok(buf)
+}
+
+fn metadata(name: &str, family: &Family) -> ProtobufResult<Vec<u8>> {
+    let metric_type = match family.kind {
+        FamilyKind::Counter => METRIC_TYPE_COUNTER,
+        FamilyKind::Gauge => METRIC_TYPE_GAUGE,
+        FamilyKind::Histogram => METRIC_TYPE_HISTOGRAM,
+        FamilyKind::Info => METRIC_TYPE_INFO,
+    };
+
+    let mut buf = Vec::new();
+    {
+        let mut os = CodedOutputStream::vec(&mut buf);
+        os.write_enum(METADATA_TYPE_FIELD, metric_type)?;
+        os.write_string(METADATA_FAMILY_NAME_FIELD, name)?;
+        if !family.help.is_empty() {
+            os.write_string(METADATA_HELP_FIELD, &family.help)?;
+        }
+        if let Some(unit) = &family.unit {
+            os.write_string(METADATA_UNIT_FIELD, unit)?;
+        }
+        os.flush()?;
+    }
+    Ok(buf)
+}
+
+fn millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_millis() as i64)
+}
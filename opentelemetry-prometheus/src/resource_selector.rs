@@ -12,7 +12,7 @@ use std::collections::HashSet;
 /// You can add resource to every metrics by set `ResourceSelector` to anything other than `None`.
 ///
 /// By default, ResourceSelector is `None`, meaning resource will not be attributes of every metrics.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 #[non_exhaustive]
 pub enum ResourceSelector {
     /// Export all resource attributes with every metrics.
@@ -0,0 +1,316 @@
+//! A lightweight HTTP server exposing the collected metrics for scraping.
+use std::{
+    convert::Infallible,
+    fmt,
+    future::Future,
+    io::{self, Write},
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use flate2::{write::GzEncoder, Compression};
+use http_body_util::Full;
+use hyper::{
+    header::{self, HeaderMap, HeaderValue},
+    server::conn::http1,
+    service::Service,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use opentelemetry::global;
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+use crate::{
+    encoding::{negotiate, ExpositionFormat},
+    TextEncoder,
+};
+
+const DEFAULT_PATH: &str = "/metrics";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const SCRAPE_TIMEOUT_HEADER: &str = "x-prometheus-scrape-timeout-seconds";
+
+/// A [hyper] service responding to scrape requests with the metrics of a
+/// [PrometheusExporter].
+///
+/// The response format is negotiated with the `Accept` header of the request,
+/// see [ExpositionFormat::from_accept], and the response body is compressed
+/// with gzip if the request's `Accept-Encoding` header allows it. Collection
+/// is aborted with `503 Service Unavailable` once the scrape timeout elapses.
+///
+/// The service can be mounted in an existing hyper server, or served on its own
+/// with a [ScrapeServer].
+///
+/// [PrometheusExporter]: crate::PrometheusExporter
+#[derive(Clone)]
+pub struct ScrapeService {
+    encoder: TextEncoder,
+    path: Arc<str>,
+    timeout: Duration,
+    gzip: bool,
+}
+
+impl fmt::Debug for ScrapeService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScrapeService")
+            .field("path", &self.path)
+            .field("timeout", &self.timeout)
+            .field("gzip", &self.gzip)
+            .finish()
+    }
+}
+
+impl ScrapeService {
+    pub(crate) fn new(encoder: TextEncoder) -> Self {
+        ScrapeService {
+            encoder,
+            path: DEFAULT_PATH.into(),
+            timeout: DEFAULT_TIMEOUT,
+            gzip: true,
+        }
+    }
+
+    /// Sets the path metrics are served on.
+    ///
+    /// Default is `/metrics`, other paths are answered with `404 Not Found`.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into().into();
+        self
+    }
+
+    /// Sets the maximum duration of a scrape.
+    ///
+    /// Prometheus sends its own scrape timeout in the
+    /// `X-Prometheus-Scrape-Timeout-Seconds` header, the shorter of the two is
+    /// used. Default is 10 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Disables gzip compression of response bodies.
+    pub fn without_gzip(mut self) -> Self {
+        self.gzip = false;
+        self
+    }
+
+    async fn scrape(&self, headers: &HeaderMap) -> Response<Full<Bytes>> {
+        let format = headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .map(ExpositionFormat::from_accept)
+            .unwrap_or_default();
+
+        let encoder = self.encoder.clone();
+        let collect = tokio::task::spawn_blocking(move || {
+            let mut body = Vec::new();
+            encoder.encode(format, &mut body).map(|_| body)
+        });
+
+        let body = match tokio::time::timeout(self.scrape_timeout(headers), collect).await {
+            Ok(Ok(Ok(body))) => body,
+            Ok(Ok(Err(err))) => {
+                global::handle_error(err);
+                return status(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+            Ok(Err(_)) => return status(StatusCode::INTERNAL_SERVER_ERROR),
+            Err(_) => return status(StatusCode::SERVICE_UNAVAILABLE),
+        };
+
+        let mut response = Response::builder()
+            .header(header::CONTENT_TYPE, format.content_type())
+            .header(header::VARY, "Accept, Accept-Encoding");
+        let body = if self.gzip && accepts_gzip(headers) {
+            match gzip(&body) {
+                Ok(compressed) => {
+                    response = response.header(header::CONTENT_ENCODING, "gzip");
+                    compressed
+                }
+                Err(_) => body,
+            }
+        } else {
+            body
+        };
+
+        response
+            .body(Full::new(Bytes::from(body)))
+            .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    fn scrape_timeout(&self, headers: &HeaderMap) -> Duration {
+        headers
+            .get(SCRAPE_TIMEOUT_HEADER)
+            .and_then(|timeout| timeout.to_str().ok()?.trim().parse::<f64>().ok())
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            .map_or(self.timeout, |timeout| timeout.min(self.timeout))
+    }
+}
+
+impl<B> Service<Request<B>> for ScrapeService {
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn call(&self, req: Request<B>) -> Self::Future {
+        if req.uri().path() != &*self.path {
+            return Box::pin(async { Ok(status(StatusCode::NOT_FOUND)) });
+        }
+        if req.method() != Method::GET {
+            let mut response = status(StatusCode::METHOD_NOT_ALLOWED);
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static("GET"));
+            return Box::pin(async { Ok(response) });
+        }
+
+        let service = self.clone();
+        let headers = req.headers().clone();
+        Box::pin(async move { Ok(service.scrape(&headers).await) })
+    }
+}
+
+fn status(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(status.to_string())));
+    *response.status_mut() = status;
+    response
+}
+
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|accept_encoding| {
+            negotiate(accept_encoding, |coding| {
+                match coding.essence.to_ascii_lowercase().as_str() {
+                    "gzip" | "x-gzip" | "*" => Some(true),
+                    "identity" => Some(false),
+                    _ => None,
+                }
+            })
+        })
+        .next()
+        .unwrap_or(false)
+}
+
+fn gzip(body: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 4), Compression::fast());
+    encoder.write_all(body)?;
+    encoder.finish()
+}
+
+/// An HTTP/1 server serving a [ScrapeService].
+///
+/// # Examples
+///
+/// ```no_run
+/// use opentelemetry_prometheus::ScrapeServer;
+/// use opentelemetry_sdk::metrics::SdkMeterProvider;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let exporter = opentelemetry_prometheus::exporter().build()?;
+/// let service = exporter.scrape_service();
+/// let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+///
+/// let server = ScrapeServer::bind("0.0.0.0:9464", service).await?;
+/// tokio::spawn(server.run());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ScrapeServer {
+    listener: TcpListener,
+    service: ScrapeService,
+}
+
+impl ScrapeServer {
+    /// Binds a server serving `service` to `addr`.
+    pub async fn bind(addr: impl ToSocketAddrs, service: ScrapeService) -> io::Result<Self> {
+        Ok(ScrapeServer {
+            listener: TcpListener::bind(addr).await?,
+            service,
+        })
+    }
+
+    /// The address the server is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections until accepting fails, serving each connection on
+    /// its own task.
+    pub async fn run(self) -> io::Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            let service = self.service.clone();
+            tokio::spawn(async move {
+                // connection errors are the scraper's concern
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(k, v)| {
+                (
+                    header::HeaderName::from_static(k),
+                    HeaderValue::from_static(v),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scrape_timeout_is_the_shorter_one() {
+        let service = ScrapeService::new(crate::exporter().build().unwrap().text_encoder())
+            .with_timeout(Duration::from_secs(5));
+
+        assert_eq!(
+            service.scrape_timeout(&headers(&[])),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            service.scrape_timeout(&headers(&[(SCRAPE_TIMEOUT_HEADER, "2.5")])),
+            Duration::from_millis(2500)
+        );
+        assert_eq!(
+            service.scrape_timeout(&headers(&[(SCRAPE_TIMEOUT_HEADER, "30")])),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            service.scrape_timeout(&headers(&[(SCRAPE_TIMEOUT_HEADER, "-1")])),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn gzip_negotiation() {
+        for (accept_encoding, expected) in [
+            ("gzip", true),
+            ("deflate, gzip;q=0.5", true),
+            ("*", true),
+            ("br", false),
+            ("gzip;q=0", false),
+            ("identity, gzip;q=0.5", false),
+        ] {
+            assert_eq!(
+                accepts_gzip(&headers(&[("accept-encoding", accept_encoding)])),
+                expected,
+                "{accept_encoding}"
+            );
+        }
+        assert!(!accepts_gzip(&headers(&[])));
+    }
+}
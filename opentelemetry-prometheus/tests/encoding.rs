@@ -0,0 +1,190 @@
+use std::fs;
+
+use opentelemetry::metrics::MeterProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_prometheus::{ExporterBuilder, ExpositionFormat, TextEncoder};
+use opentelemetry_sdk::metrics::{new_view, Aggregation, Instrument, SdkMeterProvider, Stream};
+use opentelemetry_sdk::Resource;
+use prometheus::Encoder;
+
+fn setup(builder: ExporterBuilder) -> (prometheus::Registry, TextEncoder, SdkMeterProvider) {
+    let registry = prometheus::Registry::new();
+    let exporter = builder.with_registry(registry.clone()).build().unwrap();
+    let encoder = exporter.text_encoder();
+
+    let provider = SdkMeterProvider::builder()
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            "prometheus_test",
+        )]))
+        .with_reader(exporter)
+        .with_view(
+            new_view(
+                Instrument::new().name("histogram_*"),
+                Stream::new().aggregation(Aggregation::ExplicitBucketHistogram {
+                    boundaries: vec![0.0, 5.0, 10.0, 25.0, 50.0, 100.0],
+                    record_min_max: true,
+                }),
+            )
+            .unwrap(),
+        )
+        .build();
+
+    let meter = provider.versioned_meter(
+        "testmeter",
+        Some("v0.1.0"),
+        None::<&'static str>,
+        Some(vec![KeyValue::new("k", "v")]),
+    );
+
+    let counter = meter
+        .f64_counter("foo")
+        .with_description("a simple counter")
+        .with_unit("ms")
+        .init();
+    counter.add(5.0, &[KeyValue::new("A", "B")]);
+    counter.add(10.3, &[KeyValue::new("A", "B")]);
+    counter.add(5.0, &[KeyValue::new("A", "D")]);
+
+    let gauge = meter
+        .i64_up_down_counter("bar")
+        .with_description("a fun little \"gauge\"\nwith a new line")
+        .with_unit("1")
+        .init();
+    gauge.add(2, &[KeyValue::new("C", "quoted \"value\"")]);
+    gauge.add(-1, &[KeyValue::new("C", "quoted \"value\"")]);
+
+    let histogram = meter
+        .u64_histogram("histogram_baz")
+        .with_description("a very nice histogram")
+        .with_unit("By")
+        .init();
+    for value in [23, 7, 101, 105] {
+        histogram.record(value, &[KeyValue::new("A", "B")]);
+    }
+
+    (registry, encoder, provider)
+}
+
+fn encode(encoder: &TextEncoder, format: ExpositionFormat) -> String {
+    let mut output = Vec::new();
+    encoder.encode(format, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn prometheus_text_matches_registry() {
+    let builders: Vec<fn() -> ExporterBuilder> = vec![
+        ExporterBuilder::default,
+        || ExporterBuilder::default().without_units(),
+        || ExporterBuilder::default().without_counter_suffixes(),
+        || ExporterBuilder::default().without_scope_info(),
+        || ExporterBuilder::default().without_target_info(),
+        || ExporterBuilder::default().with_namespace("test"),
+        || {
+            ExporterBuilder::default()
+                .with_resource_selector(opentelemetry_prometheus::ResourceSelector::All)
+        },
+    ];
+
+    for builder in builders {
+        let (registry, encoder, _provider) = setup(builder());
+
+        let mut expected = Vec::new();
+        prometheus::TextEncoder::new()
+            .encode(&registry.gather(), &mut expected)
+            .unwrap();
+
+        assert_eq!(
+            encode(&encoder, ExpositionFormat::PrometheusText),
+            String::from_utf8(expected).unwrap()
+        );
+    }
+}
+
+#[test]
+fn encodes_without_registry() {
+    let exporter = ExporterBuilder::default().build().unwrap();
+    let encoder = exporter.text_encoder();
+    let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+    let counter = provider.meter("testmeter").u64_counter("requests").init();
+    counter.add(3, &[]);
+
+    let output = encode(&encoder, ExpositionFormat::PrometheusText);
+    assert!(output.contains("requests_total{otel_scope_name=\"testmeter\"} 3\n"));
+}
+
+#[test]
+fn openmetrics_text() {
+    let (_registry, encoder, _provider) = setup(ExporterBuilder::default());
+
+    // `_created` timestamps vary between runs
+    let output = encode(&encoder, ExpositionFormat::OpenMetrics)
+        .lines()
+        .filter(|line| !line.contains("_created"))
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+
+    let expected = fs::read_to_string("./tests/data/openmetrics.txt").unwrap();
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn openmetrics_created() {
+    let (_registry, encoder, _provider) = setup(ExporterBuilder::default());
+    let output = encode(&encoder, ExpositionFormat::OpenMetrics);
+
+    let created = output
+        .lines()
+        .filter(|line| line.contains("_created"))
+        .map(|line| line.split(' ').next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        created,
+        vec![
+            r#"foo_milliseconds_created{A="B",otel_scope_name="testmeter",otel_scope_version="v0.1.0"}"#,
+            r#"foo_milliseconds_created{A="D",otel_scope_name="testmeter",otel_scope_version="v0.1.0"}"#,
+            r#"histogram_baz_bytes_created{A="B",otel_scope_name="testmeter",otel_scope_version="v0.1.0"}"#,
+        ]
+    );
+}
+
+#[test]
+fn content_negotiation() {
+    let test_cases = vec![
+        ("", ExpositionFormat::PrometheusText),
+        ("*/*", ExpositionFormat::PrometheusText),
+        ("text/plain", ExpositionFormat::PrometheusText),
+        ("application/openmetrics-text", ExpositionFormat::OpenMetrics),
+        (
+            "application/openmetrics-text; version=0.0.1",
+            ExpositionFormat::PrometheusText,
+        ),
+        (
+            // sent by Prometheus 2.x
+            "application/openmetrics-text;version=1.0.0;q=0.5,application/openmetrics-text;version=0.0.1;q=0.4,text/plain;version=0.0.4;q=0.3,*/*;q=0.2",
+            ExpositionFormat::OpenMetrics,
+        ),
+        (
+            "text/plain;version=0.0.4;q=0.8,application/openmetrics-text;version=1.0.0;q=0.5",
+            ExpositionFormat::PrometheusText,
+        ),
+        (
+            "application/openmetrics-text;q=0,text/plain",
+            ExpositionFormat::PrometheusText,
+        ),
+    ];
+
+    for (accept, expected) in test_cases {
+        assert_eq!(ExpositionFormat::from_accept(accept), expected, "{accept}");
+    }
+
+    assert_eq!(
+        ExpositionFormat::OpenMetrics.content_type(),
+        "application/openmetrics-text; version=1.0.0; charset=utf-8"
+    );
+    assert_eq!(
+        ExpositionFormat::PrometheusText.content_type(),
+        "text/plain; version=0.0.4; charset=utf-8"
+    );
+}
@@ -13,7 +13,9 @@ use opentelemetry_sdk::resource::{
 };
 use opentelemetry_sdk::Resource;
 use opentelemetry_semantic_conventions::resource::{SERVICE_NAME, TELEMETRY_SDK_VERSION};
-use prometheus::{Encoder, TextEncoder};
+use prometheus::proto::MetricFamily;
+use prometheus::{Encoder, ProtobufEncoder, TextEncoder};
+use protobuf::{CodedInputStream, Message};
 
 #[ignore = "https://github.com/open-telemetry/opentelemetry-rust/pull/2224"]
 #[test]
@@ -479,6 +481,92 @@ fn multiple_scopes() {
     gather_and_compare(registry, content, "multi_scope");
 }
 
+fn exponential_histogram_provider(
+    builder: ExporterBuilder,
+) -> (prometheus::Registry, SdkMeterProvider) {
+    let registry = prometheus::Registry::new();
+    let exporter = builder
+        .without_scope_info()
+        .without_target_info()
+        .with_registry(registry.clone())
+        .build()
+        .unwrap();
+
+    let provider = SdkMeterProvider::builder()
+        .with_resource(Resource::empty())
+        .with_reader(exporter)
+        .with_view(
+            new_view(
+                Instrument::new().name("exponential_*"),
+                Stream::new().aggregation(Aggregation::Base2ExponentialHistogram {
+                    max_size: 160,
+                    max_scale: 0,
+                    record_min_max: true,
+                }),
+            )
+            .unwrap(),
+        )
+        .build();
+
+    let histogram = provider
+        .meter("testmeter")
+        .f64_histogram("exponential_histogram")
+        .with_description("an exponential histogram")
+        .init();
+    for value in [0.0, 1.0, 3.0, 5.0, 5.0] {
+        histogram.record(value, &[KeyValue::new("A", "B")]);
+    }
+
+    (registry, provider)
+}
+
+#[test]
+fn exponential_histogram_text() {
+    let (registry, _provider) = exponential_histogram_provider(ExporterBuilder::default());
+    let content = fs::read_to_string("./tests/data/exponential_histogram.txt").unwrap();
+    gather_and_compare(registry, content, "exponential_histogram");
+
+    let (registry, _provider) = exponential_histogram_provider(
+        ExporterBuilder::default().without_exponential_histogram_fallback(),
+    );
+    let content =
+        fs::read_to_string("./tests/data/exponential_histogram_without_fallback.txt").unwrap();
+    gather_and_compare(registry, content, "exponential_histogram_without_fallback");
+}
+
+#[test]
+fn exponential_histogram_native() {
+    let (registry, _provider) = exponential_histogram_provider(ExporterBuilder::default());
+
+    let mut output = Vec::new();
+    ProtobufEncoder::new()
+        .encode(&registry.gather(), &mut output)
+        .unwrap();
+    let mf: MetricFamily = CodedInputStream::from_bytes(&output)
+        .read_message()
+        .unwrap();
+    let histogram = mf.get_metric()[0].get_histogram();
+    assert_eq!(histogram.get_sample_count(), 5);
+    assert_eq!(histogram.get_sample_sum(), 14.0);
+
+    let fields = histogram.get_unknown_fields();
+    let field = |number| fields.get(number).unwrap();
+    // schema
+    assert_eq!(field(5).varint, vec![0]);
+    // zero_threshold
+    assert_eq!(field(6).fixed64, vec![0f64.to_bits()]);
+    // zero_count
+    assert_eq!(field(7).varint, vec![1]);
+    // positive_span: {offset: 0, length: 1}, {offset: 1, length: 2}
+    assert_eq!(
+        field(12).length_delimited,
+        vec![vec![0x08, 0x00, 0x10, 0x01], vec![0x08, 0x02, 0x10, 0x02]]
+    );
+    // positive_delta: zigzag encoded [1, 0, 1]
+    assert_eq!(field(13).varint, vec![2, 0, 2]);
+    assert!(fields.get(9).is_none());
+}
+
 #[test]
 fn duplicate_metrics() {
     struct TestCase {
@@ -0,0 +1,286 @@
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    time::{Duration, SystemTime},
+};
+
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    header::HeaderMap,
+    server::conn::http1,
+    service::service_fn,
+    Request, Response, StatusCode,
+};
+use hyper_util::{client::legacy::connect::HttpConnector, rt::TokioIo};
+use opentelemetry::KeyValue;
+use opentelemetry_http::hyper::HyperClient;
+use opentelemetry_prometheus::{RemoteWriteExporter, RemoteWriteExporterBuilder};
+use opentelemetry_sdk::{
+    metrics::{
+        data::{
+            DataPoint, Histogram, HistogramDataPoint, Metric, ResourceMetrics, ScopeMetrics, Sum,
+            Temporality,
+        },
+        exporter::PushMetricsExporter,
+    },
+    Resource, Scope,
+};
+use protobuf::{well_known_types::Empty, Message, UnknownFields};
+use tokio::{net::TcpListener, sync::mpsc};
+
+struct Received {
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// Starts a remote write receiver responding with `status`, forwarding the
+/// received requests to the returned channel.
+async fn receiver(status: StatusCode) -> (SocketAddr, mpsc::UnboundedReceiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let tx = tx.clone();
+            let service = service_fn(move |req: Request<Incoming>| {
+                let tx = tx.clone();
+                async move {
+                    let headers = req.headers().clone();
+                    let body = req.into_body().collect().await.unwrap().to_bytes();
+                    let _ = tx.send(Received { headers, body });
+                    let mut res = Response::new(Full::new(Bytes::new()));
+                    *res.status_mut() = status;
+                    Ok::<_, Infallible>(res)
+                }
+            });
+            tokio::spawn(http1::Builder::new().serve_connection(TokioIo::new(stream), service));
+        }
+    });
+
+    (addr, rx)
+}
+
+fn builder(addr: SocketAddr) -> RemoteWriteExporterBuilder {
+    opentelemetry_prometheus::exporter()
+        .remote_write(format!("http://{addr}/api/v1/write"))
+        .with_http_client(HyperClient::new(
+            HttpConnector::new(),
+            Duration::from_secs(5),
+            None,
+        ))
+}
+
+fn resource_metrics() -> ResourceMetrics {
+    ResourceMetrics {
+        resource: Resource::new([KeyValue::new("service.name", "test")]),
+        scope_metrics: vec![ScopeMetrics {
+            scope: Scope::builder("test").build(),
+            metrics: vec![
+                Metric {
+                    name: "http.requests".into(),
+                    description: "Counts requests".into(),
+                    unit: "".into(),
+                    data: Box::new(Sum {
+                        data_points: vec![DataPoint {
+                            attributes: vec![KeyValue::new("http.method", "GET")],
+                            start_time: Some(SystemTime::UNIX_EPOCH),
+                            time: Some(SystemTime::UNIX_EPOCH),
+                            value: 3u64,
+                            exemplars: vec![],
+                        }],
+                        temporality: Temporality::Cumulative,
+                        is_monotonic: true,
+                    }),
+                },
+                Metric {
+                    name: "http.duration".into(),
+                    description: "".into(),
+                    unit: "s".into(),
+                    data: Box::new(Histogram {
+                        data_points: vec![HistogramDataPoint {
+                            attributes: vec![],
+                            start_time: SystemTime::UNIX_EPOCH,
+                            time: SystemTime::UNIX_EPOCH,
+                            count: 2,
+                            bounds: vec![1.0],
+                            bucket_counts: vec![1, 1],
+                            min: None,
+                            max: None,
+                            sum: 2.5,
+                            exemplars: vec![],
+                        }],
+                        temporality: Temporality::Cumulative,
+                    }),
+                },
+            ],
+        }],
+    }
+}
+
+fn fields(bytes: &[u8]) -> UnknownFields {
+    Empty::parse_from_bytes(bytes)
+        .unwrap()
+        .get_unknown_fields()
+        .clone()
+}
+
+fn messages(fields: &UnknownFields, number: u32) -> Vec<UnknownFields> {
+    fields
+        .get(number)
+        .map(|values| {
+            values
+                .length_delimited
+                .iter()
+                .map(|m| self::fields(m))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn string(fields: &UnknownFields, number: u32) -> String {
+    String::from_utf8(fields.get(number).unwrap().length_delimited[0].clone()).unwrap()
+}
+
+/// Decodes the time series of a snappy compressed `WriteRequest` as their
+/// labels and sample value.
+fn time_series(body: &[u8]) -> Vec<(Vec<(String, String)>, f64)> {
+    let write_request = snap::raw::Decoder::new().decompress_vec(body).unwrap();
+    messages(&fields(&write_request), 1)
+        .iter()
+        .map(|ts| {
+            let labels = messages(ts, 1)
+                .iter()
+                .map(|label| (string(label, 1), string(label, 2)))
+                .collect();
+            let samples = messages(ts, 2);
+            assert_eq!(samples.len(), 1);
+            let value = f64::from_bits(samples[0].get(1).unwrap().fixed64[0]);
+            (labels, value)
+        })
+        .collect()
+}
+
+fn labels(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn pushes_snappy_compressed_write_request() {
+    let (addr, mut rx) = receiver(StatusCode::NO_CONTENT).await;
+    let exporter = builder(addr).with_bearer_token("secret").build().unwrap();
+
+    exporter.export(&mut resource_metrics()).await.unwrap();
+
+    let received = rx.recv().await.unwrap();
+    assert_eq!(received.headers["content-type"], "application/x-protobuf");
+    assert_eq!(received.headers["content-encoding"], "snappy");
+    assert_eq!(
+        received.headers["x-prometheus-remote-write-version"],
+        "0.1.0"
+    );
+    assert_eq!(received.headers["authorization"], "Bearer secret");
+
+    let scope = ("otel_scope_name", "test");
+    assert_eq!(
+        time_series(&received.body),
+        vec![
+            (
+                labels(&[
+                    ("__name__", "http_duration_seconds_bucket"),
+                    ("le", "1"),
+                    scope
+                ]),
+                1.0
+            ),
+            (
+                labels(&[
+                    ("__name__", "http_duration_seconds_bucket"),
+                    ("le", "+Inf"),
+                    scope
+                ]),
+                2.0
+            ),
+            (
+                labels(&[("__name__", "http_duration_seconds_sum"), scope]),
+                2.5
+            ),
+            (
+                labels(&[("__name__", "http_duration_seconds_count"), scope]),
+                2.0
+            ),
+            (
+                labels(&[
+                    ("__name__", "http_requests_total"),
+                    ("http_method", "GET"),
+                    scope,
+                ]),
+                3.0
+            ),
+            (
+                labels(&[("__name__", "target_info"), ("service_name", "test")]),
+                1.0
+            ),
+        ]
+    );
+
+    let write_request = snap::raw::Decoder::new()
+        .decompress_vec(&received.body)
+        .unwrap();
+    let metadata = messages(&fields(&write_request), 3)
+        .iter()
+        .map(|m| (m.get(1).unwrap().varint[0], string(m, 2)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        metadata,
+        vec![
+            (3, "http_duration_seconds".to_string()),
+            (1, "http_requests_total".to_string()),
+            (6, "target_info".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn basic_auth() {
+    let (addr, mut rx) = receiver(StatusCode::OK).await;
+    let exporter = builder(addr)
+        .with_basic_auth("user", "pass")
+        .build()
+        .unwrap();
+
+    exporter.export(&mut resource_metrics()).await.unwrap();
+
+    let received = rx.recv().await.unwrap();
+    assert_eq!(received.headers["authorization"], "Basic dXNlcjpwYXNz");
+}
+
+#[tokio::test]
+async fn receiver_errors_fail_the_export() {
+    let (addr, _rx) = receiver(StatusCode::BAD_REQUEST).await;
+    let exporter = builder(addr).build().unwrap();
+
+    assert!(exporter.export(&mut resource_metrics()).await.is_err());
+}
+
+#[tokio::test]
+async fn export_after_shutdown_fails() {
+    let (addr, _rx) = receiver(StatusCode::OK).await;
+    let exporter: RemoteWriteExporter = builder(addr).build().unwrap();
+
+    exporter.shutdown().unwrap();
+
+    assert!(exporter.export(&mut resource_metrics()).await.is_err());
+}
+
+#[test]
+fn build_requires_http_client() {
+    assert!(opentelemetry_prometheus::exporter()
+        .remote_write("http://localhost:9090/api/v1/write")
+        .build()
+        .is_err());
+}
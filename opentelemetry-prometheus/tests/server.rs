@@ -0,0 +1,171 @@
+use std::{io::Read, net::SocketAddr};
+
+use flate2::read::GzDecoder;
+use http_body_util::{BodyExt, Empty};
+use hyper::{body::Bytes, header, Method, Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use opentelemetry::metrics::MeterProvider;
+use opentelemetry_prometheus::{ScrapeServer, ScrapeService};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use tokio::net::TcpStream;
+
+struct Scrape {
+    status: StatusCode,
+    headers: header::HeaderMap,
+    body: Bytes,
+}
+
+async fn serve(
+    configure: impl FnOnce(ScrapeService) -> ScrapeService,
+) -> (SocketAddr, SdkMeterProvider) {
+    let exporter = opentelemetry_prometheus::exporter()
+        .without_scope_info()
+        .without_target_info()
+        .build()
+        .unwrap();
+    let service = configure(exporter.scrape_service());
+    let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+    provider
+        .meter("test")
+        .u64_counter("requests")
+        .with_description("Counts requests")
+        .init()
+        .add(3, &[]);
+
+    let server = ScrapeServer::bind("127.0.0.1:0", service).await.unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(server.run());
+
+    (addr, provider)
+}
+
+async fn scrape(addr: SocketAddr, method: Method, path: &str, headers: &[(&str, &str)]) -> Scrape {
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .unwrap();
+    tokio::spawn(conn);
+
+    let mut req = Request::builder().method(method).uri(path);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let res = sender
+        .send_request(req.body(Empty::<Bytes>::new()).unwrap())
+        .await
+        .unwrap();
+
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    Scrape {
+        status,
+        headers,
+        body,
+    }
+}
+
+const PROMETHEUS_TEXT: &str = "# HELP requests_total Counts requests\n\
+                               # TYPE requests_total counter\n\
+                               requests_total 3\n";
+
+#[tokio::test]
+async fn serves_prometheus_text_by_default() {
+    let (addr, _provider) = serve(|service| service).await;
+
+    let res = scrape(addr, Method::GET, "/metrics", &[]).await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(
+        res.headers[header::CONTENT_TYPE],
+        "text/plain; version=0.0.4; charset=utf-8"
+    );
+    assert!(res.headers.get(header::CONTENT_ENCODING).is_none());
+    assert_eq!(res.body, PROMETHEUS_TEXT);
+}
+
+#[tokio::test]
+async fn negotiates_openmetrics() {
+    let (addr, _provider) = serve(|service| service).await;
+
+    let res = scrape(
+        addr,
+        Method::GET,
+        "/metrics",
+        &[(
+            "accept",
+            "application/openmetrics-text;version=1.0.0;q=0.9,text/plain;version=0.0.4;q=0.5",
+        )],
+    )
+    .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(
+        res.headers[header::CONTENT_TYPE],
+        "application/openmetrics-text; version=1.0.0; charset=utf-8"
+    );
+    let body = std::str::from_utf8(&res.body).unwrap();
+    assert!(body.starts_with("# TYPE requests counter\n"), "{body}");
+    assert!(body.ends_with("# EOF\n"), "{body}");
+}
+
+#[tokio::test]
+async fn compresses_with_gzip() {
+    let (addr, _provider) = serve(|service| service).await;
+
+    let res = scrape(
+        addr,
+        Method::GET,
+        "/metrics",
+        &[("accept-encoding", "gzip")],
+    )
+    .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.headers[header::CONTENT_ENCODING], "gzip");
+    let mut body = String::new();
+    GzDecoder::new(&res.body[..])
+        .read_to_string(&mut body)
+        .unwrap();
+    assert_eq!(body, PROMETHEUS_TEXT);
+}
+
+#[tokio::test]
+async fn gzip_can_be_disabled() {
+    let (addr, _provider) = serve(ScrapeService::without_gzip).await;
+
+    let res = scrape(
+        addr,
+        Method::GET,
+        "/metrics",
+        &[("accept-encoding", "gzip")],
+    )
+    .await;
+
+    assert!(res.headers.get(header::CONTENT_ENCODING).is_none());
+    assert_eq!(res.body, PROMETHEUS_TEXT);
+}
+
+#[tokio::test]
+async fn custom_path() {
+    let (addr, _provider) = serve(|service| service.with_path("/custom")).await;
+
+    assert_eq!(
+        scrape(addr, Method::GET, "/custom", &[]).await.body,
+        PROMETHEUS_TEXT
+    );
+    assert_eq!(
+        scrape(addr, Method::GET, "/metrics", &[]).await.status,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn rejects_other_methods() {
+    let (addr, _provider) = serve(|service| service).await;
+
+    let res = scrape(addr, Method::POST, "/metrics", &[]).await;
+
+    assert_eq!(res.status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(res.headers[header::ALLOW], "GET");
+}
@@ -121,7 +121,7 @@ pub mod tonic {
                 severity_number: severity_number.into(),
                 severity_text: log_record.severity_text.map(Into::into).unwrap_or_default(),
                 body: log_record.body.clone().map(Into::into),
-                dropped_attributes_count: 0,
+                dropped_attributes_count: log_record.dropped_attributes_count(),
                 flags: trace_context
                     .map(|ctx| {
                         ctx.trace_flags
@@ -0,0 +1,334 @@
+//! The bounded queue shared by the batch span and log processors.
+use crate::self_metrics::ComponentMetrics;
+use opentelemetry::otel_warn;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// What a batch processor does with a new item when its queue is full.
+///
+/// Set with `with_queue_full_policy` on the trace or logs `BatchConfigBuilder`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub enum QueueFullPolicy {
+    /// Drops the new item. This is the default.
+    #[default]
+    DropNewest,
+    /// Drops the oldest queued item to make room for the new one, favoring
+    /// recent telemetry.
+    DropOldest,
+    /// Blocks the thread ending the span or emitting the record until there
+    /// is room in the queue, for at most the given duration, then drops the
+    /// new item.
+    ///
+    /// The processor exports from another task or thread, but with a
+    /// single-threaded async runtime, blocking its only thread stalls the
+    /// export until the timeout.
+    Block(Duration),
+    /// Keeps a decreasing share of the new items once the queue is more than
+    /// half full: all items are kept at half capacity, none when the queue is
+    /// full. This spreads drops over a burst instead of losing its tail.
+    Sample,
+}
+
+/// A handle on the counters of a batch processor queue.
+///
+/// It can be cloned and kept after the processor is moved into a provider.
+#[derive(Clone, Debug, Default)]
+pub struct QueueStats {
+    inner: Arc<QueueCounters>,
+}
+
+#[derive(Debug, Default)]
+struct QueueCounters {
+    len: AtomicUsize,
+    dropped: AtomicU64,
+}
+
+impl QueueStats {
+    /// The number of items waiting in the queue.
+    pub fn queued(&self) -> usize {
+        self.inner.len.load(Ordering::Relaxed)
+    }
+
+    /// The number of items dropped because the queue was full, in total.
+    pub fn dropped(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "experimental_self_metrics")]
+    pub(crate) fn downgrade(&self) -> WeakQueueStats {
+        WeakQueueStats(Arc::downgrade(&self.inner))
+    }
+}
+
+/// A [`QueueStats`] that does not keep the counters alive.
+#[cfg(feature = "experimental_self_metrics")]
+#[derive(Debug)]
+pub(crate) struct WeakQueueStats(std::sync::Weak<QueueCounters>);
+
+#[cfg(feature = "experimental_self_metrics")]
+impl WeakQueueStats {
+    pub(crate) fn upgrade(&self) -> Option<QueueStats> {
+        self.0.upgrade().map(|inner| QueueStats { inner })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BatchQueue<T> {
+    name: &'static str,
+    items: Mutex<VecDeque<T>>,
+    not_full: Condvar,
+    capacity: usize,
+    policy: QueueFullPolicy,
+    stats: QueueStats,
+    sequence: AtomicUsize,
+    export_requested: AtomicBool,
+    metrics: ComponentMetrics,
+}
+
+impl<T> BatchQueue<T> {
+    /// Create a new queue. `name` identifies the processor in internal logs,
+    /// `metrics` in self-metrics.
+    pub(crate) fn new(
+        name: &'static str,
+        capacity: usize,
+        policy: QueueFullPolicy,
+        metrics: ComponentMetrics,
+    ) -> Self {
+        let stats = QueueStats::default();
+        metrics.register_queue(&stats, capacity);
+        BatchQueue {
+            name,
+            items: Mutex::new(VecDeque::new()),
+            not_full: Condvar::new(),
+            capacity,
+            policy,
+            stats,
+            sequence: AtomicUsize::new(0),
+            export_requested: AtomicBool::new(false),
+            metrics,
+        }
+    }
+
+    pub(crate) fn stats(&self) -> &QueueStats {
+        &self.stats
+    }
+
+    pub(crate) fn metrics(&self) -> &ComponentMetrics {
+        &self.metrics
+    }
+
+    /// Queues `item` following the queue full policy, returning the queue
+    /// length, or `None` if the item was dropped.
+    pub(crate) fn push(&self, item: T) -> Option<usize> {
+        let Ok(mut items) = self.items.lock() else {
+            self.record_drop();
+            return None;
+        };
+
+        match self.policy {
+            QueueFullPolicy::DropNewest => {}
+            QueueFullPolicy::DropOldest => {
+                if items.len() >= self.capacity {
+                    items.pop_front();
+                    self.record_drop();
+                }
+            }
+            QueueFullPolicy::Block(timeout) => {
+                if items.len() >= self.capacity {
+                    match self
+                        .not_full
+                        .wait_timeout_while(items, timeout, |items| items.len() >= self.capacity)
+                    {
+                        Ok((guard, _)) => items = guard,
+                        Err(_) => {
+                            self.record_drop();
+                            return None;
+                        }
+                    }
+                }
+            }
+            QueueFullPolicy::Sample => {
+                let half = (self.capacity / 2).max(1);
+                let free = self.capacity.saturating_sub(items.len());
+                // keep `free` out of every `half` items, evenly spread
+                if free < half && self.sequence.fetch_add(1, Ordering::Relaxed) % half >= free {
+                    self.record_drop();
+                    return None;
+                }
+            }
+        }
+
+        if items.len() >= self.capacity {
+            self.record_drop();
+            return None;
+        }
+        items.push_back(item);
+        self.stats.inner.len.store(items.len(), Ordering::Relaxed);
+        Some(items.len())
+    }
+
+    /// Removes up to `max` items from the front of the queue.
+    pub(crate) fn pop_batch(&self, max: usize) -> Vec<T> {
+        let Ok(mut items) = self.items.lock() else {
+            return Vec::new();
+        };
+        let count = max.min(items.len());
+        let batch = items.drain(..count).collect();
+        self.stats.inner.len.store(items.len(), Ordering::Relaxed);
+        drop(items);
+        self.not_full.notify_all();
+        batch
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.stats.queued()
+    }
+
+    /// Returns whether the caller should wake up the exporting task, which
+    /// is the case once per batch until [`Self::clear_export_request`].
+    pub(crate) fn request_export(&self) -> bool {
+        !self.export_requested.swap(true, Ordering::AcqRel)
+    }
+
+    pub(crate) fn clear_export_request(&self) {
+        self.export_requested.store(false, Ordering::Release);
+    }
+
+    /// Reports the total count of dropped items through internal logs.
+    pub(crate) fn report_dropped(&self) {
+        let dropped = self.stats.dropped();
+        if dropped > 0 {
+            otel_warn!(
+                name: "BatchProcessor.ItemsDropped",
+                processor = self.name,
+                dropped_count = dropped
+            );
+        }
+    }
+
+    fn record_drop(&self) {
+        self.metrics.record_dropped(1);
+        // Only the first drop is logged, the total is reported on shutdown.
+        if self.stats.inner.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+            otel_warn!(
+                name: "BatchProcessor.ItemDroppingStarted",
+                processor = self.name,
+                policy = format!("{:?}", self.policy),
+                message = "The queue is full, items are dropped. The count is reported on shutdown."
+            );
+        }
+    }
+}
+
+/// Sends `message` to a processor thread, waiting for room in its channel
+/// until `deadline`. Returns `TrySendError::Full` if the deadline passed.
+#[cfg(any(
+    feature = "experimental_trace_batch_span_processor_no_runtime",
+    feature = "experimental_logs_batch_log_processor_no_runtime"
+))]
+pub(crate) fn send_until<T>(
+    sender: &std::sync::mpsc::SyncSender<T>,
+    mut message: T,
+    deadline: std::time::Instant,
+) -> Result<(), std::sync::mpsc::TrySendError<T>> {
+    use std::sync::mpsc::TrySendError;
+
+    loop {
+        match sender.try_send(message) {
+            Err(TrySendError::Full(returned)) if std::time::Instant::now() < deadline => {
+                message = returned;
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::self_metrics::Signal;
+    use std::thread;
+    use std::time::Instant;
+
+    fn test_metrics() -> ComponentMetrics {
+        ComponentMetrics::new(Signal::Traces, "test_processor")
+    }
+
+    fn filled(policy: QueueFullPolicy, capacity: usize) -> BatchQueue<usize> {
+        let queue = BatchQueue::new("test", capacity, policy, test_metrics());
+        for i in 0..capacity {
+            assert_eq!(queue.push(i), Some(i + 1));
+        }
+        queue
+    }
+
+    #[test]
+    fn drop_newest_keeps_queued_items() {
+        let queue = filled(QueueFullPolicy::DropNewest, 3);
+        assert_eq!(queue.push(3), None);
+        assert_eq!(queue.stats().dropped(), 1);
+        assert_eq!(queue.pop_batch(10), [0, 1, 2]);
+        assert_eq!(queue.stats().queued(), 0);
+    }
+
+    #[test]
+    fn drop_oldest_keeps_new_items() {
+        let queue = filled(QueueFullPolicy::DropOldest, 3);
+        assert_eq!(queue.push(3), Some(3));
+        assert_eq!(queue.push(4), Some(3));
+        assert_eq!(queue.stats().dropped(), 2);
+        assert_eq!(queue.pop_batch(10), [2, 3, 4]);
+    }
+
+    #[test]
+    fn block_waits_for_room() {
+        let queue = Arc::new(filled(QueueFullPolicy::Block(Duration::from_secs(10)), 2));
+        let consumer = {
+            let queue = queue.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                queue.pop_batch(1)
+            })
+        };
+        assert_eq!(queue.push(2), Some(2));
+        assert_eq!(consumer.join().unwrap(), [0]);
+        assert_eq!(queue.stats().dropped(), 0);
+
+        let queue = filled(QueueFullPolicy::Block(Duration::from_millis(20)), 2);
+        let start = Instant::now();
+        assert_eq!(queue.push(2), None);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(queue.stats().dropped(), 1);
+    }
+
+    #[test]
+    fn sample_drops_a_growing_share_above_half_capacity() {
+        let queue = BatchQueue::new("test", 100, QueueFullPolicy::Sample, test_metrics());
+        for i in 0..50 {
+            assert!(queue.push(i).is_some());
+        }
+        let mut kept = 0;
+        for i in 0..1000 {
+            if queue.push(i).is_some() {
+                kept += 1;
+            }
+        }
+        assert_eq!(queue.len(), 100);
+        assert_eq!(kept, 50);
+        assert_eq!(queue.stats().dropped(), 950);
+    }
+
+    #[test]
+    fn export_is_requested_once_until_cleared() {
+        let queue =
+            BatchQueue::<usize>::new("test", 10, QueueFullPolicy::DropNewest, test_metrics());
+        assert!(queue.request_export());
+        assert!(!queue.request_export());
+        queue.clear_export_request();
+        assert!(queue.request_export());
+    }
+}
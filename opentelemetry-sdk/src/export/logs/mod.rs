@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use opentelemetry::logs::Severity;
 use opentelemetry::InstrumentationScope;
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// A batch of log records to be exported by a `LogExporter`.
 ///
@@ -83,6 +84,18 @@ pub trait LogExporter: Send + Sync + Debug {
     /// `Ok(())`) or an error (`Err(LogError)`) if the export operation failed.
     ///
     async fn export(&mut self, batch: LogBatch<'_>) -> LogResult<()>;
+    /// Flushes any log records buffered by the exporter, within `timeout`.
+    ///
+    /// This is called by the processors on [`LogProcessor::force_flush`], after
+    /// they have exported the records they buffer themselves. Exporters
+    /// writing to a buffered destination, such as a file or a stream, should
+    /// implement it so that `LoggerProvider::force_flush` guarantees that the
+    /// records emitted before it are delivered.
+    ///
+    /// [`LogProcessor::force_flush`]: crate::logs::LogProcessor::force_flush
+    fn force_flush(&mut self, _timeout: Duration) -> LogResult<()> {
+        Ok(())
+    }
     /// Shuts down the exporter.
     fn shutdown(&mut self) {}
     #[cfg(feature = "spec_unstable_logs_enabled")]
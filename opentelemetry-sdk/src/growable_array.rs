@@ -106,6 +106,16 @@ impl<
                 .chain(self.overflow.as_ref().unwrap().iter())
         }
     }
+
+    /// Returns an iterator over mutable references to the elements in the `GrowableArray`.
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.inline
+            .iter_mut()
+            .take(self.count)
+            .chain(self.overflow.iter_mut().flatten())
+    }
 }
 
 // Implement `IntoIterator` for `GrowableArray`
@@ -232,6 +242,18 @@ mod tests {
         assert_eq!(count, 15);
     }
 
+    #[test]
+    fn test_mut_iter() {
+        let mut collection = GrowableArray::<i32>::new();
+        for i in 0..15 {
+            collection.push(i);
+        }
+        for value in collection.iter_mut() {
+            *value *= 2;
+        }
+        assert!(collection.iter().copied().eq((0..15).map(|i| i * 2)));
+    }
+
     #[test]
     fn test_key_value_pair_storage_growable_array() {
         let mut collection = GrowableArray::<KeyValuePair>::new();
@@ -120,6 +120,8 @@
 )]
 #![cfg_attr(test, deny(warnings))]
 
+#[cfg(any(feature = "trace", feature = "logs"))]
+mod batch_queue;
 pub mod export;
 pub(crate) mod growable_array;
 
@@ -132,8 +134,11 @@ pub mod metrics;
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 pub mod propagation;
+#[cfg(feature = "redaction")]
+mod redaction;
 pub mod resource;
 pub mod runtime;
+pub mod self_metrics;
 #[cfg(any(feature = "testing", test))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "testing", test))))]
 pub mod testing;
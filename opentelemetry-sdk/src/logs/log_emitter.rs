@@ -1,7 +1,11 @@
-use super::{BatchLogProcessor, LogProcessor, LogRecord, SimpleLogProcessor, TraceContext};
+use super::{
+    BatchLogProcessor, LogProcessor, LogRecord, LogRecordLimits, SimpleLogProcessor, TraceContext,
+};
 use crate::{export::logs::LogExporter, runtime::RuntimeChannel, Resource};
 use crate::{logs::LogError, logs::LogResult};
-use opentelemetry::{otel_debug, trace::TraceContextExt, Context, InstrumentationScope};
+use opentelemetry::{
+    logs::LevelControl, otel_debug, trace::TraceContextExt, Context, InstrumentationScope,
+};
 
 #[cfg(feature = "spec_unstable_logs_enabled")]
 use opentelemetry::logs::Severity;
@@ -9,6 +13,8 @@ use opentelemetry::logs::Severity;
 use std::time::SystemTime;
 use std::{
     borrow::Cow,
+    env,
+    str::FromStr,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -22,6 +28,8 @@ static NOOP_LOGGER_PROVIDER: Lazy<LoggerProvider> = Lazy::new(|| LoggerProvider
     inner: Arc::new(LoggerProviderInner {
         processors: Vec::new(),
         resource: Resource::empty(),
+        log_record_limits: Arc::new(LogRecordLimits::default()),
+        level_control: None,
         is_shutdown: AtomicBool::new(true),
     }),
 });
@@ -84,6 +92,14 @@ impl LoggerProvider {
         &self.inner.resource
     }
 
+    pub(crate) fn log_record_limits(&self) -> &Arc<LogRecordLimits> {
+        &self.inner.log_record_limits
+    }
+
+    pub(crate) fn level_control(&self) -> Option<&LevelControl> {
+        self.inner.level_control.as_ref()
+    }
+
     /// Force flush all remaining logs in log processors and return results.
     pub fn force_flush(&self) -> Vec<LogResult<()>> {
         self.log_processors()
@@ -117,6 +133,8 @@ impl LoggerProvider {
 struct LoggerProviderInner {
     processors: Vec<Box<dyn LogProcessor>>,
     resource: Resource,
+    log_record_limits: Arc<LogRecordLimits>,
+    level_control: Option<LevelControl>,
     is_shutdown: AtomicBool,
 }
 
@@ -163,11 +181,42 @@ impl Drop for LoggerProviderInner {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 /// Builder for provider attributes.
 pub struct Builder {
     processors: Vec<Box<dyn LogProcessor>>,
     resource: Option<Resource>,
+    log_record_limits: LogRecordLimits,
+    level_control: Option<LevelControl>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        let mut log_record_limits = LogRecordLimits::default();
+
+        if let Some(max_attributes_per_log_record) =
+            env::var("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT")
+                .ok()
+                .and_then(|count_limit| u32::from_str(&count_limit).ok())
+        {
+            log_record_limits.max_attributes_per_log_record = max_attributes_per_log_record;
+        }
+
+        if let Some(max_attribute_value_length) =
+            env::var("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT")
+                .ok()
+                .and_then(|length_limit| u32::from_str(&length_limit).ok())
+        {
+            log_record_limits.max_attribute_value_length = Some(max_attribute_value_length);
+        }
+
+        Builder {
+            processors: Vec::new(),
+            resource: None,
+            log_record_limits,
+            level_control: None,
+        }
+    }
 }
 
 impl Builder {
@@ -205,6 +254,41 @@ impl Builder {
         }
     }
 
+    /// Specify the number of attributes that can be added to a log record.
+    pub fn with_max_attributes_per_log_record(mut self, max_attributes: u32) -> Self {
+        self.log_record_limits.max_attributes_per_log_record = max_attributes;
+        self
+    }
+
+    /// Specify the max length of string and byte attribute values.
+    pub fn with_max_attribute_value_length(mut self, max_length: u32) -> Self {
+        self.log_record_limits.max_attribute_value_length = Some(max_length);
+        self
+    }
+
+    /// Specify the max nesting depth of maps and lists in attribute values
+    /// and bodies.
+    pub fn with_max_attribute_nesting_depth(mut self, max_depth: u32) -> Self {
+        self.log_record_limits.max_attribute_nesting_depth = max_depth;
+        self
+    }
+
+    /// Specify all limits via the log record limits.
+    pub fn with_log_record_limits(mut self, log_record_limits: LogRecordLimits) -> Self {
+        self.log_record_limits = log_record_limits;
+        self
+    }
+
+    /// Drop the log records disabled by `level_control`, by severity and
+    /// target, or by severity and logger name for records without a target.
+    ///
+    /// The same `LevelControl` can be given to the log bridges so that they
+    /// skip the creation of disabled records.
+    pub fn with_level_control(mut self, level_control: LevelControl) -> Self {
+        self.level_control = Some(level_control);
+        self
+    }
+
     /// Create a new provider from this configuration.
     pub fn build(self) -> LoggerProvider {
         let resource = self.resource.unwrap_or_default();
@@ -213,6 +297,8 @@ impl Builder {
             inner: Arc::new(LoggerProviderInner {
                 processors: self.processors,
                 resource,
+                log_record_limits: Arc::new(self.log_record_limits),
+                level_control: self.level_control,
                 is_shutdown: AtomicBool::new(false),
             }),
         };
@@ -254,7 +340,10 @@ impl opentelemetry::logs::Logger for Logger {
     type LogRecord = LogRecord;
 
     fn create_log_record(&self) -> Self::LogRecord {
-        LogRecord::default()
+        LogRecord {
+            limits: Some(self.provider.log_record_limits().clone()),
+            ..Default::default()
+        }
     }
 
     /// Emit a `LogRecord`.
@@ -262,6 +351,18 @@ impl opentelemetry::logs::Logger for Logger {
         let provider = self.provider();
         let processors = provider.log_processors();
 
+        if let (Some(level_control), Some(severity)) =
+            (provider.level_control(), record.severity_number)
+        {
+            let target = record
+                .target
+                .as_deref()
+                .unwrap_or_else(|| self.instrumentation_scope().name());
+            if !level_control.is_enabled(severity, target) {
+                return;
+            }
+        }
+
         //let mut log_record = record;
         if record.trace_context.is_none() {
             let trace_context = Context::map_current(|cx| {
@@ -285,6 +386,11 @@ impl opentelemetry::logs::Logger for Logger {
     #[cfg(feature = "spec_unstable_logs_enabled")]
     fn event_enabled(&self, level: Severity, target: &str) -> bool {
         let provider = self.provider();
+        if let Some(level_control) = provider.level_control() {
+            if !level_control.is_enabled(level, target) {
+                return false;
+            }
+        }
 
         let mut enabled = false;
         for processor in provider.log_processors() {
@@ -540,6 +646,120 @@ mod tests {
         });
     }
 
+    #[test]
+    fn log_record_limits_test() {
+        let exporter = InMemoryLogExporter::default();
+        let logger_provider = temp_env::with_vars(
+            [
+                ("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", Some("2")),
+                ("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("4")),
+            ],
+            || {
+                LoggerProvider::builder()
+                    .with_simple_exporter(exporter.clone())
+                    .build()
+            },
+        );
+        assert_eq!(
+            **logger_provider.log_record_limits(),
+            LogRecordLimits {
+                max_attributes_per_log_record: 2,
+                max_attribute_value_length: Some(4),
+                ..Default::default()
+            }
+        );
+
+        let logger = logger_provider.logger("test-logger");
+        let mut record = logger.create_log_record();
+        record.add_attributes([("a", "value"), ("b", "value"), ("c", "value")]);
+        logger.emit(record);
+
+        let emitted = exporter.get_emitted_logs().unwrap();
+        let attributes: Vec<_> = emitted[0].record.attributes_iter().cloned().collect();
+        assert_eq!(
+            attributes,
+            vec![
+                (Key::new("a"), AnyValue::from("valu")),
+                (Key::new("b"), AnyValue::from("valu"))
+            ]
+        );
+        assert_eq!(emitted[0].record.dropped_attributes_count(), 1);
+    }
+
+    #[test]
+    fn level_control_test() {
+        use opentelemetry::logs::Severity;
+
+        let exporter = InMemoryLogExporter::default();
+        let level_control = LevelControl::new("info,my_crate::db=debug").unwrap();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .with_level_control(level_control.clone())
+            .build();
+        let logger = logger_provider.logger("my_crate");
+
+        let emit = |target: Option<&'static str>, severity: Severity| {
+            let mut record = logger.create_log_record();
+            if let Some(target) = target {
+                record.set_target(target);
+            }
+            record.set_severity_number(severity);
+            logger.emit(record);
+        };
+
+        emit(Some("my_crate::db"), Severity::Debug);
+        emit(Some("my_crate::http"), Severity::Debug);
+        emit(None, Severity::Info);
+        level_control.set_directives("warn").unwrap();
+        emit(Some("my_crate::db"), Severity::Debug);
+        emit(None, Severity::Warn);
+
+        let emitted = exporter.get_emitted_logs().unwrap();
+        let emitted: Vec<_> = emitted
+            .iter()
+            .map(|log| (log.record.target.as_deref(), log.record.severity_number))
+            .collect();
+        assert_eq!(
+            emitted,
+            vec![
+                (Some("my_crate::db"), Some(Severity::Debug)),
+                (None, Some(Severity::Info)),
+                (None, Some(Severity::Warn)),
+            ]
+        );
+    }
+
+    #[test]
+    fn global_logger_provider_test() {
+        let exporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let _ = opentelemetry::global::set_logger_provider(logger_provider.clone());
+
+        let logger = opentelemetry::global::logger("test-logger");
+        let mut record = logger.create_log_record();
+        record.set_body(AnyValue::String("global".into()));
+        record.add_attribute("key", "value");
+        logger.emit(record);
+
+        let emitted = exporter.get_emitted_logs().unwrap();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(
+            Some(AnyValue::String("global".into())),
+            emitted[0].record.body
+        );
+        assert!(emitted[0]
+            .record
+            .attributes_contains(&Key::new("key"), &AnyValue::String("value".into())));
+        assert_eq!(emitted[0].instrumentation.name(), "test-logger");
+
+        let _ = opentelemetry::global::set_logger_provider(
+            opentelemetry::logs::NoopLoggerProvider::new(),
+        );
+        let _ = logger_provider.shutdown();
+    }
+
     #[test]
     fn shutdown_test() {
         let counter = Arc::new(AtomicU64::new(0));
@@ -629,6 +849,8 @@ mod tests {
                     flush_called.clone(),
                 ))],
                 resource: Resource::empty(),
+                log_record_limits: Arc::new(LogRecordLimits::default()),
+                level_control: None,
                 is_shutdown: AtomicBool::new(false),
             });
 
@@ -670,6 +892,8 @@ mod tests {
                 flush_called.clone(),
             ))],
             resource: Resource::empty(),
+            log_record_limits: Arc::new(LogRecordLimits::default()),
+            level_control: None,
             is_shutdown: AtomicBool::new(false),
         });
 
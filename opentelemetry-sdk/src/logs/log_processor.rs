@@ -1,7 +1,9 @@
 use crate::{
+    batch_queue::{BatchQueue, QueueFullPolicy, QueueStats},
     export::logs::{ExportResult, LogBatch, LogExporter},
     logs::{LogError, LogRecord, LogResult},
     runtime::{RuntimeChannel, TrySend},
+    self_metrics::{ComponentMetrics, Signal},
     Resource,
 };
 use futures_channel::oneshot;
@@ -80,13 +82,16 @@ pub trait LogProcessor: Send + Sync + Debug {
 pub struct SimpleLogProcessor {
     exporter: Mutex<Box<dyn LogExporter>>,
     is_shutdown: AtomicBool,
+    metrics: ComponentMetrics,
 }
 
 impl SimpleLogProcessor {
-    pub(crate) fn new(exporter: Box<dyn LogExporter>) -> Self {
+    /// Create a new `SimpleLogProcessor` exporting every record with `exporter`.
+    pub fn new(exporter: Box<dyn LogExporter>) -> Self {
         SimpleLogProcessor {
             exporter: Mutex::new(exporter),
             is_shutdown: AtomicBool::new(false),
+            metrics: ComponentMetrics::new(Signal::Logs, "simple_log_processor"),
         }
     }
 }
@@ -102,6 +107,7 @@ impl LogProcessor for SimpleLogProcessor {
             return;
         }
 
+        let timer = self.metrics.start_export();
         let result = self
             .exporter
             .lock()
@@ -110,6 +116,7 @@ impl LogProcessor for SimpleLogProcessor {
                 let log_tuple = &[(record as &LogRecord, instrumentation)];
                 futures_executor::block_on(exporter.export(LogBatch::new(log_tuple)))
             });
+        timer.finish(1, &result);
         // Handle errors with specific static names
         match result {
             Err(LogError::MutexPoisoned(_)) => {
@@ -129,7 +136,12 @@ impl LogProcessor for SimpleLogProcessor {
     }
 
     fn force_flush(&self) -> LogResult<()> {
-        Ok(())
+        self.exporter
+            .lock()
+            .map_err(|_| LogError::MutexPoisoned("SimpleLogProcessor".into()))
+            .and_then(|mut exporter| {
+                exporter.force_flush(Duration::from_millis(OTEL_BLRP_EXPORT_TIMEOUT_DEFAULT))
+            })
     }
 
     fn shutdown(&self) -> LogResult<()> {
@@ -154,25 +166,32 @@ impl LogProcessor for SimpleLogProcessor {
 /// them at a pre-configured interval.
 pub struct BatchLogProcessor<R: RuntimeChannel> {
     message_sender: R::Sender<BatchMessage>,
+    queue: Arc<BatchQueue<(LogRecord, InstrumentationScope)>>,
+    max_export_batch_size: usize,
 }
 
 impl<R: RuntimeChannel> Debug for BatchLogProcessor<R> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("BatchLogProcessor")
             .field("message_sender", &self.message_sender)
+            .field("queue_stats", self.queue.stats())
             .finish()
     }
 }
 
 impl<R: RuntimeChannel> LogProcessor for BatchLogProcessor<R> {
     fn emit(&self, record: &mut LogRecord, instrumentation: &InstrumentationScope) {
-        let result = self.message_sender.try_send(BatchMessage::ExportLog((
-            record.clone(),
-            instrumentation.clone(),
-        )));
+        let Some(len) = self.queue.push((record.clone(), instrumentation.clone())) else {
+            return;
+        };
+        if len < self.max_export_batch_size || !self.queue.request_export() {
+            return;
+        }
+
+        let result = self.message_sender.try_send(BatchMessage::ExportLogs);
 
-        // TODO - Implement throttling to prevent error flooding when the queue is full or closed.
         if let Err(err) = result {
+            self.queue.clear_export_request();
             otel_error!(
                 name: "BatchLogProcessor.Export.Error",
                 error = format!("{}", err)
@@ -214,6 +233,14 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
     pub(crate) fn new(mut exporter: Box<dyn LogExporter>, config: BatchConfig, runtime: R) -> Self {
         let (message_sender, message_receiver) =
             runtime.batch_message_channel(config.max_queue_size);
+        let queue = Arc::new(BatchQueue::new(
+            "BatchLogProcessor",
+            config.max_queue_size,
+            config.queue_full_policy,
+            ComponentMetrics::new(Signal::Logs, "batching_log_processor"),
+        ));
+        let max_export_batch_size = config.max_export_batch_size.max(1);
+        let worker_queue = queue.clone();
         let inner_runtime = runtime.clone();
 
         // Spawn worker process via user-defined spawn function.
@@ -225,42 +252,45 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
                 .skip(1) // The ticker is fired immediately, so we should skip the first one to align with the interval.
                 .map(|_| BatchMessage::Flush(None));
             let timeout_runtime = inner_runtime.clone();
-            let mut logs = Vec::new();
             let mut messages = Box::pin(stream::select(message_receiver, ticker));
 
             while let Some(message) = messages.next().await {
                 match message {
-                    // Log has finished, add to buffer of pending logs.
-                    BatchMessage::ExportLog(log) => {
-                        logs.push(log);
-                        if logs.len() == config.max_export_batch_size {
-                            let result = export_with_timeout(
-                                config.max_export_timeout,
-                                exporter.as_mut(),
-                                &timeout_runtime,
-                                logs.split_off(0),
-                            )
-                            .await;
-
-                            if let Err(err) = result {
-                                otel_error!(
-                                    name: "BatchLogProcessor.Export.Error",
-                                    error = format!("{}", err)
-                                );
-                            }
+                    // A batch of logs is ready in the queue.
+                    BatchMessage::ExportLogs => {
+                        let result = export_queued(
+                            &worker_queue,
+                            config.max_export_batch_size,
+                            false,
+                            config.max_export_timeout,
+                            exporter.as_mut(),
+                            &timeout_runtime,
+                        )
+                        .await;
+
+                        if let Err(err) = result {
+                            otel_error!(
+                                name: "BatchLogProcessor.Export.Error",
+                                error = format!("{}", err)
+                            );
                         }
                     }
                     // Log batch interval time reached or a force flush has been invoked, export current spans.
                     BatchMessage::Flush(res_channel) => {
-                        let result = export_with_timeout(
+                        let result = export_queued(
+                            &worker_queue,
+                            config.max_export_batch_size,
+                            true,
                             config.max_export_timeout,
                             exporter.as_mut(),
                             &timeout_runtime,
-                            logs.split_off(0),
                         )
                         .await;
 
                         if let Some(channel) = res_channel {
+                            // a force flush also drains the exporter's own buffers
+                            let result = result
+                                .and_then(|_| exporter.force_flush(config.max_export_timeout));
                             if let Err(send_error) = channel.send(result) {
                                 otel_debug!(
                                     name: "BatchLogProcessor.Flush.SendResultError",
@@ -271,15 +301,18 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
                     }
                     // Stream has terminated or processor is shutdown, return to finish execution.
                     BatchMessage::Shutdown(ch) => {
-                        let result = export_with_timeout(
+                        let result = export_queued(
+                            &worker_queue,
+                            config.max_export_batch_size,
+                            true,
                             config.max_export_timeout,
                             exporter.as_mut(),
                             &timeout_runtime,
-                            logs.split_off(0),
                         )
                         .await;
 
                         exporter.shutdown();
+                        worker_queue.report_dropped();
 
                         if let Err(send_error) = ch.send(result) {
                             otel_debug!(
@@ -297,7 +330,17 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
             }
         }));
         // Return batch processor with link to worker
-        BatchLogProcessor { message_sender }
+        BatchLogProcessor {
+            message_sender,
+            queue,
+            max_export_batch_size,
+        }
+    }
+
+    /// Returns a handle on the counters of the log queue, such as the number
+    /// of records dropped because it was full.
+    pub fn queue_stats(&self) -> QueueStats {
+        self.queue.stats().clone()
     }
 
     /// Create a new batch processor builder
@@ -313,6 +356,37 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
     }
 }
 
+/// Exports the queued logs one batch at a time. Unless `drain` is set, a
+/// partial batch is left in the queue for the next tick. Returns the last
+/// export error, if any.
+async fn export_queued<R, E>(
+    queue: &BatchQueue<(LogRecord, InstrumentationScope)>,
+    max_export_batch_size: usize,
+    drain: bool,
+    time_out: Duration,
+    exporter: &mut E,
+    runtime: &R,
+) -> ExportResult
+where
+    R: RuntimeChannel,
+    E: LogExporter + ?Sized,
+{
+    queue.clear_export_request();
+    let batch_size = max_export_batch_size.max(1);
+    let mut result = Ok(());
+    while queue.len() >= batch_size || (drain && queue.len() > 0) {
+        let batch = queue.pop_batch(batch_size);
+        let count = batch.len();
+        let timer = queue.metrics().start_export();
+        let export_result = export_with_timeout(time_out, exporter, runtime, batch).await;
+        timer.finish(count, &export_result);
+        if let Err(err) = export_result {
+            result = Err(err);
+        }
+    }
+    result
+}
+
 async fn export_with_timeout<R, E>(
     time_out: Duration,
     exporter: &mut E,
@@ -348,20 +422,23 @@ where
 pub struct BatchConfig {
     /// The maximum queue size to buffer logs for delayed processing. If the
     /// queue gets full it drops the logs. The default value of is 2048.
-    max_queue_size: usize,
+    pub(crate) max_queue_size: usize,
 
     /// The delay interval in milliseconds between two consecutive processing
     /// of batches. The default value is 1 second.
-    scheduled_delay: Duration,
+    pub(crate) scheduled_delay: Duration,
 
     /// The maximum number of logs to process in a single batch. If there are
     /// more than one batch worth of logs then it processes multiple batches
     /// of logs one batch after the other without any delay. The default value
     /// is 512.
-    max_export_batch_size: usize,
+    pub(crate) max_export_batch_size: usize,
 
     /// The maximum duration to export a batch of data.
-    max_export_timeout: Duration,
+    pub(crate) max_export_timeout: Duration,
+
+    /// What to do with a log when the queue is full. The default is to drop it.
+    pub(crate) queue_full_policy: QueueFullPolicy,
 }
 
 impl Default for BatchConfig {
@@ -377,6 +454,7 @@ pub struct BatchConfigBuilder {
     scheduled_delay: Duration,
     max_export_batch_size: usize,
     max_export_timeout: Duration,
+    queue_full_policy: QueueFullPolicy,
 }
 
 impl Default for BatchConfigBuilder {
@@ -393,6 +471,7 @@ impl Default for BatchConfigBuilder {
             scheduled_delay: Duration::from_millis(OTEL_BLRP_SCHEDULE_DELAY_DEFAULT),
             max_export_batch_size: OTEL_BLRP_MAX_EXPORT_BATCH_SIZE_DEFAULT,
             max_export_timeout: Duration::from_millis(OTEL_BLRP_EXPORT_TIMEOUT_DEFAULT),
+            queue_full_policy: QueueFullPolicy::default(),
         }
         .init_from_env_vars()
     }
@@ -434,6 +513,14 @@ impl BatchConfigBuilder {
         self
     }
 
+    /// Set queue_full_policy for [`BatchConfigBuilder`].
+    /// It's what happens to a log emitted while the queue is full.
+    /// The default is [`QueueFullPolicy::DropNewest`].
+    pub fn with_queue_full_policy(mut self, queue_full_policy: QueueFullPolicy) -> Self {
+        self.queue_full_policy = queue_full_policy;
+        self
+    }
+
     /// Builds a `BatchConfig` enforcing the following invariants:
     /// * `max_export_batch_size` must be less than or equal to `max_queue_size`.
     pub fn build(self) -> BatchConfig {
@@ -445,6 +532,7 @@ impl BatchConfigBuilder {
             max_queue_size: self.max_queue_size,
             scheduled_delay: self.scheduled_delay,
             max_export_timeout: self.max_export_timeout,
+            queue_full_policy: self.queue_full_policy,
             max_export_batch_size,
         }
     }
@@ -508,11 +596,11 @@ where
 }
 
 /// Messages sent between application thread and batch log processor's work thread.
-#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 enum BatchMessage {
-    /// Export logs, usually called when the log is emitted.
-    ExportLog((LogRecord, InstrumentationScope)),
+    /// Export the full batches waiting in the queue, sent when an emitted log
+    /// fills a batch.
+    ExportLogs,
     /// Flush the current buffer to the backend, it can be triggered by
     /// pre configured interval or a call to `force_push` function.
     Flush(Option<oneshot::Sender<ExportResult>>),
@@ -538,7 +626,8 @@ mod tests {
                 OTEL_BLRP_EXPORT_TIMEOUT_DEFAULT, OTEL_BLRP_MAX_EXPORT_BATCH_SIZE_DEFAULT,
                 OTEL_BLRP_MAX_QUEUE_SIZE_DEFAULT, OTEL_BLRP_SCHEDULE_DELAY_DEFAULT,
             },
-            BatchConfig, BatchConfigBuilder, LogProcessor, LoggerProvider, SimpleLogProcessor,
+            BatchConfig, BatchConfigBuilder, LogProcessor, LoggerProvider, QueueFullPolicy,
+            SimpleLogProcessor,
         },
         runtime,
         testing::logs::InMemoryLogExporter,
@@ -584,6 +673,26 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Clone, Default)]
+    struct FlushCountingExporter {
+        exported: Arc<AtomicUsize>,
+        flushed: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl LogExporter for FlushCountingExporter {
+        async fn export(&mut self, batch: LogBatch<'_>) -> LogResult<()> {
+            self.exported
+                .fetch_add(batch.iter().count(), Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn force_flush(&mut self, _timeout: Duration) -> LogResult<()> {
+            self.flushed.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
     #[test]
     fn test_default_const_values() {
         assert_eq!(OTEL_BLRP_SCHEDULE_DELAY, "OTEL_BLRP_SCHEDULE_DELAY");
@@ -671,12 +780,14 @@ mod tests {
             .with_scheduled_delay(Duration::from_millis(2))
             .with_max_export_timeout(Duration::from_millis(3))
             .with_max_queue_size(4)
+            .with_queue_full_policy(QueueFullPolicy::DropOldest)
             .build();
 
         assert_eq!(batch.max_export_batch_size, 1);
         assert_eq!(batch.scheduled_delay, Duration::from_millis(2));
         assert_eq!(batch.max_export_timeout, Duration::from_millis(3));
         assert_eq!(batch.max_queue_size, 4);
+        assert_eq!(batch.queue_full_policy, QueueFullPolicy::DropOldest);
     }
 
     #[test]
@@ -802,6 +913,66 @@ mod tests {
         assert_eq!(1, exporter.get_emitted_logs().unwrap().len())
     }
 
+    #[test]
+    fn test_simple_force_flush_reaches_exporter() {
+        let exporter = FlushCountingExporter::default();
+        let processor = SimpleLogProcessor::new(Box::new(exporter.clone()));
+
+        processor.emit(&mut LogRecord::default(), &InstrumentationScope::default());
+        processor.force_flush().unwrap();
+
+        assert_eq!(exporter.exported.load(Ordering::SeqCst), 1);
+        assert_eq!(exporter.flushed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_batch_force_flush_reaches_exporter() {
+        let exporter = FlushCountingExporter::default();
+        let processor = BatchLogProcessor::new(
+            Box::new(exporter.clone()),
+            BatchConfigBuilder::default()
+                .with_scheduled_delay(Duration::from_secs(60))
+                .build(),
+            runtime::Tokio,
+        );
+
+        processor.emit(&mut LogRecord::default(), &InstrumentationScope::default());
+        processor.force_flush().unwrap();
+
+        // the records are exported before the exporter is flushed
+        assert_eq!(exporter.exported.load(Ordering::SeqCst), 1);
+        assert_eq!(exporter.flushed.load(Ordering::SeqCst), 1);
+
+        processor.shutdown().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_batch_force_flush_exports_queue_in_batches() {
+        let exporter = FlushCountingExporter::default();
+        let processor = BatchLogProcessor::new(
+            Box::new(exporter.clone()),
+            BatchConfigBuilder::default()
+                .with_max_queue_size(8)
+                .with_max_export_batch_size(3)
+                .with_scheduled_delay(Duration::from_secs(60))
+                .with_queue_full_policy(QueueFullPolicy::Block(Duration::from_secs(5)))
+                .build(),
+            runtime::Tokio,
+        );
+        let stats = processor.queue_stats();
+
+        for _ in 0..20 {
+            processor.emit(&mut LogRecord::default(), &InstrumentationScope::default());
+        }
+        processor.force_flush().unwrap();
+
+        assert_eq!(exporter.exported.load(Ordering::SeqCst), 20);
+        assert_eq!(stats.queued(), 0);
+        assert_eq!(stats.dropped(), 0);
+
+        processor.shutdown().unwrap();
+    }
+
     #[test]
     fn test_simple_shutdown() {
         let exporter = InMemoryLogExporterBuilder::default()
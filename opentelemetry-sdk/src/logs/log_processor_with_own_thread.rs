@@ -0,0 +1,449 @@
+use crate::{
+    batch_queue::{send_until, BatchQueue, QueueStats},
+    export::logs::{ExportResult, LogBatch, LogExporter},
+    logs::{BatchConfig, LogError, LogProcessor, LogRecord, LogResult},
+    self_metrics::{ComponentMetrics, Signal},
+    Resource,
+};
+use opentelemetry::{otel_debug, otel_error, otel_info, InstrumentationScope};
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Messages sent between application threads and the processor's own thread.
+#[derive(Debug)]
+enum BatchMessage {
+    /// Export the full batches waiting in the queue, sent when an emitted log
+    /// fills a batch.
+    ExportLogs,
+    /// Export the current buffer, flush the exporter and report the result.
+    Flush(SyncSender<ExportResult>),
+    /// Export the current buffer, shut down the exporter and stop the thread.
+    Shutdown(SyncSender<ExportResult>),
+    /// Set the resource for the exporter.
+    SetResource(Arc<Resource>),
+}
+
+/// A [`LogProcessor`] that buffers log records and exports them in batches
+/// from a dedicated thread, without requiring an async runtime.
+///
+/// It is configured with the same [`BatchConfig`] as [`BatchLogProcessor`].
+/// Exports are performed on the processor thread by blocking on the
+/// exporter's future with `futures_executor`. This suits binaries without an
+/// async runtime and plugins loaded through FFI, as long as the exporter
+/// itself does not need one.
+///
+/// [`force_flush`] and [`shutdown`] wait for the processor thread for at most
+/// `max_export_timeout`, and return [`LogError::ExportTimedOut`] otherwise.
+/// An export already in progress is not interrupted. Records still queued
+/// when the processor is dropped without shutdown are exported before its
+/// thread stops.
+///
+/// Records emitted while the queue is full are handled according to the
+/// configured [`QueueFullPolicy`]; the count of dropped records is available
+/// from [`queue_stats`] and reported through internal logs on shutdown.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::export::logs::LogExporter;
+/// use opentelemetry_sdk::logs::{
+///     BatchConfigBuilder, BatchLogProcessorWithOwnThread, LoggerProvider,
+/// };
+///
+/// fn logger_provider(exporter: impl LogExporter + 'static) -> LoggerProvider {
+///     let processor = BatchLogProcessorWithOwnThread::builder(exporter)
+///         .with_batch_config(BatchConfigBuilder::default().with_max_queue_size(4096).build())
+///         .build();
+///
+///     LoggerProvider::builder()
+///         .with_log_processor(processor)
+///         .build()
+/// }
+/// ```
+///
+/// [`BatchLogProcessor`]: crate::logs::BatchLogProcessor
+/// [`force_flush`]: LogProcessor::force_flush
+/// [`shutdown`]: LogProcessor::shutdown
+/// [`QueueFullPolicy`]: crate::logs::QueueFullPolicy
+/// [`queue_stats`]: BatchLogProcessorWithOwnThread::queue_stats
+pub struct BatchLogProcessorWithOwnThread {
+    message_sender: SyncSender<BatchMessage>,
+    handle: Mutex<Option<thread::JoinHandle<()>>>,
+    timeout: Duration,
+    queue: Arc<BatchQueue<(LogRecord, InstrumentationScope)>>,
+    max_export_batch_size: usize,
+    is_shutdown: AtomicBool,
+}
+
+impl Debug for BatchLogProcessorWithOwnThread {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchLogProcessorWithOwnThread")
+            .field("timeout", &self.timeout)
+            .field("queue_stats", self.queue.stats())
+            .finish()
+    }
+}
+
+impl BatchLogProcessorWithOwnThread {
+    /// Create a new batch processor builder
+    pub fn builder<E>(exporter: E) -> BatchLogProcessorWithOwnThreadBuilder<E>
+    where
+        E: LogExporter,
+    {
+        BatchLogProcessorWithOwnThreadBuilder {
+            exporter,
+            config: Default::default(),
+        }
+    }
+
+    fn new(mut exporter: Box<dyn LogExporter>, config: BatchConfig) -> Self {
+        let (message_sender, message_receiver) = mpsc::sync_channel(config.max_queue_size);
+        let timeout = config.max_export_timeout;
+        let queue = Arc::new(BatchQueue::new(
+            "BatchLogProcessorWithOwnThread",
+            config.max_queue_size,
+            config.queue_full_policy,
+            ComponentMetrics::new(Signal::Logs, "batching_log_processor"),
+        ));
+        let max_export_batch_size = config.max_export_batch_size.max(1);
+        let worker_queue = queue.clone();
+
+        let result_thread_creation = thread::Builder::new()
+            .name("OpenTelemetry.Logs.BatchProcessor".to_string())
+            .spawn(move || {
+                otel_info!(
+                    name: "BatchLogProcessor.ThreadStarted",
+                    interval_in_millisecs = config.scheduled_delay.as_millis(),
+                    max_export_batch_size = config.max_export_batch_size,
+                    max_queue_size = config.max_queue_size
+                );
+                let mut last_export_time = Instant::now();
+                let export = |exporter: &mut dyn LogExporter, drain: bool| {
+                    export_queued(exporter, &worker_queue, max_export_batch_size, drain)
+                };
+
+                loop {
+                    let remaining_time = config
+                        .scheduled_delay
+                        .saturating_sub(last_export_time.elapsed());
+                    match message_receiver.recv_timeout(remaining_time) {
+                        Ok(BatchMessage::ExportLogs) => {
+                            if let Err(err) = export(exporter.as_mut(), false) {
+                                otel_error!(
+                                    name: "BatchLogProcessor.Export.Error",
+                                    error = format!("{}", err)
+                                );
+                            }
+                            last_export_time = Instant::now();
+                        }
+                        Ok(BatchMessage::Flush(sender)) => {
+                            // a force flush also drains the exporter's own buffers
+                            let result = export(exporter.as_mut(), true)
+                                .and_then(|_| exporter.force_flush(config.max_export_timeout));
+                            last_export_time = Instant::now();
+                            if let Err(send_error) = sender.send(result) {
+                                otel_debug!(
+                                    name: "BatchLogProcessor.Flush.SendResultError",
+                                    error = format!("{:?}", send_error),
+                                );
+                            }
+                        }
+                        Ok(BatchMessage::Shutdown(sender)) => {
+                            let result = export(exporter.as_mut(), true);
+                            exporter.shutdown();
+                            worker_queue.report_dropped();
+                            if let Err(send_error) = sender.send(result) {
+                                otel_debug!(
+                                    name: "BatchLogProcessor.Shutdown.SendResultError",
+                                    error = format!("{:?}", send_error),
+                                );
+                            }
+                            break;
+                        }
+                        Ok(BatchMessage::SetResource(resource)) => {
+                            exporter.set_resource(&resource);
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            if let Err(err) = export(exporter.as_mut(), true) {
+                                otel_error!(
+                                    name: "BatchLogProcessor.Export.Error",
+                                    error = format!("{}", err)
+                                );
+                            }
+                            last_export_time = Instant::now();
+                        }
+                        Err(RecvTimeoutError::Disconnected) => {
+                            // The processor was dropped without shutdown.
+                            if let Err(err) = export(exporter.as_mut(), true) {
+                                otel_error!(
+                                    name: "BatchLogProcessor.Export.Error",
+                                    error = format!("{}", err)
+                                );
+                            }
+                            break;
+                        }
+                    }
+                }
+                otel_info!(name: "BatchLogProcessor.ThreadStopped");
+            });
+
+        let handle = match result_thread_creation {
+            Ok(handle) => Some(handle),
+            Err(err) => {
+                otel_error!(
+                    name: "BatchLogProcessor.ThreadStartError",
+                    error = format!("{:?}", err)
+                );
+                None
+            }
+        };
+
+        BatchLogProcessorWithOwnThread {
+            message_sender,
+            handle: Mutex::new(handle),
+            timeout,
+            queue,
+            max_export_batch_size,
+            is_shutdown: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns a handle on the counters of the log queue, such as the number
+    /// of records dropped because it was full.
+    pub fn queue_stats(&self) -> QueueStats {
+        self.queue.stats().clone()
+    }
+
+    /// Sends a control message and waits for the processor thread to answer,
+    /// for at most the export timeout in total.
+    fn request(
+        &self,
+        message: impl FnOnce(SyncSender<ExportResult>) -> BatchMessage,
+    ) -> LogResult<()> {
+        let deadline = Instant::now() + self.timeout;
+        let (sender, receiver) = mpsc::sync_channel(1);
+        match send_until(&self.message_sender, message(sender), deadline) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => return Err(LogError::ExportTimedOut(self.timeout)),
+            Err(err) => return Err(LogError::Other(err.to_string().into())),
+        }
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(LogError::ExportTimedOut(self.timeout)),
+            Err(err) => Err(LogError::Other(err.into())),
+        }
+    }
+}
+
+/// Exports the queued logs one batch at a time, blocking on the exporter's
+/// future. Unless `drain` is set, a partial batch is left in the queue.
+/// Returns the last export error, if any.
+fn export_queued(
+    exporter: &mut dyn LogExporter,
+    queue: &BatchQueue<(LogRecord, InstrumentationScope)>,
+    batch_size: usize,
+    drain: bool,
+) -> ExportResult {
+    queue.clear_export_request();
+    let mut result = Ok(());
+    while queue.len() >= batch_size || (drain && queue.len() > 0) {
+        let logs = queue.pop_batch(batch_size);
+        let log_vec: Vec<(&LogRecord, &InstrumentationScope)> =
+            logs.iter().map(|(record, scope)| (record, scope)).collect();
+        let batch = LogBatch::new(log_vec.as_slice());
+        let timer = queue.metrics().start_export();
+        let export_result = futures_executor::block_on(exporter.export(batch));
+        timer.finish(logs.len(), &export_result);
+        if let Err(err) = export_result {
+            result = Err(err);
+        }
+    }
+    result
+}
+
+impl LogProcessor for BatchLogProcessorWithOwnThread {
+    fn emit(&self, record: &mut LogRecord, instrumentation: &InstrumentationScope) {
+        if self.is_shutdown.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let Some(len) = self.queue.push((record.clone(), instrumentation.clone())) else {
+            return;
+        };
+        if len < self.max_export_batch_size || !self.queue.request_export() {
+            return;
+        }
+
+        match self.message_sender.try_send(BatchMessage::ExportLogs) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                // the thread is busy, it exports the queue on its next message
+                self.queue.clear_export_request();
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                otel_debug!(name: "BatchLogProcessor.Emit.ThreadStopped");
+            }
+        }
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        if self.is_shutdown.load(Ordering::Relaxed) {
+            return Err(LogError::AlreadyShutdown(
+                "BatchLogProcessorWithOwnThread".into(),
+            ));
+        }
+        self.request(BatchMessage::Flush)
+    }
+
+    fn shutdown(&self) -> LogResult<()> {
+        if self.is_shutdown.swap(true, Ordering::Relaxed) {
+            return Err(LogError::AlreadyShutdown(
+                "BatchLogProcessorWithOwnThread".into(),
+            ));
+        }
+
+        let result = self.request(BatchMessage::Shutdown);
+        if result.is_ok() {
+            if let Some(handle) = self.handle.lock().ok().and_then(|mut handle| handle.take()) {
+                let _ = handle.join();
+            }
+        }
+        result
+    }
+
+    fn set_resource(&self, resource: &Resource) {
+        let resource = Arc::new(resource.clone());
+        let _ = self
+            .message_sender
+            .send(BatchMessage::SetResource(resource));
+    }
+}
+
+/// A builder for creating [`BatchLogProcessorWithOwnThread`] instances.
+#[derive(Debug)]
+pub struct BatchLogProcessorWithOwnThreadBuilder<E> {
+    exporter: E,
+    config: BatchConfig,
+}
+
+impl<E> BatchLogProcessorWithOwnThreadBuilder<E>
+where
+    E: LogExporter + 'static,
+{
+    /// Set the BatchConfig for [`BatchLogProcessorWithOwnThreadBuilder`]
+    pub fn with_batch_config(self, config: BatchConfig) -> Self {
+        BatchLogProcessorWithOwnThreadBuilder { config, ..self }
+    }
+
+    /// Build a batch processor and start its thread.
+    pub fn build(self) -> BatchLogProcessorWithOwnThread {
+        BatchLogProcessorWithOwnThread::new(Box::new(self.exporter), self.config)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    // cargo test logs::log_processor_with_own_thread::tests --features=testing,experimental_logs_batch_log_processor_no_runtime
+    use super::*;
+    use crate::logs::{BatchConfigBuilder, LoggerProvider};
+    use crate::testing::logs::{InMemoryLogExporter, InMemoryLogExporterBuilder};
+    use opentelemetry::logs::{LogRecord as _, Logger as _, LoggerProvider as _};
+
+    fn provider(exporter: &InMemoryLogExporter, config: BatchConfigBuilder) -> LoggerProvider {
+        LoggerProvider::builder()
+            .with_log_processor(
+                BatchLogProcessorWithOwnThread::builder(exporter.clone())
+                    .with_batch_config(config.build())
+                    .build(),
+            )
+            .build()
+    }
+
+    fn emit(provider: &LoggerProvider, count: usize) {
+        let logger = provider.logger("test-logger");
+        for _ in 0..count {
+            let mut record = logger.create_log_record();
+            record.set_body("message".into());
+            logger.emit(record);
+        }
+    }
+
+    fn emitted(exporter: &InMemoryLogExporter) -> usize {
+        exporter.get_emitted_logs().unwrap().len()
+    }
+
+    #[test]
+    fn exports_full_batches_and_flushes_the_rest() {
+        let exporter = InMemoryLogExporter::default();
+        let provider = provider(
+            &exporter,
+            BatchConfigBuilder::default()
+                .with_max_export_batch_size(2)
+                .with_scheduled_delay(Duration::from_secs(3600)),
+        );
+
+        emit(&provider, 3);
+        // the first batch is exported by the processor thread without flush
+        let start = Instant::now();
+        while emitted(&exporter) < 2 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(emitted(&exporter), 2);
+
+        for result in provider.force_flush() {
+            result.unwrap();
+        }
+        assert_eq!(emitted(&exporter), 3);
+    }
+
+    #[test]
+    fn exports_on_scheduled_delay() {
+        let exporter = InMemoryLogExporter::default();
+        let provider = provider(
+            &exporter,
+            BatchConfigBuilder::default().with_scheduled_delay(Duration::from_millis(10)),
+        );
+
+        emit(&provider, 1);
+
+        let start = Instant::now();
+        while emitted(&exporter) == 0 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(emitted(&exporter), 1);
+    }
+
+    #[test]
+    fn shutdown_exports_and_stops() {
+        let exporter = InMemoryLogExporterBuilder::default()
+            .keep_records_on_shutdown()
+            .build();
+        let processor = BatchLogProcessorWithOwnThread::builder(exporter.clone())
+            .with_batch_config(
+                BatchConfigBuilder::default()
+                    .with_scheduled_delay(Duration::from_secs(3600))
+                    .build(),
+            )
+            .build();
+
+        let mut record = LogRecord::default();
+        processor.emit(&mut record, &InstrumentationScope::default());
+        processor.shutdown().unwrap();
+        assert_eq!(emitted(&exporter), 1);
+
+        processor.emit(&mut record, &InstrumentationScope::default());
+        assert!(processor.force_flush().is_err());
+        assert!(matches!(
+            processor.shutdown(),
+            Err(LogError::AlreadyShutdown(_))
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+//! # Log record limit
+//! Erroneous code can add an unbounded number of attributes, very long values
+//! or deeply nested maps and lists to a log record, which can quickly exhaust
+//! available memory or be rejected by the backend.
+//!
+//! To protect against those errors, users can use log record limits to configure
+//!  - Maximum allowed attribute count per log record
+//!  - Maximum allowed attribute value length
+//!  - Maximum allowed nesting depth of maps and lists, in attributes and body
+//!
+//! Attributes added after the count limit has been reached are dropped, and
+//! counted in the log record's dropped attributes count. String and byte
+//! values longer than the length limit are truncated. Maps and lists nested
+//! deeper than the depth limit are removed from their parent.
+use opentelemetry::logs::AnyValue;
+use opentelemetry::StringValue;
+
+pub(crate) const DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD: u32 = 128;
+pub(crate) const DEFAULT_MAX_ATTRIBUTE_NESTING_DEPTH: u32 = 16;
+
+/// The limits of records not created by a `Logger`.
+pub(crate) const DEFAULT_LOG_RECORD_LIMITS: LogRecordLimits = LogRecordLimits {
+    max_attributes_per_log_record: DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD,
+    max_attribute_value_length: None,
+    max_attribute_nesting_depth: DEFAULT_MAX_ATTRIBUTE_NESTING_DEPTH,
+};
+
+/// Log record limit configuration to keep attributes of a log record in a
+/// reasonable size.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LogRecordLimits {
+    /// The max attributes that can be added to a `LogRecord`.
+    pub max_attributes_per_log_record: u32,
+    /// The max length of string and byte values of attributes, `None` for no
+    /// limit.
+    pub max_attribute_value_length: Option<u32>,
+    /// The max number of maps and lists nested into one another in attribute
+    /// values and in the body.
+    pub max_attribute_nesting_depth: u32,
+}
+
+impl Default for LogRecordLimits {
+    fn default() -> Self {
+        DEFAULT_LOG_RECORD_LIMITS
+    }
+}
+
+impl LogRecordLimits {
+    /// Truncates the strings and bytes of `value` and removes its maps and
+    /// lists nested too deep.
+    pub(crate) fn limit_attribute_value(&self, value: &mut AnyValue) {
+        self.limit_value(value, self.max_attribute_value_length, 0);
+    }
+
+    /// Removes the maps and lists of `body` nested too deep.
+    pub(crate) fn limit_body(&self, body: &mut AnyValue) {
+        self.limit_value(body, None, 0);
+    }
+
+    /// `depth` is the number of maps and lists enclosing `value`.
+    fn limit_value(&self, value: &mut AnyValue, max_length: Option<u32>, depth: u32) {
+        let max_depth = self.max_attribute_nesting_depth;
+        match value {
+            AnyValue::String(string) => {
+                if let Some(max_length) = max_length {
+                    truncate_string(string, max_length as usize);
+                }
+            }
+            AnyValue::Bytes(bytes) => {
+                if let Some(max_length) = max_length {
+                    bytes.truncate(max_length as usize);
+                }
+            }
+            AnyValue::ListAny(list) => {
+                if depth >= max_depth {
+                    list.clear();
+                    return;
+                }
+                list.retain(|value| depth + 1 < max_depth || !is_nested(value));
+                for value in list.iter_mut() {
+                    self.limit_value(value, max_length, depth + 1);
+                }
+            }
+            AnyValue::Map(map) => {
+                if depth >= max_depth {
+                    map.clear();
+                    return;
+                }
+                map.retain(|_, value| depth + 1 < max_depth || !is_nested(value));
+                for value in map.values_mut() {
+                    self.limit_value(value, max_length, depth + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_nested(value: &AnyValue) -> bool {
+    matches!(value, AnyValue::ListAny(_) | AnyValue::Map(_))
+}
+
+/// Truncates `string` to at most `max_length` bytes, on a char boundary.
+fn truncate_string(string: &mut StringValue, max_length: usize) {
+    let s = string.as_str();
+    if s.len() <= max_length {
+        return;
+    }
+    let mut end = max_length;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    *string = StringValue::from(s[..end].to_owned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::Key;
+    use std::collections::HashMap;
+
+    fn nested(depth: usize) -> AnyValue {
+        let mut value = AnyValue::Int(1);
+        for _ in 0..depth {
+            value = AnyValue::Map(Box::new(HashMap::from([(Key::new("k"), value)])));
+        }
+        value
+    }
+
+    fn depth(value: &AnyValue) -> usize {
+        match value {
+            AnyValue::Map(map) => 1 + map.values().map(depth).max().unwrap_or(0),
+            AnyValue::ListAny(list) => 1 + list.iter().map(depth).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn truncates_strings_on_char_boundary() {
+        let limits = LogRecordLimits {
+            max_attribute_value_length: Some(4),
+            ..Default::default()
+        };
+
+        let mut value = AnyValue::from("héllo");
+        limits.limit_attribute_value(&mut value);
+        assert_eq!(value, AnyValue::from("hél"));
+
+        let mut value = AnyValue::ListAny(Box::new(vec![
+            AnyValue::from("abcdef"),
+            AnyValue::Bytes(Box::new(vec![1, 2, 3, 4, 5])),
+        ]));
+        limits.limit_attribute_value(&mut value);
+        assert_eq!(
+            value,
+            AnyValue::ListAny(Box::new(vec![
+                AnyValue::from("abcd"),
+                AnyValue::Bytes(Box::new(vec![1, 2, 3, 4])),
+            ]))
+        );
+    }
+
+    #[test]
+    fn removes_values_nested_too_deep() {
+        let limits = LogRecordLimits {
+            max_attribute_nesting_depth: 3,
+            ..Default::default()
+        };
+
+        let mut value = nested(3);
+        limits.limit_body(&mut value);
+        assert_eq!(value, nested(3));
+
+        let mut value = nested(10);
+        limits.limit_body(&mut value);
+        assert_eq!(depth(&value), 3);
+    }
+}
@@ -2,7 +2,12 @@
 mod error;
 mod log_emitter;
 mod log_processor;
+#[cfg(feature = "experimental_logs_batch_log_processor_no_runtime")]
+mod log_processor_with_own_thread;
+mod log_record_limit;
+mod processors;
 pub(crate) mod record;
+mod sampling;
 
 pub use error::{LogError, LogResult};
 pub use log_emitter::{Builder, Logger, LoggerProvider};
@@ -10,8 +15,19 @@ pub use log_processor::{
     BatchConfig, BatchConfigBuilder, BatchLogProcessor, BatchLogProcessorBuilder, LogProcessor,
     SimpleLogProcessor,
 };
+#[cfg(feature = "experimental_logs_batch_log_processor_no_runtime")]
+pub use log_processor_with_own_thread::{
+    BatchLogProcessorWithOwnThread, BatchLogProcessorWithOwnThreadBuilder,
+};
+pub use log_record_limit::LogRecordLimits;
 use opentelemetry::InstrumentationScope;
+#[cfg(feature = "redaction")]
+pub use processors::{AttributeRedactionProcessor, Redaction};
+pub use processors::{BodyRewriteProcessor, SeverityFilterProcessor};
 pub use record::{LogRecord, TraceContext};
+pub use sampling::SamplingLogProcessor;
+
+pub use crate::batch_queue::{QueueFullPolicy, QueueStats};
 
 /// `LogData` represents a single log event without resource context.
 #[derive(Clone, Debug)]
@@ -0,0 +1,541 @@
+use crate::{
+    logs::{LogProcessor, LogRecord, LogResult},
+    Resource,
+};
+use opentelemetry::{
+    logs::{AnyValue, Severity},
+    InstrumentationScope,
+};
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+
+/// A [`LogProcessor`] passing log records to `inner` only when their severity
+/// is at least the minimum severity configured for their target.
+///
+/// Targets are matched by module path prefix: a minimum severity configured
+/// for `my_crate::db` applies to the targets `my_crate::db` and
+/// `my_crate::db::pool`, but not `my_crate::dbx`. The longest matching target
+/// wins; records of other targets use the default minimum severity. Records
+/// without severity number are always passed on.
+///
+/// With the `spec_unstable_logs_enabled` feature, the same rules answer
+/// [`LogProcessor::event_enabled`], so that disabled records are not even
+/// created by appenders checking it.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::Severity;
+/// use opentelemetry_sdk::export::logs::LogExporter;
+/// use opentelemetry_sdk::logs::{LoggerProvider, SeverityFilterProcessor, SimpleLogProcessor};
+///
+/// fn logger_provider(exporter: Box<dyn LogExporter>) -> LoggerProvider {
+///     let processor =
+///         SeverityFilterProcessor::new(SimpleLogProcessor::new(exporter), Severity::Info)
+///             .with_target_severity("hyper", Severity::Warn)
+///             .with_target_severity("my_app::db", Severity::Debug);
+///
+///     LoggerProvider::builder()
+///         .with_log_processor(processor)
+///         .build()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SeverityFilterProcessor<P> {
+    inner: P,
+    min_severity: Severity,
+    // sorted by descending length, so that the first match is the longest
+    targets: Vec<(Cow<'static, str>, Severity)>,
+}
+
+impl<P: LogProcessor> SeverityFilterProcessor<P> {
+    /// Create a new `SeverityFilterProcessor` passing records of at least
+    /// `min_severity` to `inner`.
+    pub fn new(inner: P, min_severity: Severity) -> Self {
+        SeverityFilterProcessor {
+            inner,
+            min_severity,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Sets the minimum severity of records whose target is `target` or one of
+    /// its submodules.
+    pub fn with_target_severity(
+        mut self,
+        target: impl Into<Cow<'static, str>>,
+        min_severity: Severity,
+    ) -> Self {
+        let target = target.into();
+        self.targets.retain(|(existing, _)| *existing != target);
+        self.targets.push((target, min_severity));
+        self.targets
+            .sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        self
+    }
+
+    fn min_severity(&self, target: &str) -> Severity {
+        self.targets
+            .iter()
+            .find(|(prefix, _)| target_matches(target, prefix))
+            .map_or(self.min_severity, |(_, severity)| *severity)
+    }
+
+    fn is_enabled(&self, severity: Severity, target: &str) -> bool {
+        severity >= self.min_severity(target)
+    }
+}
+
+impl<P: LogProcessor> LogProcessor for SeverityFilterProcessor<P> {
+    fn emit(&self, record: &mut LogRecord, instrumentation: &InstrumentationScope) {
+        if let Some(severity) = record.severity_number {
+            let target = record.target.as_deref().unwrap_or_default();
+            if !self.is_enabled(severity, target) {
+                return;
+            }
+        }
+        self.inner.emit(record, instrumentation);
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> LogResult<()> {
+        self.inner.shutdown()
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool {
+        self.is_enabled(level, target) && self.inner.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+/// Returns whether `target` is the module path `prefix` or one of its submodules.
+pub(crate) fn target_matches(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// A [`LogProcessor`] rewriting the body of log records before passing them
+/// to `inner`.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::AnyValue;
+/// use opentelemetry_sdk::export::logs::LogExporter;
+/// use opentelemetry_sdk::logs::{BodyRewriteProcessor, LoggerProvider, SimpleLogProcessor};
+///
+/// fn logger_provider(exporter: Box<dyn LogExporter>) -> LoggerProvider {
+///     // Keep multi-line messages on a single line.
+///     let processor =
+///         BodyRewriteProcessor::new(SimpleLogProcessor::new(exporter), |body: &mut AnyValue| {
+///             if let AnyValue::String(message) = body {
+///                 *body = AnyValue::from(message.as_str().replace('\n', " "));
+///             }
+///         });
+///
+///     LoggerProvider::builder()
+///         .with_log_processor(processor)
+///         .build()
+/// }
+/// ```
+pub struct BodyRewriteProcessor<P, F> {
+    inner: P,
+    rewrite: F,
+}
+
+impl<P, F> BodyRewriteProcessor<P, F>
+where
+    P: LogProcessor,
+    F: Fn(&mut AnyValue) + Send + Sync,
+{
+    /// Create a new `BodyRewriteProcessor` applying `rewrite` to the body of
+    /// every record having one.
+    pub fn new(inner: P, rewrite: F) -> Self {
+        BodyRewriteProcessor { inner, rewrite }
+    }
+}
+
+impl<P: Debug, F> Debug for BodyRewriteProcessor<P, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyRewriteProcessor")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<P, F> LogProcessor for BodyRewriteProcessor<P, F>
+where
+    P: LogProcessor,
+    F: Fn(&mut AnyValue) + Send + Sync,
+{
+    fn emit(&self, record: &mut LogRecord, instrumentation: &InstrumentationScope) {
+        if let Some(body) = record.body.as_mut() {
+            (self.rewrite)(body);
+        }
+        self.inner.emit(record, instrumentation);
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> LogResult<()> {
+        self.inner.shutdown()
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool {
+        self.inner.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(feature = "redaction")]
+pub use redaction::{AttributeRedactionProcessor, Redaction};
+
+#[cfg(feature = "redaction")]
+mod redaction {
+    use super::*;
+    use crate::redaction::hash;
+    pub use crate::redaction::Redaction;
+    use opentelemetry::Key;
+    use regex::Regex;
+    use std::collections::HashSet;
+
+    #[derive(Debug)]
+    enum Rule {
+        Key(Regex, Redaction),
+        Value(Regex, Redaction),
+    }
+
+    /// A [`LogProcessor`] removing or obfuscating attributes of log records
+    /// before passing them to `inner`.
+    ///
+    /// Attributes are matched by exact key, by key regex, or, for string values
+    /// including those nested in lists and maps, by value regex. Rules apply in
+    /// the order they are added; once an attribute is dropped, later rules do
+    /// not see it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry_sdk::export::logs::LogExporter;
+    /// use opentelemetry_sdk::logs::{
+    ///     AttributeRedactionProcessor, LoggerProvider, Redaction, SimpleLogProcessor,
+    /// };
+    /// use regex::Regex;
+    ///
+    /// fn logger_provider(exporter: Box<dyn LogExporter>) -> LoggerProvider {
+    ///     let processor = AttributeRedactionProcessor::new(SimpleLogProcessor::new(exporter))
+    ///         .with_dropped_keys(["password"])
+    ///         .with_key_rule(Regex::new("(?i)user_?id").unwrap(), Redaction::Hash)
+    ///         .with_value_rule(
+    ///             Regex::new(r"\b\d{4}-\d{4}-\d{4}-\d{4}\b").unwrap(),
+    ///             Redaction::Mask("****".into()),
+    ///         );
+    ///
+    ///     LoggerProvider::builder()
+    ///         .with_log_processor(processor)
+    ///         .build()
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct AttributeRedactionProcessor<P> {
+        inner: P,
+        dropped_keys: HashSet<Key>,
+        rules: Vec<Rule>,
+    }
+
+    impl<P: LogProcessor> AttributeRedactionProcessor<P> {
+        /// Create a new `AttributeRedactionProcessor` without rules.
+        pub fn new(inner: P) -> Self {
+            AttributeRedactionProcessor {
+                inner,
+                dropped_keys: HashSet::new(),
+                rules: Vec::new(),
+            }
+        }
+
+        /// Removes the attributes with the given keys.
+        pub fn with_dropped_keys<K: Into<Key>>(
+            mut self,
+            keys: impl IntoIterator<Item = K>,
+        ) -> Self {
+            self.dropped_keys.extend(keys.into_iter().map(Into::into));
+            self
+        }
+
+        /// Applies `redaction` to the values of the attributes whose key
+        /// matches `key`.
+        pub fn with_key_rule(mut self, key: Regex, redaction: Redaction) -> Self {
+            self.rules.push(Rule::Key(key, redaction));
+            self
+        }
+
+        /// Applies `redaction` to the string values matching `value`.
+        ///
+        /// [`Redaction::Mask`] only replaces the matched parts of the string,
+        /// while [`Redaction::Drop`] removes the whole attribute.
+        pub fn with_value_rule(mut self, value: Regex, redaction: Redaction) -> Self {
+            self.rules.push(Rule::Value(value, redaction));
+            self
+        }
+
+        fn redact(&self, attribute: &mut Option<(Key, AnyValue)>) {
+            let Some((key, value)) = attribute else {
+                return;
+            };
+            if self.dropped_keys.contains(key) {
+                *attribute = None;
+                return;
+            }
+            for rule in &self.rules {
+                let keep = match rule {
+                    Rule::Key(regex, redaction) => {
+                        !regex.is_match(key.as_str()) || redact_value(value, redaction)
+                    }
+                    Rule::Value(regex, redaction) => redact_strings(value, regex, redaction),
+                };
+                if !keep {
+                    *attribute = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Applies `redaction` to the whole `value`, returning whether to keep it.
+    fn redact_value(value: &mut AnyValue, redaction: &Redaction) -> bool {
+        match redaction {
+            Redaction::Drop => return false,
+            Redaction::Mask(mask) => *value = AnyValue::from(mask.to_string()),
+            Redaction::Hash => *value = AnyValue::from(hash(&string_value(value))),
+        }
+        true
+    }
+
+    /// Applies `redaction` to the strings in `value` matching `regex`,
+    /// returning whether to keep it.
+    fn redact_strings(value: &mut AnyValue, regex: &Regex, redaction: &Redaction) -> bool {
+        match value {
+            AnyValue::String(string) if regex.is_match(string.as_str()) => match redaction {
+                Redaction::Mask(mask) => {
+                    let masked = regex
+                        .replace_all(string.as_str(), mask.as_ref())
+                        .into_owned();
+                    *value = AnyValue::from(masked);
+                    true
+                }
+                redaction => redact_value(value, redaction),
+            },
+            AnyValue::ListAny(values) => {
+                values.retain_mut(|value| redact_strings(value, regex, redaction));
+                true
+            }
+            AnyValue::Map(values) => {
+                values.retain(|_, value| redact_strings(value, regex, redaction));
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn string_value(value: &AnyValue) -> String {
+        match value {
+            AnyValue::String(string) => string.to_string(),
+            AnyValue::Int(value) => value.to_string(),
+            AnyValue::Double(value) => value.to_string(),
+            AnyValue::Boolean(value) => value.to_string(),
+            value => format!("{value:?}"),
+        }
+    }
+
+    impl<P: LogProcessor> LogProcessor for AttributeRedactionProcessor<P> {
+        fn emit(&self, record: &mut LogRecord, instrumentation: &InstrumentationScope) {
+            for attribute in record.attributes_iter_mut() {
+                self.redact(attribute);
+            }
+            self.inner.emit(record, instrumentation);
+        }
+
+        fn force_flush(&self) -> LogResult<()> {
+            self.inner.force_flush()
+        }
+
+        fn shutdown(&self) -> LogResult<()> {
+            self.inner.shutdown()
+        }
+
+        #[cfg(feature = "spec_unstable_logs_enabled")]
+        fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool {
+            self.inner.event_enabled(level, target, name)
+        }
+
+        fn set_resource(&self, resource: &Resource) {
+            self.inner.set_resource(resource);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{LoggerProvider, SimpleLogProcessor};
+    use crate::testing::logs::InMemoryLogExporter;
+    use opentelemetry::logs::{LogRecord as _, Logger as _, LoggerProvider as _};
+
+    fn emit(provider: &LoggerProvider, target: &'static str, severity: Severity, body: &str) {
+        let logger = provider.logger("test-logger");
+        let mut record = logger.create_log_record();
+        record.set_target(target);
+        record.set_severity_number(severity);
+        record.set_body(AnyValue::from(body.to_owned()));
+        record.add_attribute("user.id", "alice");
+        record.add_attribute("card", "paid with 4111-1111-1111-1111");
+        record.add_attribute("password", "secret");
+        record.add_attribute(
+            "tags",
+            AnyValue::ListAny(Box::new(vec![
+                AnyValue::from("4111-1111-1111-1111"),
+                AnyValue::from("checkout"),
+            ])),
+        );
+        logger.emit(record);
+    }
+
+    fn bodies(exporter: &InMemoryLogExporter) -> Vec<AnyValue> {
+        exporter
+            .get_emitted_logs()
+            .unwrap()
+            .into_iter()
+            .filter_map(|log| log.record.body)
+            .collect()
+    }
+
+    #[test]
+    fn severity_filter_uses_longest_target_prefix() {
+        let exporter = InMemoryLogExporter::default();
+        let processor = SeverityFilterProcessor::new(
+            SimpleLogProcessor::new(Box::new(exporter.clone())),
+            Severity::Info,
+        )
+        .with_target_severity("noisy", Severity::Warn)
+        .with_target_severity("noisy::important", Severity::Debug);
+        let provider = LoggerProvider::builder()
+            .with_log_processor(processor)
+            .build();
+
+        emit(&provider, "app", Severity::Debug, "app debug");
+        emit(&provider, "app", Severity::Info, "app info");
+        emit(&provider, "noisy::db", Severity::Info, "noisy info");
+        emit(&provider, "noisy::db", Severity::Error, "noisy error");
+        emit(
+            &provider,
+            "noisy::important",
+            Severity::Debug,
+            "important debug",
+        );
+        emit(&provider, "noisyx", Severity::Info, "noisyx info");
+
+        assert_eq!(
+            bodies(&exporter),
+            [
+                AnyValue::from("app info"),
+                AnyValue::from("noisy error"),
+                AnyValue::from("important debug"),
+                AnyValue::from("noisyx info"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    #[test]
+    fn severity_filter_answers_event_enabled() {
+        let processor = SeverityFilterProcessor::new(
+            SimpleLogProcessor::new(Box::new(InMemoryLogExporter::default())),
+            Severity::Info,
+        )
+        .with_target_severity("noisy", Severity::Error);
+
+        assert!(processor.event_enabled(Severity::Info, "app", "event"));
+        assert!(!processor.event_enabled(Severity::Debug, "app", "event"));
+        assert!(!processor.event_enabled(Severity::Warn, "noisy::db", "event"));
+        assert!(processor.event_enabled(Severity::Error, "noisy::db", "event"));
+    }
+
+    #[test]
+    fn body_rewrite_rewrites_bodies() {
+        let exporter = InMemoryLogExporter::default();
+        let processor = BodyRewriteProcessor::new(
+            SimpleLogProcessor::new(Box::new(exporter.clone())),
+            |body: &mut AnyValue| {
+                if let AnyValue::String(message) = body {
+                    *body = AnyValue::from(message.as_str().to_uppercase());
+                }
+            },
+        );
+        let provider = LoggerProvider::builder()
+            .with_log_processor(processor)
+            .build();
+
+        emit(&provider, "app", Severity::Info, "hello");
+
+        assert_eq!(bodies(&exporter), [AnyValue::from("HELLO")]);
+    }
+
+    #[cfg(feature = "redaction")]
+    #[test]
+    fn attribute_redaction_applies_rules() {
+        use opentelemetry::Key;
+        use regex::Regex;
+
+        let exporter = InMemoryLogExporter::default();
+        let processor =
+            AttributeRedactionProcessor::new(SimpleLogProcessor::new(Box::new(exporter.clone())))
+                .with_dropped_keys(["password"])
+                .with_key_rule(Regex::new(r"^user\.").unwrap(), Redaction::Hash)
+                .with_value_rule(
+                    Regex::new(r"\d{4}-\d{4}-\d{4}-\d{4}").unwrap(),
+                    Redaction::Mask("****".into()),
+                );
+        let provider = LoggerProvider::builder()
+            .with_log_processor(processor)
+            .build();
+
+        emit(&provider, "app", Severity::Info, "checkout");
+
+        let logs = exporter.get_emitted_logs().unwrap();
+        let attributes: Vec<_> = logs[0].record.attributes_iter().cloned().collect();
+        assert_eq!(
+            attributes,
+            [
+                (
+                    Key::new("user.id"),
+                    // sha256("alice")
+                    AnyValue::from(
+                        "2bd806c97f0e00af1a1fc3328fa763a9269723c8db8fac4f93af71db186d6e90"
+                    )
+                ),
+                (Key::new("card"), AnyValue::from("paid with ****")),
+                (
+                    Key::new("tags"),
+                    AnyValue::ListAny(Box::new(vec![
+                        AnyValue::from("****"),
+                        AnyValue::from("checkout"),
+                    ]))
+                ),
+            ]
+        );
+    }
+}
@@ -1,10 +1,12 @@
+use super::log_record_limit::DEFAULT_LOG_RECORD_LIMITS;
+use super::LogRecordLimits;
 use crate::growable_array::GrowableArray;
 use opentelemetry::{
     logs::{AnyValue, Severity},
     trace::{SpanContext, SpanId, TraceFlags, TraceId},
     Key,
 };
-use std::{borrow::Cow, time::SystemTime};
+use std::{borrow::Cow, sync::Arc, time::SystemTime};
 
 // According to a Go-specific study mentioned on https://go.dev/blog/slog,
 // up to 5 attributes is the most common case.
@@ -49,6 +51,13 @@ pub struct LogRecord {
 
     /// Additional attributes associated with this record
     pub(crate) attributes: LogRecordAttributes,
+
+    /// Number of attributes dropped because of the attribute count limit
+    pub(crate) dropped_attributes_count: u32,
+
+    /// Limits of the provider the record was created by, the default limits
+    /// if `None`
+    pub(crate) limits: Option<Arc<LogRecordLimits>>,
 }
 
 impl opentelemetry::logs::LogRecord for LogRecord {
@@ -80,7 +89,8 @@ impl opentelemetry::logs::LogRecord for LogRecord {
         self.severity_number = Some(severity_number);
     }
 
-    fn set_body(&mut self, body: AnyValue) {
+    fn set_body(&mut self, mut body: AnyValue) {
+        self.limits().limit_body(&mut body);
         self.body = Some(body);
     }
 
@@ -100,7 +110,14 @@ impl opentelemetry::logs::LogRecord for LogRecord {
         K: Into<Key>,
         V: Into<AnyValue>,
     {
-        self.attributes.push(Some((key.into(), value.into())));
+        let limits = *self.limits();
+        if self.attributes.len() >= limits.max_attributes_per_log_record as usize {
+            self.dropped_attributes_count = self.dropped_attributes_count.saturating_add(1);
+            return;
+        }
+        let mut value = value.into();
+        limits.limit_attribute_value(&mut value);
+        self.attributes.push(Some((key.into(), value)));
     }
 
     fn set_trace_context(
@@ -118,11 +135,30 @@ impl opentelemetry::logs::LogRecord for LogRecord {
 }
 
 impl LogRecord {
+    /// Returns the number of attributes dropped because the record reached
+    /// the attribute count limit of [`LogRecordLimits`].
+    pub fn dropped_attributes_count(&self) -> u32 {
+        self.dropped_attributes_count
+    }
+
+    fn limits(&self) -> &LogRecordLimits {
+        self.limits.as_deref().unwrap_or(&DEFAULT_LOG_RECORD_LIMITS)
+    }
+
     /// Provides an iterator over the attributes.
     pub fn attributes_iter(&self) -> impl Iterator<Item = &(Key, AnyValue)> {
         self.attributes.iter().filter_map(|opt| opt.as_ref())
     }
 
+    #[allow(dead_code)]
+    /// Provides an iterator over mutable references to the attributes.
+    /// Removed attributes are `None`.
+    pub(crate) fn attributes_iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut Option<(Key, AnyValue)>> {
+        self.attributes.iter_mut()
+    }
+
     #[allow(dead_code)]
     /// Returns the number of attributes in the `LogRecord`.
     pub(crate) fn attributes_len(&self) -> usize {
@@ -241,6 +277,37 @@ mod tests {
         assert!(log_record.attributes_contains(&key, &value));
     }
 
+    #[test]
+    fn test_attribute_limits() {
+        let mut log_record = LogRecord {
+            limits: Some(Arc::new(LogRecordLimits {
+                max_attributes_per_log_record: 2,
+                max_attribute_value_length: Some(3),
+                max_attribute_nesting_depth: 1,
+            })),
+            ..Default::default()
+        };
+        log_record.add_attributes([("a", "value"), ("b", "value"), ("c", "value")]);
+        log_record.set_body(AnyValue::ListAny(Box::new(vec![AnyValue::ListAny(
+            Box::new(vec![AnyValue::Int(1)]),
+        )])));
+
+        assert_eq!(log_record.attributes_len(), 2);
+        assert_eq!(log_record.dropped_attributes_count(), 1);
+        assert!(log_record.attributes_contains(&Key::new("a"), &AnyValue::from("val")));
+        assert!(!log_record.attributes_contains(&Key::new("c"), &AnyValue::from("val")));
+        assert_eq!(log_record.body, Some(AnyValue::ListAny(Box::default())));
+    }
+
+    #[test]
+    fn test_default_attribute_count_limit() {
+        let mut log_record = LogRecord::default();
+        log_record.add_attributes((0..200).map(|i| (Key::new(i.to_string()), i)));
+
+        assert_eq!(log_record.attributes_len(), 128);
+        assert_eq!(log_record.dropped_attributes_count(), 72);
+    }
+
     #[test]
     fn compare_trace_context() {
         let trace_context = TraceContext {
@@ -273,6 +340,8 @@ mod tests {
             severity_number: Some(Severity::Error),
             body: Some(AnyValue::String("Test body".into())),
             attributes: LogRecordAttributes::new(),
+            dropped_attributes_count: 0,
+            limits: None,
             trace_context: Some(TraceContext {
                 trace_id: TraceId::from_u128(1),
                 span_id: SpanId::from_u64(1),
@@ -0,0 +1,513 @@
+use crate::{
+    logs::{processors::target_matches, LogProcessor, LogRecord, LogResult},
+    Resource,
+};
+use opentelemetry::{logs::Severity, otel_debug, otel_info, otel_warn, InstrumentationScope};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Maximum number of targets rate limited separately. Records of further
+/// targets share a single overflow bucket.
+const MAX_RATE_LIMITED_TARGETS: usize = 2000;
+
+/// Number of severity levels, TRACE to FATAL, each made of four severity numbers.
+const LEVELS: usize = 6;
+
+/// Returns the level of `severity`, from 0 for TRACE to 5 for FATAL.
+fn level(severity: Severity) -> usize {
+    (severity as usize - 1) / 4
+}
+
+/// A ratio overriding the one of a level for a target and its submodules.
+#[derive(Debug)]
+struct TargetRatio {
+    target: Cow<'static, str>,
+    level: usize,
+    ratio: f64,
+    // records of the target only, so that the ratio holds whatever other
+    // targets are logged in between
+    counter: AtomicU64,
+}
+
+#[derive(Debug)]
+struct RateLimit {
+    per_second: f64,
+    max_severity: Severity,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    targets: HashMap<String, TokenBucket>,
+    // shared by the targets beyond `MAX_RATE_LIMITED_TARGETS`
+    overflow: TokenBucket,
+}
+
+/// A token bucket holding up to one second worth of records.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+    limited: bool,
+}
+
+impl TokenBucket {
+    fn new(per_second: f64, now: Instant) -> Self {
+        TokenBucket {
+            tokens: per_second,
+            refilled_at: now,
+            limited: false,
+        }
+    }
+}
+
+/// A [`LogProcessor`] passing only a sample of log records to `inner`, to
+/// protect it from high-volume services.
+///
+/// Records are dropped by two mechanisms, applied in order:
+///
+/// * ratio sampling: a configured ratio of the records of each severity level
+///   (e.g. DEBUG, which includes DEBUG2 to DEBUG4) is kept, optionally per
+///   target. All records are kept by default. Sampling is deterministic: a
+///   ratio of `0.01` keeps every hundredth record of the level, or of the
+///   matching targets for a ratio set with [`Self::with_target_ratio`].
+/// * rate limiting: up to a configured number of records per second and per
+///   target is kept, for records up to a maximum severity. Beyond 2000
+///   targets, the records of new targets share a single limit.
+///
+/// In trace-aware mode, records whose trace context is sampled are always
+/// kept, so that the logs of sampled traces are complete.
+///
+/// The numbers of records dropped by each mechanism are available from
+/// [`Self::dropped_by_sampling`] and [`Self::dropped_by_rate_limit`], and are
+/// reported through the SDK's internal logs when the processor shuts down.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::Severity;
+/// use opentelemetry_sdk::export::logs::LogExporter;
+/// use opentelemetry_sdk::logs::{LoggerProvider, SamplingLogProcessor, SimpleLogProcessor};
+///
+/// fn logger_provider(exporter: Box<dyn LogExporter>) -> LoggerProvider {
+///     // keep all WARN and above, 1% of DEBUG, and at most 100 INFO and
+///     // below per second and target, unless part of a sampled trace
+///     let processor = SamplingLogProcessor::new(SimpleLogProcessor::new(exporter))
+///         .with_ratio(Severity::Debug, 0.01)
+///         .with_rate_limit(100, Severity::Info4)
+///         .with_trace_aware();
+///
+///     LoggerProvider::builder()
+///         .with_log_processor(processor)
+///         .build()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SamplingLogProcessor<P> {
+    inner: P,
+    ratios: [f64; LEVELS],
+    // sorted by descending length, so that the first match is the longest
+    target_ratios: Vec<TargetRatio>,
+    counters: [AtomicU64; LEVELS],
+    rate_limit: Option<RateLimit>,
+    trace_aware: bool,
+    dropped_by_sampling: AtomicU64,
+    dropped_by_rate_limit: AtomicU64,
+}
+
+impl<P: LogProcessor> SamplingLogProcessor<P> {
+    /// Create a new `SamplingLogProcessor` passing all records to `inner`
+    /// until ratios or a rate limit are configured.
+    pub fn new(inner: P) -> Self {
+        SamplingLogProcessor {
+            inner,
+            ratios: [1.0; LEVELS],
+            target_ratios: Vec::new(),
+            counters: Default::default(),
+            rate_limit: None,
+            trace_aware: false,
+            dropped_by_sampling: AtomicU64::new(0),
+            dropped_by_rate_limit: AtomicU64::new(0),
+        }
+    }
+
+    /// Keeps `ratio` of the records of the level of `severity`, e.g.
+    /// `Severity::Debug` for DEBUG to DEBUG4.
+    ///
+    /// `ratio` is clamped to `0.0..=1.0`.
+    pub fn with_ratio(mut self, severity: Severity, ratio: f64) -> Self {
+        self.ratios[level(severity)] = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Keeps `ratio` of the records of the level of `severity` whose target is
+    /// `target` or one of its submodules, overriding [`Self::with_ratio`].
+    pub fn with_target_ratio(
+        mut self,
+        target: impl Into<Cow<'static, str>>,
+        severity: Severity,
+        ratio: f64,
+    ) -> Self {
+        let target = target.into();
+        let level = level(severity);
+        self.target_ratios
+            .retain(|existing| existing.target != target || existing.level != level);
+        self.target_ratios.push(TargetRatio {
+            target,
+            level,
+            ratio: ratio.clamp(0.0, 1.0),
+            counter: AtomicU64::new(0),
+        });
+        self.target_ratios
+            .sort_by_key(|target_ratio| std::cmp::Reverse(target_ratio.target.len()));
+        self
+    }
+
+    /// Keeps at most `per_second` records per second and per target, among
+    /// the records of at most `max_severity` and those without severity.
+    pub fn with_rate_limit(mut self, per_second: u32, max_severity: Severity) -> Self {
+        self.rate_limit = Some(RateLimit {
+            per_second: f64::from(per_second),
+            max_severity,
+            buckets: Mutex::new(Buckets {
+                targets: HashMap::new(),
+                overflow: TokenBucket::new(f64::from(per_second), Instant::now()),
+            }),
+        });
+        self
+    }
+
+    /// Always keeps the records whose trace context is sampled.
+    pub fn with_trace_aware(mut self) -> Self {
+        self.trace_aware = true;
+        self
+    }
+
+    /// Returns the number of records dropped by ratio sampling so far.
+    pub fn dropped_by_sampling(&self) -> u64 {
+        self.dropped_by_sampling.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of records dropped by rate limiting so far.
+    pub fn dropped_by_rate_limit(&self) -> u64 {
+        self.dropped_by_rate_limit.load(Ordering::Relaxed)
+    }
+
+    /// Returns the ratio applying to `target` at `level`, with the counter of
+    /// the records it applies to.
+    fn ratio(&self, level: usize, target: &str) -> (f64, &AtomicU64) {
+        self.target_ratios
+            .iter()
+            .find(|target_ratio| {
+                target_ratio.level == level && target_matches(target, &target_ratio.target)
+            })
+            .map_or(
+                (self.ratios[level], &self.counters[level]),
+                |target_ratio| (target_ratio.ratio, &target_ratio.counter),
+            )
+    }
+
+    fn sample(&self, severity: Severity, target: &str) -> bool {
+        let (ratio, counter) = self.ratio(level(severity), target);
+        if ratio >= 1.0 {
+            return true;
+        }
+        // keeps the records where the running count crosses an integer
+        let count = counter.fetch_add(1, Ordering::Relaxed) as f64;
+        ((count + 1.0) * ratio).floor() > (count * ratio).floor()
+    }
+
+    fn within_rate_limit(&self, severity: Option<Severity>, target: &str) -> bool {
+        let Some(rate_limit) = &self.rate_limit else {
+            return true;
+        };
+        if severity.is_some_and(|severity| severity > rate_limit.max_severity) {
+            return true;
+        }
+
+        let Ok(mut buckets) = rate_limit.buckets.lock() else {
+            return true;
+        };
+        let now = Instant::now();
+        let Buckets { targets, overflow } = &mut *buckets;
+        if !targets.contains_key(target) && targets.len() < MAX_RATE_LIMITED_TARGETS {
+            targets.insert(
+                target.to_owned(),
+                TokenBucket::new(rate_limit.per_second, now),
+            );
+            if targets.len() == MAX_RATE_LIMITED_TARGETS {
+                otel_warn!(
+                    name: "SamplingLogProcessor.TargetLimitReached",
+                    message = "Records of further targets share a single rate limit.",
+                    max_targets = MAX_RATE_LIMITED_TARGETS
+                );
+            }
+        }
+        let bucket = targets.get_mut(target).unwrap_or(overflow);
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * rate_limit.per_second).min(rate_limit.per_second);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            bucket.limited = false;
+            true
+        } else {
+            if !bucket.limited {
+                bucket.limited = true;
+                otel_debug!(
+                    name: "SamplingLogProcessor.RateLimited",
+                    target = target.to_owned(),
+                    per_second = rate_limit.per_second
+                );
+            }
+            false
+        }
+    }
+
+    fn keep(&self, record: &LogRecord) -> bool {
+        if self.trace_aware
+            && record.trace_context.as_ref().is_some_and(|trace_context| {
+                trace_context
+                    .trace_flags
+                    .is_some_and(|trace_flags| trace_flags.is_sampled())
+            })
+        {
+            return true;
+        }
+
+        let target = record.target.as_deref().unwrap_or_default();
+        if let Some(severity) = record.severity_number {
+            if !self.sample(severity, target) {
+                self.dropped_by_sampling.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+        }
+        if !self.within_rate_limit(record.severity_number, target) {
+            self.dropped_by_rate_limit.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+}
+
+impl<P: LogProcessor> LogProcessor for SamplingLogProcessor<P> {
+    fn emit(&self, record: &mut LogRecord, instrumentation: &InstrumentationScope) {
+        if self.keep(record) {
+            self.inner.emit(record, instrumentation);
+        }
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> LogResult<()> {
+        otel_info!(
+            name: "SamplingLogProcessor.Shutdown",
+            dropped_by_sampling = self.dropped_by_sampling(),
+            dropped_by_rate_limit = self.dropped_by_rate_limit()
+        );
+        self.inner.shutdown()
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool {
+        self.inner.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{LoggerProvider, SimpleLogProcessor};
+    use crate::testing::logs::InMemoryLogExporter;
+    use opentelemetry::logs::{AnyValue, LogRecord as _, Logger as _, LoggerProvider as _};
+    use opentelemetry::trace::{SpanId, TraceFlags, TraceId};
+
+    fn provider(processor: SamplingLogProcessor<SimpleLogProcessor>) -> LoggerProvider {
+        LoggerProvider::builder()
+            .with_log_processor(processor)
+            .build()
+    }
+
+    fn emit(
+        provider: &LoggerProvider,
+        target: &'static str,
+        severity: Severity,
+        trace_flags: Option<TraceFlags>,
+        count: usize,
+    ) {
+        let logger = provider.logger("test-logger");
+        for _ in 0..count {
+            let mut record = logger.create_log_record();
+            record.set_target(target);
+            record.set_severity_number(severity);
+            record.set_body(AnyValue::from(target));
+            if let Some(trace_flags) = trace_flags {
+                record.set_trace_context(
+                    TraceId::from_u128(1),
+                    SpanId::from_u64(1),
+                    Some(trace_flags),
+                );
+            }
+            logger.emit(record);
+        }
+    }
+
+    fn count(exporter: &InMemoryLogExporter, target: &str, severity: Severity) -> usize {
+        exporter
+            .get_emitted_logs()
+            .unwrap()
+            .iter()
+            .filter(|log| {
+                log.record.target.as_deref() == Some(target)
+                    && log.record.severity_number == Some(severity)
+            })
+            .count()
+    }
+
+    #[test]
+    fn samples_by_severity_and_target() {
+        let exporter = InMemoryLogExporter::default();
+        let provider = provider(
+            SamplingLogProcessor::new(SimpleLogProcessor::new(Box::new(exporter.clone())))
+                .with_ratio(Severity::Debug, 0.01)
+                .with_ratio(Severity::Trace, 0.0)
+                .with_target_ratio("db", Severity::Debug, 0.5),
+        );
+
+        emit(&provider, "app", Severity::Debug2, None, 300);
+        emit(&provider, "app", Severity::Trace, None, 10);
+        emit(&provider, "app", Severity::Warn, None, 10);
+        emit(&provider, "db::pool", Severity::Debug, None, 10);
+
+        assert_eq!(count(&exporter, "app", Severity::Debug2), 3);
+        assert_eq!(count(&exporter, "app", Severity::Trace), 0);
+        assert_eq!(count(&exporter, "app", Severity::Warn), 10);
+        assert_eq!(count(&exporter, "db::pool", Severity::Debug), 5);
+    }
+
+    #[test]
+    fn targets_with_different_ratios_are_sampled_separately() {
+        let exporter = InMemoryLogExporter::default();
+        let provider = provider(
+            SamplingLogProcessor::new(SimpleLogProcessor::new(Box::new(exporter.clone())))
+                .with_ratio(Severity::Debug, 0.25)
+                .with_target_ratio("a", Severity::Debug, 0.5)
+                .with_target_ratio("b", Severity::Debug, 0.1),
+        );
+
+        for _ in 0..100 {
+            emit(&provider, "a", Severity::Debug, None, 1);
+            emit(&provider, "b", Severity::Debug, None, 1);
+            emit(&provider, "c", Severity::Debug, None, 1);
+        }
+
+        assert_eq!(count(&exporter, "a", Severity::Debug), 50);
+        assert_eq!(count(&exporter, "b", Severity::Debug), 10);
+        assert_eq!(count(&exporter, "c", Severity::Debug), 25);
+    }
+
+    #[test]
+    fn rate_limits_per_target() {
+        let exporter = InMemoryLogExporter::default();
+        let provider = provider(
+            SamplingLogProcessor::new(SimpleLogProcessor::new(Box::new(exporter.clone())))
+                .with_rate_limit(3, Severity::Info4),
+        );
+
+        emit(&provider, "a", Severity::Info, None, 10);
+        emit(&provider, "b", Severity::Info, None, 10);
+        emit(&provider, "a", Severity::Error, None, 10);
+
+        assert_eq!(count(&exporter, "a", Severity::Info), 3);
+        assert_eq!(count(&exporter, "b", Severity::Info), 3);
+        assert_eq!(count(&exporter, "a", Severity::Error), 10);
+    }
+
+    #[test]
+    fn targets_beyond_the_limit_share_a_bucket() {
+        let processor = SamplingLogProcessor::new(SimpleLogProcessor::new(Box::new(
+            InMemoryLogExporter::default(),
+        )))
+        .with_rate_limit(3, Severity::Info4);
+
+        for target in 0..MAX_RATE_LIMITED_TARGETS {
+            assert!(processor.within_rate_limit(Some(Severity::Info), &target.to_string()));
+        }
+        let kept = (0..10)
+            .filter(|i| processor.within_rate_limit(Some(Severity::Info), &format!("new{i}")))
+            .count();
+
+        assert_eq!(kept, 3);
+        let buckets = processor
+            .rate_limit
+            .as_ref()
+            .unwrap()
+            .buckets
+            .lock()
+            .unwrap();
+        assert_eq!(buckets.targets.len(), MAX_RATE_LIMITED_TARGETS);
+    }
+
+    #[test]
+    fn counts_dropped_records() {
+        let processor = SamplingLogProcessor::new(SimpleLogProcessor::new(Box::new(
+            InMemoryLogExporter::default(),
+        )))
+        .with_ratio(Severity::Debug, 0.5)
+        .with_rate_limit(3, Severity::Info4);
+        let scope = InstrumentationScope::builder("test").build();
+
+        for severity in [Severity::Debug, Severity::Info] {
+            for _ in 0..10 {
+                let mut record = LogRecord::default();
+                record.set_target("app");
+                record.set_severity_number(severity);
+                processor.emit(&mut record, &scope);
+            }
+        }
+
+        assert_eq!(processor.dropped_by_sampling(), 5);
+        // 3 tokens for the 5 sampled DEBUG and 10 INFO records
+        assert_eq!(processor.dropped_by_rate_limit(), 12);
+    }
+
+    #[test]
+    fn trace_aware_keeps_sampled_traces() {
+        let exporter = InMemoryLogExporter::default();
+        let provider = provider(
+            SamplingLogProcessor::new(SimpleLogProcessor::new(Box::new(exporter.clone())))
+                .with_ratio(Severity::Info, 0.0)
+                .with_rate_limit(1, Severity::Fatal4)
+                .with_trace_aware(),
+        );
+
+        emit(
+            &provider,
+            "sampled",
+            Severity::Info,
+            Some(TraceFlags::SAMPLED),
+            5,
+        );
+        emit(
+            &provider,
+            "unsampled",
+            Severity::Info,
+            Some(TraceFlags::default()),
+            5,
+        );
+
+        assert_eq!(count(&exporter, "sampled", Severity::Info), 5);
+        assert_eq!(count(&exporter, "unsampled", Severity::Info), 0);
+    }
+}
@@ -19,6 +19,7 @@ use once_cell::sync::Lazy;
 use opentelemetry::{otel_warn, KeyValue};
 
 use crate::metrics::AttributeSet;
+use crate::self_metrics;
 
 pub(crate) static STREAM_OVERFLOW_ATTRIBUTES: Lazy<Vec<KeyValue>> =
     Lazy::new(|| vec![KeyValue::new("otel.metric.overflow", "true")]);
@@ -125,6 +126,7 @@ where
             self.count.fetch_add(1, Ordering::SeqCst);
         } else if let Some(overflow_value) = trackers.get(STREAM_OVERFLOW_ATTRIBUTES.as_slice()) {
             overflow_value.update(value);
+            self_metrics::record_cardinality_overflow();
         } else {
             let new_tracker = A::create(&self.config);
             new_tracker.update(value);
@@ -132,6 +134,7 @@ where
             otel_warn!( name: "ValueMap.measure",
                 message = "Maximum data points for metric stream exceeded. Entry added to overflow. Subsequent overflows to same metric until next collect will not be logged."
             );
+            self_metrics::record_cardinality_overflow();
         }
     }
 
@@ -16,6 +16,7 @@ use crate::metrics::{
     pipeline::{Pipelines, Resolver},
     MetricError, MetricResult,
 };
+use crate::self_metrics::CallbackMetrics;
 
 use super::noop::NoopSyncInstrument;
 
@@ -150,8 +151,9 @@ impl SdkMeter {
 
                 for callback in builder.callbacks {
                     let cb_inst = Arc::clone(&observable);
+                    let cb_metrics = CallbackMetrics::new(&self.scope, &builder.name);
                     self.pipes
-                        .register_callback(move || callback(cb_inst.as_ref()));
+                        .register_callback(move || cb_metrics.time(|| callback(cb_inst.as_ref())));
                 }
 
                 ObservableCounter::new()
@@ -209,8 +211,9 @@ impl SdkMeter {
 
                 for callback in builder.callbacks {
                     let cb_inst = Arc::clone(&observable);
+                    let cb_metrics = CallbackMetrics::new(&self.scope, &builder.name);
                     self.pipes
-                        .register_callback(move || callback(cb_inst.as_ref()));
+                        .register_callback(move || cb_metrics.time(|| callback(cb_inst.as_ref())));
                 }
 
                 ObservableUpDownCounter::new()
@@ -268,8 +271,9 @@ impl SdkMeter {
 
                 for callback in builder.callbacks {
                     let cb_inst = Arc::clone(&observable);
+                    let cb_metrics = CallbackMetrics::new(&self.scope, &builder.name);
                     self.pipes
-                        .register_callback(move || callback(cb_inst.as_ref()));
+                        .register_callback(move || cb_metrics.time(|| callback(cb_inst.as_ref())));
                 }
 
                 ObservableGauge::new()
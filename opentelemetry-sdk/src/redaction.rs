@@ -0,0 +1,28 @@
+//! Shared pieces of the attribute redaction processors of the trace and logs
+//! SDKs.
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+
+/// What happens to a value matched by an `AttributeRedactionProcessor` rule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Redaction {
+    /// Removes the attribute.
+    Drop,
+    /// Replaces the value, or the matched parts of string values for value
+    /// rules, with the given string, e.g. `"***"`.
+    Mask(Cow<'static, str>),
+    /// Replaces the value with the hex encoded SHA-256 hash of its string
+    /// representation, keeping values correlatable without revealing them.
+    Hash,
+}
+
+/// Returns the hex encoded SHA-256 hash of `value`.
+pub(crate) fn hash(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
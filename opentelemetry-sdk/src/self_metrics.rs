@@ -0,0 +1,680 @@
+//! # SDK self-observability metrics
+//!
+//! With the `experimental_self_metrics` feature, the SDK can record metrics
+//! about its own pipelines through a [`MeterProvider`] installed with
+//! [`set_meter_provider`]. Until then, and without the feature, nothing is
+//! recorded.
+//!
+//! The instruments follow the semantic conventions for `otel.sdk.*` metrics.
+//! Span and log processors are identified by the `otel.component.type` and
+//! `otel.component.name` attributes, for example `batching_span_processor`
+//! and `batching_span_processor/0`; the exporter metrics are attributed to
+//! the processor driving the exporter.
+//!
+//! | Instrument | Kind | Recorded when |
+//! |---|---|---|
+//! | `otel.sdk.processor.span.processed` | Counter | spans are handed to the exporter, or dropped with `error.type` `queue_full` |
+//! | `otel.sdk.processor.log.processed` | Counter | same for log records |
+//! | `otel.sdk.processor.span.queue.size` | UpDownCounter | observed for each batch processor |
+//! | `otel.sdk.processor.span.queue.capacity` | UpDownCounter | observed for each batch processor |
+//! | `otel.sdk.processor.log.queue.size` | UpDownCounter | same for log records |
+//! | `otel.sdk.processor.log.queue.capacity` | UpDownCounter | same for log records |
+//! | `otel.sdk.exporter.span.exported` | Counter | an export ends, with `error.type` if it failed |
+//! | `otel.sdk.exporter.log.exported` | Counter | same for log records |
+//! | `otel.sdk.exporter.operation.duration` | Histogram | an export ends, in seconds |
+//! | `otel.sdk.metric.cardinality_overflow` | Counter | a measurement is aggregated into the overflow data point |
+//! | `otel.sdk.metric.callback.duration` | Histogram | an observable instrument callback returns, in seconds |
+//!
+//! The last two are not covered by the semantic conventions yet.
+//!
+//! ## Feedback loops
+//!
+//! The provider is typically a separate, small [`SdkMeterProvider`], but it
+//! can be the provider being observed. Recording a self-metric never records
+//! another one on the same thread: an overflow of a self-metric stream is
+//! not counted, and the callbacks of the self-metrics instruments are not
+//! timed. Self-metrics are recorded synchronously and do not emit spans or
+//! logs, but an exporter of the self-metrics provider that is itself
+//! instrumented will feed its telemetry back into the observed pipelines.
+//!
+//! [`MeterProvider`]: opentelemetry::metrics::MeterProvider
+//! [`SdkMeterProvider`]: crate::metrics::SdkMeterProvider
+#[cfg(any(feature = "trace", feature = "logs"))]
+use crate::batch_queue::QueueStats;
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+use crate::batch_queue::WeakQueueStats;
+
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+use opentelemetry::KeyValue;
+#[cfg(feature = "experimental_self_metrics")]
+use opentelemetry::{
+    metrics::{Counter, Histogram, MeterProvider},
+    InstrumentationScope,
+};
+#[cfg(feature = "experimental_self_metrics")]
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+    time::Instant,
+};
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicUsize, Arc, Mutex},
+};
+
+/// The instrumentation scope name of the self-metrics instruments.
+#[cfg(feature = "experimental_self_metrics")]
+const SCOPE_NAME: &str = "opentelemetry_sdk";
+
+#[cfg(feature = "experimental_self_metrics")]
+const DURATION_BOUNDARIES: [f64; 14] = [
+    0.005, 0.01, 0.025, 0.05, 0.075, 0.1, 0.25, 0.5, 0.75, 1.0, 2.5, 5.0, 7.5, 10.0,
+];
+
+#[cfg(feature = "experimental_self_metrics")]
+static ENABLED: AtomicBool = AtomicBool::new(false);
+#[cfg(feature = "experimental_self_metrics")]
+static INSTRUMENTS: RwLock<Option<Instruments>> = RwLock::new(None);
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+static QUEUES: Mutex<Vec<QueueEntry>> = Mutex::new(Vec::new());
+/// The signals of the batch processor queues, with the unit of their items.
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+const QUEUE_SIGNALS: &[(Signal, &str)] = &[
+    #[cfg(feature = "trace")]
+    (Signal::Traces, "{span}"),
+    #[cfg(feature = "logs")]
+    (Signal::Logs, "{log_record}"),
+];
+/// Incremented by each call to [`set_meter_provider`], so that only the queue
+/// callbacks registered by the last call observe the queues.
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "experimental_self_metrics")]
+thread_local! {
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Records the SDK's own metrics through `provider`, replacing the provider
+/// set previously, if any.
+///
+/// Components created before this call are observed as well. The
+/// instruments registered by a previous call stop recording, so calling it
+/// again with the same provider does not duplicate them.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::metrics::{ManualReader, SdkMeterProvider};
+///
+/// let self_metrics_provider = SdkMeterProvider::builder()
+///     .with_reader(ManualReader::builder().build())
+///     .build();
+/// opentelemetry_sdk::self_metrics::set_meter_provider(&self_metrics_provider);
+/// ```
+#[cfg(feature = "experimental_self_metrics")]
+pub fn set_meter_provider<P: MeterProvider + ?Sized>(provider: &P) {
+    let scope = InstrumentationScope::builder(SCOPE_NAME)
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .build();
+    let meter = provider.meter_with_scope(scope);
+
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    let generation = GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    for &(signal, unit) in QUEUE_SIGNALS {
+        let name = signal.name();
+        meter
+            .i64_observable_up_down_counter(format!("otel.sdk.processor.{name}.queue.size"))
+            .with_description("The number of items in the queue of a batch processor.")
+            .with_unit(unit)
+            .with_callback(move |observer| {
+                observe_queues(generation, signal, |entry, stats| {
+                    observer.observe(stats.queued() as i64, &entry.attributes)
+                })
+            })
+            .build();
+        meter
+            .i64_observable_up_down_counter(format!("otel.sdk.processor.{name}.queue.capacity"))
+            .with_description("The maximum number of items in the queue of a batch processor.")
+            .with_unit(unit)
+            .with_callback(move |observer| {
+                observe_queues(generation, signal, |entry, _| {
+                    observer.observe(entry.capacity as i64, &entry.attributes)
+                })
+            })
+            .build();
+    }
+
+    let instruments = Instruments {
+        #[cfg(feature = "trace")]
+        span_processed: meter
+            .u64_counter("otel.sdk.processor.span.processed")
+            .with_description("The number of spans for which the processing has finished.")
+            .with_unit("{span}")
+            .build(),
+        #[cfg(feature = "logs")]
+        log_processed: meter
+            .u64_counter("otel.sdk.processor.log.processed")
+            .with_description("The number of log records for which the processing has finished.")
+            .with_unit("{log_record}")
+            .build(),
+        #[cfg(feature = "trace")]
+        span_exported: meter
+            .u64_counter("otel.sdk.exporter.span.exported")
+            .with_description("The number of spans for which the export has finished.")
+            .with_unit("{span}")
+            .build(),
+        #[cfg(feature = "logs")]
+        log_exported: meter
+            .u64_counter("otel.sdk.exporter.log.exported")
+            .with_description("The number of log records for which the export has finished.")
+            .with_unit("{log_record}")
+            .build(),
+        #[cfg(any(feature = "trace", feature = "logs"))]
+        export_duration: meter
+            .f64_histogram("otel.sdk.exporter.operation.duration")
+            .with_description("The duration of exporting a batch of telemetry records.")
+            .with_unit("s")
+            .with_boundaries(DURATION_BOUNDARIES.to_vec())
+            .build(),
+        cardinality_overflow: meter
+            .u64_counter("otel.sdk.metric.cardinality_overflow")
+            .with_description("The number of measurements aggregated into an overflow data point.")
+            .with_unit("{measurement}")
+            .build(),
+        callback_duration: meter
+            .f64_histogram("otel.sdk.metric.callback.duration")
+            .with_description("The duration of an observable instrument callback.")
+            .with_unit("s")
+            .with_boundaries(DURATION_BOUNDARIES.to_vec())
+            .build(),
+    };
+
+    if let Ok(mut current) = INSTRUMENTS.write() {
+        *current = Some(instruments);
+        ENABLED.store(true, Ordering::Release);
+    }
+}
+
+#[cfg(feature = "experimental_self_metrics")]
+#[derive(Debug)]
+struct Instruments {
+    #[cfg(feature = "trace")]
+    span_processed: Counter<u64>,
+    #[cfg(feature = "logs")]
+    log_processed: Counter<u64>,
+    #[cfg(feature = "trace")]
+    span_exported: Counter<u64>,
+    #[cfg(feature = "logs")]
+    log_exported: Counter<u64>,
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    export_duration: Histogram<f64>,
+    cardinality_overflow: Counter<u64>,
+    callback_duration: Histogram<f64>,
+}
+
+/// Runs `f` with the instruments, unless self-metrics are disabled or already
+/// being recorded on this thread.
+#[cfg(feature = "experimental_self_metrics")]
+fn record(f: impl FnOnce(&Instruments)) {
+    if !ENABLED.load(Ordering::Acquire) {
+        return;
+    }
+    let _ = RECORDING.try_with(|recording| {
+        if recording.replace(true) {
+            return;
+        }
+        if let Ok(instruments) = INSTRUMENTS.read() {
+            if let Some(instruments) = instruments.as_ref() {
+                f(instruments);
+            }
+        }
+        recording.set(false);
+    });
+}
+
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+fn observe_queues(
+    generation: usize,
+    signal: Signal,
+    mut observe: impl FnMut(&QueueEntry, &QueueStats),
+) {
+    if GENERATION.load(Ordering::Acquire) != generation {
+        return;
+    }
+    let _ = RECORDING.try_with(|recording| {
+        let was_recording = recording.replace(true);
+        if let Ok(mut queues) = QUEUES.lock() {
+            queues.retain(|entry| match entry.stats.upgrade() {
+                Some(stats) => {
+                    if entry.signal == signal {
+                        observe(entry, &stats);
+                    }
+                    true
+                }
+                None => false,
+            });
+        }
+        recording.set(was_recording);
+    });
+}
+
+/// The signal of the items handled by a component.
+#[cfg(any(feature = "trace", feature = "logs"))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Signal {
+    #[cfg(feature = "trace")]
+    Traces,
+    #[cfg(feature = "logs")]
+    Logs,
+}
+
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+impl Signal {
+    fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "trace")]
+            Signal::Traces => "span",
+            #[cfg(feature = "logs")]
+            Signal::Logs => "log",
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+#[derive(Debug)]
+struct QueueEntry {
+    signal: Signal,
+    attributes: Arc<[KeyValue]>,
+    stats: WeakQueueStats,
+    capacity: usize,
+}
+
+/// The error kind recorded as `error.type` for a failed export.
+#[cfg(all(
+    feature = "experimental_self_metrics",
+    any(feature = "trace", feature = "logs")
+))]
+pub(crate) trait ErrorType {
+    fn error_type(&self) -> &'static str;
+}
+
+#[cfg(all(feature = "experimental_self_metrics", feature = "trace"))]
+impl ErrorType for opentelemetry::trace::TraceError {
+    fn error_type(&self) -> &'static str {
+        match self {
+            opentelemetry::trace::TraceError::ExportFailed(_) => "export_failed",
+            opentelemetry::trace::TraceError::ExportTimedOut(_) => "timeout",
+            _ => "_OTHER",
+        }
+    }
+}
+
+#[cfg(all(feature = "experimental_self_metrics", feature = "logs"))]
+impl ErrorType for crate::logs::LogError {
+    fn error_type(&self) -> &'static str {
+        match self {
+            crate::logs::LogError::ExportFailed(_) => "export_failed",
+            crate::logs::LogError::ExportTimedOut(_) => "timeout",
+            _ => "_OTHER",
+        }
+    }
+}
+
+/// The self-metrics of a span or log processor.
+#[cfg(any(feature = "trace", feature = "logs"))]
+#[derive(Clone, Debug)]
+pub(crate) struct ComponentMetrics {
+    #[cfg(feature = "experimental_self_metrics")]
+    signal: Signal,
+    #[cfg(feature = "experimental_self_metrics")]
+    attributes: Arc<[KeyValue]>,
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl ComponentMetrics {
+    /// Names the component after `component_type` and the count of
+    /// components of that type created before it.
+    #[cfg(feature = "experimental_self_metrics")]
+    pub(crate) fn new(signal: Signal, component_type: &'static str) -> Self {
+        static INSTANCES: Mutex<Option<HashMap<&'static str, usize>>> = Mutex::new(None);
+        let index = INSTANCES
+            .lock()
+            .map(|mut instances| {
+                let count = instances
+                    .get_or_insert_with(HashMap::new)
+                    .entry(component_type)
+                    .or_default();
+                *count += 1;
+                *count - 1
+            })
+            .unwrap_or_default();
+        ComponentMetrics {
+            signal,
+            attributes: Arc::new([
+                KeyValue::new("otel.component.type", component_type),
+                KeyValue::new("otel.component.name", format!("{component_type}/{index}")),
+            ]),
+        }
+    }
+
+    #[cfg(not(feature = "experimental_self_metrics"))]
+    pub(crate) fn new(_signal: Signal, _component_type: &'static str) -> Self {
+        ComponentMetrics {}
+    }
+
+    /// Makes the queue observable through the queue size instruments for as
+    /// long as it is alive.
+    pub(crate) fn register_queue(&self, stats: &QueueStats, capacity: usize) {
+        #[cfg(feature = "experimental_self_metrics")]
+        if let Ok(mut queues) = QUEUES.lock() {
+            queues.push(QueueEntry {
+                signal: self.signal,
+                attributes: self.attributes.clone(),
+                stats: stats.downgrade(),
+                capacity,
+            });
+        }
+        #[cfg(not(feature = "experimental_self_metrics"))]
+        let _ = (stats, capacity);
+    }
+
+    /// Records items dropped because the queue was full.
+    pub(crate) fn record_dropped(&self, count: u64) {
+        #[cfg(feature = "experimental_self_metrics")]
+        record(|instruments| {
+            let attributes = self.with_error_type("queue_full");
+            match self.signal {
+                #[cfg(feature = "trace")]
+                Signal::Traces => instruments.span_processed.add(count, &attributes),
+                #[cfg(feature = "logs")]
+                Signal::Logs => instruments.log_processed.add(count, &attributes),
+            }
+        });
+        #[cfg(not(feature = "experimental_self_metrics"))]
+        let _ = count;
+    }
+
+    /// Starts timing an export.
+    pub(crate) fn start_export(&self) -> ExportTimer {
+        ExportTimer {
+            #[cfg(feature = "experimental_self_metrics")]
+            metrics: self.clone(),
+            #[cfg(feature = "experimental_self_metrics")]
+            start: ENABLED.load(Ordering::Relaxed).then(Instant::now),
+        }
+    }
+
+    #[cfg(feature = "experimental_self_metrics")]
+    fn with_error_type(&self, error_type: &'static str) -> Vec<KeyValue> {
+        let mut attributes = self.attributes.to_vec();
+        attributes.push(KeyValue::new("error.type", error_type));
+        attributes
+    }
+}
+
+/// Records the outcome of an export started with
+/// [`ComponentMetrics::start_export`].
+#[cfg(any(feature = "trace", feature = "logs"))]
+#[derive(Debug)]
+pub(crate) struct ExportTimer {
+    #[cfg(feature = "experimental_self_metrics")]
+    metrics: ComponentMetrics,
+    #[cfg(feature = "experimental_self_metrics")]
+    start: Option<Instant>,
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl ExportTimer {
+    /// Records that `count` items were processed and exported with `result`.
+    #[cfg(feature = "experimental_self_metrics")]
+    pub(crate) fn finish<E: ErrorType>(self, count: usize, result: &Result<(), E>) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let metrics = &self.metrics;
+        record(|instruments| {
+            let count = count as u64;
+            let attributes = match result {
+                Ok(()) => metrics.attributes.to_vec(),
+                Err(err) => metrics.with_error_type(err.error_type()),
+            };
+            let (processed, exported) = match metrics.signal {
+                #[cfg(feature = "trace")]
+                Signal::Traces => (&instruments.span_processed, &instruments.span_exported),
+                #[cfg(feature = "logs")]
+                Signal::Logs => (&instruments.log_processed, &instruments.log_exported),
+            };
+            processed.add(count, &metrics.attributes);
+            exported.add(count, &attributes);
+            instruments
+                .export_duration
+                .record(start.elapsed().as_secs_f64(), &attributes);
+        });
+    }
+
+    #[cfg(not(feature = "experimental_self_metrics"))]
+    pub(crate) fn finish<E>(self, _count: usize, _result: &Result<(), E>) {}
+}
+
+/// Records a measurement aggregated into the overflow data point of a stream.
+#[cfg(feature = "metrics")]
+pub(crate) fn record_cardinality_overflow() {
+    #[cfg(feature = "experimental_self_metrics")]
+    record(|instruments| instruments.cardinality_overflow.add(1, &[]));
+}
+
+/// Times the callbacks of an observable instrument.
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+pub(crate) struct CallbackMetrics {
+    /// `None` for the self-metrics instruments, which are not timed.
+    #[cfg(feature = "experimental_self_metrics")]
+    attributes: Option<[opentelemetry::KeyValue; 2]>,
+}
+
+#[cfg(feature = "metrics")]
+impl CallbackMetrics {
+    #[cfg(feature = "experimental_self_metrics")]
+    pub(crate) fn new(scope: &opentelemetry::InstrumentationScope, instrument: &str) -> Self {
+        CallbackMetrics {
+            attributes: (scope.name() != SCOPE_NAME).then(|| {
+                [
+                    opentelemetry::KeyValue::new("otel.scope.name", scope.name().to_owned()),
+                    opentelemetry::KeyValue::new("otel.instrument.name", instrument.to_owned()),
+                ]
+            }),
+        }
+    }
+
+    #[cfg(not(feature = "experimental_self_metrics"))]
+    pub(crate) fn new(_scope: &opentelemetry::InstrumentationScope, _instrument: &str) -> Self {
+        CallbackMetrics {}
+    }
+
+    pub(crate) fn time(&self, callback: impl FnOnce()) {
+        #[cfg(feature = "experimental_self_metrics")]
+        if let Some(attributes) = &self.attributes {
+            if ENABLED.load(Ordering::Relaxed) {
+                let start = Instant::now();
+                callback();
+                let duration = start.elapsed().as_secs_f64();
+                record(|instruments| instruments.callback_duration.record(duration, attributes));
+                return;
+            }
+        }
+        callback();
+    }
+}
+
+#[cfg(all(test, feature = "experimental_self_metrics", feature = "testing"))]
+mod tests {
+    // cargo test self_metrics --features=testing,experimental_self_metrics
+    use super::*;
+    use crate::batch_queue::{BatchQueue, QueueFullPolicy};
+    use crate::metrics::{data, PeriodicReader, SdkMeterProvider};
+    use crate::runtime;
+    use crate::testing::metrics::InMemoryMetricExporter;
+    use opentelemetry::trace::TraceError;
+    use std::time::Duration;
+
+    fn has_attribute(attributes: &[KeyValue], key: &str, value: &str) -> bool {
+        attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == key && kv.value.as_str() == value)
+    }
+
+    // The provider is global, so a single test covers the instruments, with
+    // the component names of this test only. The cardinality overflow count
+    // is checked in tests/self_metrics.rs.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn records_through_the_observed_provider() {
+        let exporter = InMemoryMetricExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let provider = SdkMeterProvider::builder().with_reader(reader).build();
+        set_meter_provider(&provider);
+        // setting the same provider again does not duplicate the instruments
+        set_meter_provider(&provider);
+
+        let metrics = ComponentMetrics::new(Signal::Traces, "test_span_processor");
+        let queue = BatchQueue::new("test", 1, QueueFullPolicy::DropNewest, metrics.clone());
+        queue.push(());
+        queue.push(());
+        metrics.start_export().finish(3, &Ok::<(), TraceError>(()));
+        metrics
+            .start_export()
+            .finish(2, &Err(TraceError::ExportTimedOut(Duration::from_secs(1))));
+
+        provider
+            .meter("test")
+            .u64_observable_gauge("test.gauge")
+            .with_callback(|observer| observer.observe(1, &[]))
+            .build();
+
+        provider.force_flush().unwrap();
+        let resource_metrics = exporter.get_finished_metrics().unwrap();
+        let scope = resource_metrics[0]
+            .scope_metrics
+            .iter()
+            .find(|sm| sm.scope.name() == SCOPE_NAME)
+            .unwrap();
+        let metric = |name: &str| scope.metrics.iter().find(|m| m.name == name).unwrap();
+        let ours = |attributes: &[KeyValue]| {
+            has_attribute(attributes, "otel.component.name", "test_span_processor/0")
+        };
+
+        let processed = metric("otel.sdk.processor.span.processed")
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .unwrap();
+        let processed = |error_type: Option<&str>| {
+            processed
+                .data_points
+                .iter()
+                .find(|dp| {
+                    ours(&dp.attributes)
+                        && match error_type {
+                            Some(error_type) => {
+                                has_attribute(&dp.attributes, "error.type", error_type)
+                            }
+                            None => dp.attributes.len() == 2,
+                        }
+                })
+                .map(|dp| dp.value)
+        };
+        assert_eq!(processed(None), Some(5));
+        assert_eq!(processed(Some("queue_full")), Some(1));
+
+        let exported = metric("otel.sdk.exporter.span.exported")
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .unwrap();
+        let failed = exported
+            .data_points
+            .iter()
+            .find(|dp| {
+                ours(&dp.attributes) && has_attribute(&dp.attributes, "error.type", "timeout")
+            })
+            .unwrap();
+        assert_eq!(failed.value, 2);
+
+        let duration = metric("otel.sdk.exporter.operation.duration")
+            .data
+            .as_any()
+            .downcast_ref::<data::Histogram<f64>>()
+            .unwrap();
+        let count: u64 = duration
+            .data_points
+            .iter()
+            .filter(|dp| ours(&dp.attributes))
+            .map(|dp| dp.count)
+            .sum();
+        assert_eq!(count, 2);
+
+        let queue_size = metric("otel.sdk.processor.span.queue.size")
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<i64>>()
+            .unwrap();
+        let queued = queue_size
+            .data_points
+            .iter()
+            .find(|dp| ours(&dp.attributes))
+            .unwrap();
+        assert_eq!(queued.value, 1);
+
+        let callbacks = metric("otel.sdk.metric.callback.duration")
+            .data
+            .as_any()
+            .downcast_ref::<data::Histogram<f64>>()
+            .unwrap();
+        assert!(callbacks.data_points.iter().any(|dp| has_attribute(
+            &dp.attributes,
+            "otel.instrument.name",
+            "test.gauge"
+        )));
+        // the self-metrics callbacks are not timed
+        assert!(!callbacks.data_points.iter().any(|dp| has_attribute(
+            &dp.attributes,
+            "otel.scope.name",
+            SCOPE_NAME
+        )));
+
+        drop(queue);
+        provider.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,533 @@
+//! # Span connectors
+//!
+//! Connectors are [`SpanProcessor`]s deriving other signals from finished
+//! spans, in process, much like the collector's `spanmetrics` connector:
+//!
+//! * [`SpanMetricsProcessor`] records request, error and duration (RED)
+//!   metrics through a [`Meter`].
+//! * [`ErrorSpanLogProcessor`] emits a log record for every span ending with
+//!   an error status through a [`Logger`].
+//!
+//! Both are registered on the [`TracerProvider`] next to the processors
+//! exporting the spans themselves.
+//!
+//! [`Meter`]: opentelemetry::metrics::Meter
+//! [`Logger`]: opentelemetry::logs::Logger
+//! [`TracerProvider`]: crate::trace::TracerProvider
+
+use crate::export::trace::SpanData;
+#[cfg(feature = "metrics")]
+use crate::resource::{Resource, SERVICE_NAME};
+use crate::trace::{Span, SpanProcessor};
+use opentelemetry::trace::{SpanKind, Status, TraceResult};
+use opentelemetry::Context;
+#[cfg(feature = "metrics")]
+use opentelemetry::{Key, KeyValue, StringValue};
+#[cfg(feature = "metrics")]
+use std::borrow::Cow;
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+
+/// Returns the `span.kind` value used by the collector's `spanmetrics`
+/// connector, and expected by Jaeger's service performance monitoring.
+fn span_kind_value(kind: &SpanKind) -> &'static str {
+    match kind {
+        SpanKind::Client => "SPAN_KIND_CLIENT",
+        SpanKind::Server => "SPAN_KIND_SERVER",
+        SpanKind::Producer => "SPAN_KIND_PRODUCER",
+        SpanKind::Consumer => "SPAN_KIND_CONSUMER",
+        SpanKind::Internal => "SPAN_KIND_INTERNAL",
+    }
+}
+
+/// Returns the `status.code` value used by the collector's `spanmetrics`
+/// connector.
+#[cfg(feature = "metrics")]
+fn status_code_value(status: &Status) -> &'static str {
+    match status {
+        Status::Unset => "STATUS_CODE_UNSET",
+        Status::Error { .. } => "STATUS_CODE_ERROR",
+        Status::Ok => "STATUS_CODE_OK",
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn service_name(resource: &Resource) -> Option<StringValue> {
+    resource
+        .get(Key::from_static_str(SERVICE_NAME))
+        .map(|value| value.as_str().into_owned().into())
+}
+
+#[cfg(feature = "metrics")]
+const DEFAULT_NAMESPACE: &str = "traces.span.metrics";
+
+/// Default bucket boundaries of the duration histogram, in milliseconds, as
+/// used by the collector's `spanmetrics` connector.
+#[cfg(feature = "metrics")]
+const DEFAULT_DURATION_BOUNDARIES: [f64; 16] = [
+    2.0, 4.0, 6.0, 8.0, 10.0, 50.0, 100.0, 200.0, 400.0, 800.0, 1_000.0, 1_400.0, 2_000.0, 5_000.0,
+    10_000.0, 15_000.0,
+];
+
+/// A [`SpanProcessor`] recording request, error and duration metrics of
+/// finished spans.
+///
+/// Three instruments are created on the configured meter, named after the
+/// namespace (`traces.span.metrics` by default):
+///
+/// * `{namespace}.calls`, a counter of finished spans,
+/// * `{namespace}.errors`, a counter of spans ending with an error status,
+/// * `{namespace}.duration`, a histogram of span durations in milliseconds.
+///
+/// Measurements carry the `service.name` of the tracer provider resource and
+/// the `span.name`, `span.kind` and `status.code` of the span, with the values
+/// used by the collector's `spanmetrics` connector, so that Jaeger's service
+/// performance monitoring can read them. Additional span attributes can be
+/// added as dimensions with [`with_dimension`]. Keep them low cardinality.
+///
+/// Every span ended while recording is measured, whether it is sampled or not.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::metrics::MeterProvider as _;
+/// use opentelemetry_sdk::metrics::SdkMeterProvider;
+/// use opentelemetry_sdk::trace::{SpanMetricsProcessor, TracerProvider};
+///
+/// fn tracer_provider(meter_provider: &SdkMeterProvider) -> TracerProvider {
+///     let processor = SpanMetricsProcessor::builder(meter_provider.meter("span-metrics"))
+///         .with_dimension("http.request.method")
+///         .build();
+///
+///     TracerProvider::builder()
+///         .with_span_processor(processor)
+///         .build()
+/// }
+/// ```
+///
+/// [`with_dimension`]: SpanMetricsProcessorBuilder::with_dimension
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+pub struct SpanMetricsProcessor {
+    calls: opentelemetry::metrics::Counter<u64>,
+    errors: opentelemetry::metrics::Counter<u64>,
+    duration: opentelemetry::metrics::Histogram<f64>,
+    dimensions: Vec<Key>,
+    service_name: Option<StringValue>,
+}
+
+#[cfg(feature = "metrics")]
+impl SpanMetricsProcessor {
+    /// Create a new [`SpanMetricsProcessorBuilder`] recording to `meter`.
+    pub fn builder(meter: opentelemetry::metrics::Meter) -> SpanMetricsProcessorBuilder {
+        SpanMetricsProcessorBuilder {
+            meter,
+            namespace: Cow::Borrowed(DEFAULT_NAMESPACE),
+            dimensions: Vec::new(),
+            boundaries: DEFAULT_DURATION_BOUNDARIES.to_vec(),
+        }
+    }
+
+    fn attributes(&self, span: &SpanData) -> Vec<KeyValue> {
+        let mut attributes = Vec::with_capacity(4 + self.dimensions.len());
+        if let Some(service_name) = &self.service_name {
+            attributes.push(KeyValue::new(SERVICE_NAME, service_name.clone()));
+        }
+        attributes.push(KeyValue::new("span.name", span.name.clone()));
+        attributes.push(KeyValue::new("span.kind", span_kind_value(&span.span_kind)));
+        attributes.push(KeyValue::new(
+            "status.code",
+            status_code_value(&span.status),
+        ));
+        for dimension in &self.dimensions {
+            if let Some(kv) = span.attributes.iter().find(|kv| kv.key == *dimension) {
+                attributes.push(kv.clone());
+            }
+        }
+        attributes
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl SpanProcessor for SpanMetricsProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        // Ignored
+    }
+
+    fn on_end(&self, span: SpanData) {
+        let attributes = self.attributes(&span);
+        let duration = span
+            .end_time
+            .duration_since(span.start_time)
+            .unwrap_or(Duration::ZERO);
+
+        self.calls.add(1, &attributes);
+        if matches!(span.status, Status::Error { .. }) {
+            self.errors.add(1, &attributes);
+        }
+        self.duration
+            .record(duration.as_secs_f64() * 1_000.0, &attributes);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        // Measurements are flushed by the meter provider.
+        Ok(())
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.service_name = service_name(resource);
+    }
+}
+
+/// Builder for [`SpanMetricsProcessor`].
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+pub struct SpanMetricsProcessorBuilder {
+    meter: opentelemetry::metrics::Meter,
+    namespace: Cow<'static, str>,
+    dimensions: Vec<Key>,
+    boundaries: Vec<f64>,
+}
+
+#[cfg(feature = "metrics")]
+impl SpanMetricsProcessorBuilder {
+    /// Sets the prefix of the instrument names, `traces.span.metrics` by
+    /// default.
+    pub fn with_namespace(mut self, namespace: impl Into<Cow<'static, str>>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Adds the span attribute `key` to the attributes of the measurements,
+    /// when the span has it.
+    pub fn with_dimension(mut self, key: impl Into<Key>) -> Self {
+        let key = key.into();
+        if !self.dimensions.contains(&key) {
+            self.dimensions.push(key);
+        }
+        self
+    }
+
+    /// Sets the bucket boundaries of the duration histogram, in milliseconds.
+    pub fn with_duration_boundaries(mut self, boundaries: Vec<f64>) -> Self {
+        self.boundaries = boundaries;
+        self
+    }
+
+    /// Create the instruments and build the [`SpanMetricsProcessor`].
+    pub fn build(self) -> SpanMetricsProcessor {
+        let namespace = self.namespace;
+        SpanMetricsProcessor {
+            calls: self
+                .meter
+                .u64_counter(format!("{namespace}.calls"))
+                .with_unit("{call}")
+                .with_description("Number of finished spans")
+                .build(),
+            errors: self
+                .meter
+                .u64_counter(format!("{namespace}.errors"))
+                .with_unit("{call}")
+                .with_description("Number of spans ending with an error status")
+                .build(),
+            duration: self
+                .meter
+                .f64_histogram(format!("{namespace}.duration"))
+                .with_unit("ms")
+                .with_description("Duration of finished spans")
+                .with_boundaries(self.boundaries)
+                .build(),
+            dimensions: self.dimensions,
+            service_name: None,
+        }
+    }
+}
+
+/// A [`SpanProcessor`] emitting a log record through `logger` for every span
+/// ending with an error status.
+///
+/// The record is timestamped with the span end time, has the `ERROR`
+/// severity, the error description as body (or the span name when the
+/// description is empty) and the trace context of the span. Its attributes
+/// are the `span.name` and `span.kind` of the span, the `exception.*`
+/// attributes of its last `exception` event, if any, and the attributes of the
+/// span itself.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::LoggerProvider as _;
+/// use opentelemetry_sdk::logs::LoggerProvider;
+/// use opentelemetry_sdk::trace::{ErrorSpanLogProcessor, TracerProvider};
+///
+/// fn tracer_provider(logger_provider: &LoggerProvider) -> TracerProvider {
+///     let processor = ErrorSpanLogProcessor::new(logger_provider.logger("span-errors"));
+///
+///     TracerProvider::builder()
+///         .with_span_processor(processor)
+///         .build()
+/// }
+/// ```
+#[cfg(feature = "logs")]
+#[derive(Debug)]
+pub struct ErrorSpanLogProcessor<L> {
+    logger: L,
+}
+
+#[cfg(feature = "logs")]
+impl<L> ErrorSpanLogProcessor<L>
+where
+    L: opentelemetry::logs::Logger + Send + Sync + std::fmt::Debug,
+{
+    /// Create a new `ErrorSpanLogProcessor` emitting records through `logger`.
+    pub fn new(logger: L) -> Self {
+        ErrorSpanLogProcessor { logger }
+    }
+}
+
+#[cfg(feature = "logs")]
+impl<L> SpanProcessor for ErrorSpanLogProcessor<L>
+where
+    L: opentelemetry::logs::Logger + Send + Sync + std::fmt::Debug,
+{
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        // Ignored
+    }
+
+    fn on_end(&self, span: SpanData) {
+        use opentelemetry::logs::{AnyValue, LogRecord as _, Severity};
+
+        let Status::Error { description } = &span.status else {
+            return;
+        };
+
+        let mut record = self.logger.create_log_record();
+        record.set_event_name("span.error");
+        record.set_target(span.instrumentation_scope.name().to_owned());
+        record.set_timestamp(span.end_time);
+        record.set_observed_timestamp(std::time::SystemTime::now());
+        record.set_severity_number(Severity::Error);
+        record.set_severity_text("ERROR");
+        record.set_body(if description.is_empty() {
+            AnyValue::from(span.name.clone())
+        } else {
+            AnyValue::from(description.clone())
+        });
+        record.set_trace_context(
+            span.span_context.trace_id(),
+            span.span_context.span_id(),
+            Some(span.span_context.trace_flags()),
+        );
+
+        record.add_attribute("span.name", span.name.clone());
+        record.add_attribute("span.kind", span_kind_value(&span.span_kind));
+        if let Some(exception) = span
+            .events
+            .iter()
+            .rev()
+            .find(|event| event.name == "exception")
+        {
+            record.add_attributes(
+                exception
+                    .attributes
+                    .iter()
+                    .filter(|kv| kv.key.as_str().starts_with("exception."))
+                    .map(|kv| (kv.key.clone(), any_value(&kv.value))),
+            );
+        }
+        record.add_attributes(
+            span.attributes
+                .iter()
+                .map(|kv| (kv.key.clone(), any_value(&kv.value))),
+        );
+
+        self.logger.emit(record);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        // Records are flushed by the logger provider.
+        Ok(())
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "logs")]
+fn any_value(value: &opentelemetry::Value) -> opentelemetry::logs::AnyValue {
+    use opentelemetry::logs::AnyValue;
+    use opentelemetry::{Array, Value};
+
+    fn list<T: Clone + Into<AnyValue>>(values: &[T]) -> AnyValue {
+        AnyValue::ListAny(Box::new(values.iter().cloned().map(Into::into).collect()))
+    }
+
+    match value {
+        Value::Bool(value) => AnyValue::Boolean(*value),
+        Value::I64(value) => AnyValue::Int(*value),
+        Value::F64(value) => AnyValue::Double(*value),
+        Value::String(value) => AnyValue::String(value.clone()),
+        Value::Array(Array::Bool(values)) => list(values),
+        Value::Array(Array::I64(values)) => list(values),
+        Value::Array(Array::F64(values)) => list(values),
+        Value::Array(Array::String(values)) => list(values),
+        _ => AnyValue::String(value.as_str().into_owned().into()),
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{LoggerProvider, SimpleLogProcessor};
+    use crate::metrics::{data, PeriodicReader, SdkMeterProvider};
+    use crate::testing::logs::InMemoryLogExporter;
+    use crate::testing::metrics::InMemoryMetricExporter;
+    use crate::trace::TracerProvider;
+    use crate::{runtime, Resource};
+    use opentelemetry::logs::{AnyValue, LoggerProvider as _, Severity};
+    use opentelemetry::metrics::MeterProvider as _;
+    use opentelemetry::trace::{Span as _, Tracer as _, TracerProvider as _};
+    use opentelemetry::{Key, KeyValue};
+
+    fn has_attribute(attributes: &[KeyValue], key: &str, value: &str) -> bool {
+        attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == key && kv.value.as_str() == value)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn records_red_metrics() {
+        let exporter = InMemoryMetricExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+        let tracer_provider = TracerProvider::builder()
+            .with_resource(Resource::new(vec![KeyValue::new(SERVICE_NAME, "checkout")]))
+            .with_span_processor(
+                SpanMetricsProcessor::builder(meter_provider.meter("span-metrics"))
+                    .with_dimension("http.request.method")
+                    .build(),
+            )
+            .build();
+
+        let tracer = tracer_provider.tracer("test");
+        for status in [Status::Ok, Status::error("boom"), Status::error("boom")] {
+            let mut span = tracer
+                .span_builder("GET /cart")
+                .with_kind(SpanKind::Server)
+                .with_attributes([
+                    KeyValue::new("http.request.method", "GET"),
+                    KeyValue::new("url.path", "/cart/42"),
+                ])
+                .start(&tracer);
+            span.set_status(status);
+            span.end();
+        }
+        meter_provider.force_flush().unwrap();
+
+        let resource_metrics = exporter.get_finished_metrics().unwrap();
+        let metrics = &resource_metrics[0].scope_metrics[0].metrics;
+        let metric = |name: &str| metrics.iter().find(|m| m.name == name).unwrap();
+
+        let calls = metric("traces.span.metrics.calls")
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .unwrap();
+        assert_eq!(calls.data_points.len(), 2);
+        let error_calls = calls
+            .data_points
+            .iter()
+            .find(|dp| has_attribute(&dp.attributes, "status.code", "STATUS_CODE_ERROR"))
+            .unwrap();
+        assert_eq!(error_calls.value, 2);
+        assert!(has_attribute(
+            &error_calls.attributes,
+            "service.name",
+            "checkout"
+        ));
+        assert!(has_attribute(
+            &error_calls.attributes,
+            "span.name",
+            "GET /cart"
+        ));
+        assert!(has_attribute(
+            &error_calls.attributes,
+            "span.kind",
+            "SPAN_KIND_SERVER"
+        ));
+        assert!(has_attribute(
+            &error_calls.attributes,
+            "http.request.method",
+            "GET"
+        ));
+        assert!(!error_calls
+            .attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == "url.path"));
+
+        let errors = metric("traces.span.metrics.errors")
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .unwrap();
+        assert_eq!(errors.data_points.len(), 1);
+        assert_eq!(errors.data_points[0].value, 2);
+
+        let duration = metric("traces.span.metrics.duration");
+        assert_eq!(duration.unit, "ms");
+        let duration = duration
+            .data
+            .as_any()
+            .downcast_ref::<data::Histogram<f64>>()
+            .unwrap();
+        let count: u64 = duration.data_points.iter().map(|dp| dp.count).sum();
+        assert_eq!(count, 3);
+        assert_eq!(duration.data_points[0].bounds, DEFAULT_DURATION_BOUNDARIES);
+    }
+
+    #[test]
+    fn emits_log_record_per_error_span() {
+        let exporter = InMemoryLogExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_log_processor(SimpleLogProcessor::new(Box::new(exporter.clone())))
+            .build();
+        let tracer_provider = TracerProvider::builder()
+            .with_span_processor(ErrorSpanLogProcessor::new(
+                logger_provider.logger("span-errors"),
+            ))
+            .build();
+
+        let tracer = tracer_provider.tracer("test");
+        let mut ok = tracer.start("ok");
+        ok.set_status(Status::Ok);
+        ok.end();
+
+        let mut failed = tracer.start("failed");
+        let span_context = failed.span_context().clone();
+        failed.set_attribute(KeyValue::new("db.system", "postgresql"));
+        failed.record_error(&std::fmt::Error);
+        failed.set_status(Status::error("query failed"));
+        failed.end();
+
+        let logs = exporter.get_emitted_logs().unwrap();
+        assert_eq!(logs.len(), 1);
+        let record = &logs[0].record;
+        assert_eq!(record.severity_number, Some(Severity::Error));
+        assert_eq!(record.target.as_deref(), Some("test"));
+        assert_eq!(record.body, Some(AnyValue::from("query failed")));
+        let trace_context = record.trace_context.as_ref().unwrap();
+        assert_eq!(trace_context.trace_id, span_context.trace_id());
+        assert_eq!(trace_context.span_id, span_context.span_id());
+        assert!(record.attributes_contains(&Key::new("span.name"), &AnyValue::from("failed")));
+        assert!(record.attributes_contains(
+            &Key::new("exception.message"),
+            &AnyValue::from(std::fmt::Error.to_string())
+        ));
+        assert!(record.attributes_contains(&Key::new("db.system"), &AnyValue::from("postgresql")));
+    }
+}
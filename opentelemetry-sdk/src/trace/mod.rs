@@ -7,21 +7,33 @@
 //!   current operation execution.
 //! * The [`TracerProvider`] struct which configures and produces [`Tracer`]s.
 mod config;
+#[cfg(any(feature = "metrics", feature = "logs"))]
+mod connectors;
 mod events;
 mod id_generator;
 mod links;
+mod processors;
 mod provider;
 mod sampler;
 mod span;
 mod span_limit;
 mod span_processor;
+#[cfg(feature = "experimental_trace_batch_span_processor_no_runtime")]
+mod span_processor_with_own_thread;
 mod tracer;
 
 pub use config::{config, Config};
+#[cfg(feature = "logs")]
+pub use connectors::ErrorSpanLogProcessor;
+#[cfg(feature = "metrics")]
+pub use connectors::{SpanMetricsProcessor, SpanMetricsProcessorBuilder};
 pub use events::SpanEvents;
 
 pub use id_generator::{IdGenerator, RandomIdGenerator};
 pub use links::SpanLinks;
+#[cfg(feature = "redaction")]
+pub use processors::AttributeRedactionProcessor;
+pub use processors::{BaggageSpanProcessor, SpanRenameProcessor};
 pub use provider::{Builder, TracerProvider};
 pub use sampler::{Sampler, ShouldSample};
 pub use span::Span;
@@ -30,8 +42,17 @@ pub use span_processor::{
     BatchConfig, BatchConfigBuilder, BatchSpanProcessor, BatchSpanProcessorBuilder,
     SimpleSpanProcessor, SpanProcessor,
 };
+#[cfg(feature = "experimental_trace_batch_span_processor_no_runtime")]
+pub use span_processor_with_own_thread::{
+    BatchSpanProcessorWithOwnThread, BatchSpanProcessorWithOwnThreadBuilder,
+};
 pub use tracer::Tracer;
 
+pub use crate::batch_queue::{QueueFullPolicy, QueueStats};
+
+#[cfg(feature = "redaction")]
+pub use crate::redaction::Redaction;
+
 #[cfg(feature = "jaeger_remote_sampler")]
 pub use sampler::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
 
@@ -0,0 +1,525 @@
+use crate::{
+    export::trace::SpanData,
+    trace::{Span, SpanProcessor},
+    Resource,
+};
+use opentelemetry::{
+    baggage::BaggageExt,
+    trace::{Span as _, TraceResult},
+    Context, Key, KeyValue,
+};
+use std::fmt::{self, Debug, Formatter};
+
+type KeyPredicate = Box<dyn Fn(&Key) -> bool + Send + Sync>;
+
+/// A [`SpanProcessor`] adding the [baggage] entries of the parent context as
+/// attributes of starting spans, before passing them to `inner`.
+///
+/// All entries are copied by default. A predicate on the baggage keys can
+/// restrict them, e.g. to avoid leaking entries set by upstream services.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::export::trace::SpanExporter;
+/// use opentelemetry_sdk::trace::{BaggageSpanProcessor, SimpleSpanProcessor, TracerProvider};
+///
+/// fn tracer_provider(exporter: Box<dyn SpanExporter>) -> TracerProvider {
+///     let processor = BaggageSpanProcessor::new(SimpleSpanProcessor::new(exporter))
+///         .with_key_predicate(|key| key.as_str().starts_with("app."));
+///
+///     TracerProvider::builder()
+///         .with_span_processor(processor)
+///         .build()
+/// }
+/// ```
+///
+/// [baggage]: opentelemetry::baggage
+pub struct BaggageSpanProcessor<P> {
+    inner: P,
+    predicate: Option<KeyPredicate>,
+}
+
+impl<P: SpanProcessor> BaggageSpanProcessor<P> {
+    /// Create a new `BaggageSpanProcessor` copying every baggage entry.
+    pub fn new(inner: P) -> Self {
+        BaggageSpanProcessor {
+            inner,
+            predicate: None,
+        }
+    }
+
+    /// Only copies the baggage entries whose key matches `predicate`.
+    pub fn with_key_predicate<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Key) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Some(Box::new(predicate));
+        self
+    }
+}
+
+impl<P: Debug> Debug for BaggageSpanProcessor<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BaggageSpanProcessor")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for BaggageSpanProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        for (key, (value, _)) in cx.baggage() {
+            if self.predicate.as_ref().map_or(true, |matches| matches(key)) {
+                span.set_attribute(KeyValue::new(key.clone(), value.clone()));
+            }
+        }
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        self.inner.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[derive(Debug)]
+enum Segment {
+    Literal(String),
+    Attribute(Key),
+}
+
+/// A [`SpanProcessor`] renaming finished spans from templates before passing
+/// them to `inner`.
+///
+/// Templates reference span attributes between braces, e.g.
+/// `"{http.request.method} {http.route}"`. Templates are tried in the order
+/// they are added, and the first one whose attributes are all present on the
+/// span gives its new name. Spans matching no template keep their name.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::export::trace::SpanExporter;
+/// use opentelemetry_sdk::trace::{SimpleSpanProcessor, SpanRenameProcessor, TracerProvider};
+///
+/// fn tracer_provider(exporter: Box<dyn SpanExporter>) -> TracerProvider {
+///     let processor = SpanRenameProcessor::new(SimpleSpanProcessor::new(exporter))
+///         .with_template("{http.request.method} {http.route}")
+///         .with_template("{db.operation.name} {db.collection.name}");
+///
+///     TracerProvider::builder()
+///         .with_span_processor(processor)
+///         .build()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SpanRenameProcessor<P> {
+    inner: P,
+    templates: Vec<Vec<Segment>>,
+}
+
+impl<P: SpanProcessor> SpanRenameProcessor<P> {
+    /// Create a new `SpanRenameProcessor` without templates.
+    pub fn new(inner: P) -> Self {
+        SpanRenameProcessor {
+            inner,
+            templates: Vec::new(),
+        }
+    }
+
+    /// Adds a name template. An unclosed brace is kept as a literal.
+    pub fn with_template(mut self, template: &str) -> Self {
+        self.templates.push(parse_template(template));
+        self
+    }
+
+    fn rename(&self, span: &SpanData) -> Option<String> {
+        self.templates
+            .iter()
+            .find_map(|template| render_template(template, &span.attributes))
+    }
+}
+
+fn parse_template(mut template: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    while let Some(start) = template.find('{') {
+        let Some(len) = template[start..].find('}') else {
+            break;
+        };
+        if start > 0 {
+            segments.push(Segment::Literal(template[..start].to_owned()));
+        }
+        let key = &template[start + 1..start + len];
+        segments.push(Segment::Attribute(Key::new(key.to_owned())));
+        template = &template[start + len + 1..];
+    }
+    if !template.is_empty() {
+        segments.push(Segment::Literal(template.to_owned()));
+    }
+    segments
+}
+
+fn render_template(template: &[Segment], attributes: &[KeyValue]) -> Option<String> {
+    let mut name = String::new();
+    for segment in template {
+        match segment {
+            Segment::Literal(literal) => name.push_str(literal),
+            Segment::Attribute(key) => {
+                let kv = attributes.iter().find(|kv| kv.key == *key)?;
+                name.push_str(&kv.value.as_str());
+            }
+        }
+    }
+    Some(name)
+}
+
+impl<P: SpanProcessor> SpanProcessor for SpanRenameProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        if let Some(name) = self.rename(&span) {
+            span.name = name.into();
+        }
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        self.inner.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(feature = "redaction")]
+pub use redaction::AttributeRedactionProcessor;
+
+#[cfg(feature = "redaction")]
+mod redaction {
+    use super::*;
+    use crate::redaction::{hash, Redaction};
+    use opentelemetry::{Array, Value};
+    use regex::Regex;
+    use std::collections::HashSet;
+
+    #[derive(Debug)]
+    enum Rule {
+        Key(Regex, Redaction),
+        Value(Regex, Redaction),
+    }
+
+    /// A [`SpanProcessor`] removing or obfuscating attributes of finished
+    /// spans and of their events before passing them to `inner`.
+    ///
+    /// Attributes are matched by exact key, by key regex, or, for string
+    /// values including those in arrays, by value regex. Rules apply in the
+    /// order they are added; once an attribute is dropped, later rules do not
+    /// see it.
+    ///
+    /// Attributes are redacted when the span ends, so processors registered
+    /// before this one, and `on_start` of `inner`, see the original values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry_sdk::export::trace::SpanExporter;
+    /// use opentelemetry_sdk::trace::{
+    ///     AttributeRedactionProcessor, Redaction, SimpleSpanProcessor, TracerProvider,
+    /// };
+    /// use regex::Regex;
+    ///
+    /// fn tracer_provider(exporter: Box<dyn SpanExporter>) -> TracerProvider {
+    ///     let processor = AttributeRedactionProcessor::new(SimpleSpanProcessor::new(exporter))
+    ///         .with_dropped_keys(["http.request.header.authorization"])
+    ///         .with_key_rule(Regex::new("^enduser\\.").unwrap(), Redaction::Hash)
+    ///         .with_value_rule(
+    ///             Regex::new(r"\b\d{4}-\d{4}-\d{4}-\d{4}\b").unwrap(),
+    ///             Redaction::Mask("****".into()),
+    ///         );
+    ///
+    ///     TracerProvider::builder()
+    ///         .with_span_processor(processor)
+    ///         .build()
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct AttributeRedactionProcessor<P> {
+        inner: P,
+        dropped_keys: HashSet<Key>,
+        rules: Vec<Rule>,
+    }
+
+    impl<P: SpanProcessor> AttributeRedactionProcessor<P> {
+        /// Create a new `AttributeRedactionProcessor` without rules.
+        pub fn new(inner: P) -> Self {
+            AttributeRedactionProcessor {
+                inner,
+                dropped_keys: HashSet::new(),
+                rules: Vec::new(),
+            }
+        }
+
+        /// Removes the attributes with the given keys.
+        pub fn with_dropped_keys<K: Into<Key>>(
+            mut self,
+            keys: impl IntoIterator<Item = K>,
+        ) -> Self {
+            self.dropped_keys.extend(keys.into_iter().map(Into::into));
+            self
+        }
+
+        /// Applies `redaction` to the values of the attributes whose key
+        /// matches `key`.
+        pub fn with_key_rule(mut self, key: Regex, redaction: Redaction) -> Self {
+            self.rules.push(Rule::Key(key, redaction));
+            self
+        }
+
+        /// Applies `redaction` to the string values matching `value`.
+        ///
+        /// [`Redaction::Mask`] only replaces the matched parts of the string,
+        /// while [`Redaction::Drop`] removes the whole attribute.
+        pub fn with_value_rule(mut self, value: Regex, redaction: Redaction) -> Self {
+            self.rules.push(Rule::Value(value, redaction));
+            self
+        }
+
+        fn redact(&self, attributes: &mut Vec<KeyValue>) {
+            attributes.retain_mut(|KeyValue { key, value, .. }| {
+                if self.dropped_keys.contains(key) {
+                    return false;
+                }
+                self.rules.iter().all(|rule| match rule {
+                    Rule::Key(regex, redaction) => {
+                        !regex.is_match(key.as_str()) || redact_value(value, redaction)
+                    }
+                    Rule::Value(regex, redaction) => redact_strings(value, regex, redaction),
+                })
+            });
+        }
+    }
+
+    /// Applies `redaction` to the whole `value`, returning whether to keep it.
+    fn redact_value(value: &mut Value, redaction: &Redaction) -> bool {
+        match redaction {
+            Redaction::Drop => return false,
+            Redaction::Mask(mask) => *value = Value::from(mask.to_string()),
+            Redaction::Hash => *value = Value::from(hash(&value.as_str())),
+        }
+        true
+    }
+
+    /// Applies `redaction` to the strings in `value` matching `regex`,
+    /// returning whether to keep it.
+    fn redact_strings(value: &mut Value, regex: &Regex, redaction: &Redaction) -> bool {
+        match value {
+            Value::String(string) if regex.is_match(string.as_str()) => match redaction {
+                Redaction::Mask(mask) => {
+                    let masked = regex
+                        .replace_all(string.as_str(), mask.as_ref())
+                        .into_owned();
+                    *value = Value::from(masked);
+                    true
+                }
+                redaction => redact_value(value, redaction),
+            },
+            Value::Array(Array::String(strings)) => {
+                strings.retain_mut(|string| {
+                    if !regex.is_match(string.as_str()) {
+                        return true;
+                    }
+                    match redaction {
+                        Redaction::Drop => return false,
+                        Redaction::Mask(mask) => {
+                            *string = regex
+                                .replace_all(string.as_str(), mask.as_ref())
+                                .into_owned()
+                                .into()
+                        }
+                        Redaction::Hash => *string = hash(string.as_str()).into(),
+                    }
+                    true
+                });
+                true
+            }
+            _ => true,
+        }
+    }
+
+    impl<P: SpanProcessor> SpanProcessor for AttributeRedactionProcessor<P> {
+        fn on_start(&self, span: &mut Span, cx: &Context) {
+            self.inner.on_start(span, cx);
+        }
+
+        fn on_end(&self, mut span: SpanData) {
+            self.redact(&mut span.attributes);
+            for event in span.events.events.iter_mut() {
+                self.redact(&mut event.attributes);
+            }
+            self.inner.on_end(span);
+        }
+
+        fn force_flush(&self) -> TraceResult<()> {
+            self.inner.force_flush()
+        }
+
+        fn shutdown(&self) -> TraceResult<()> {
+            self.inner.shutdown()
+        }
+
+        fn set_resource(&mut self, resource: &Resource) {
+            self.inner.set_resource(resource);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::InMemorySpanExporter;
+    use crate::trace::{SimpleSpanProcessor, TracerProvider};
+    use opentelemetry::trace::{Tracer as _, TracerProvider as _};
+
+    fn simple(exporter: &InMemorySpanExporter) -> SimpleSpanProcessor {
+        SimpleSpanProcessor::new(Box::new(exporter.clone()))
+    }
+
+    #[test]
+    fn baggage_entries_become_attributes() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_span_processor(
+                BaggageSpanProcessor::new(simple(&exporter))
+                    .with_key_predicate(|key| key.as_str().starts_with("app.")),
+            )
+            .build();
+
+        let cx = Context::new().with_baggage([
+            KeyValue::new("app.tenant", "acme"),
+            KeyValue::new("session.token", "secret"),
+        ]);
+        provider
+            .tracer("test")
+            .start_with_context("span", &cx)
+            .end();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans[0].attributes, [KeyValue::new("app.tenant", "acme")]);
+    }
+
+    #[test]
+    fn rename_uses_first_complete_template() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_span_processor(
+                SpanRenameProcessor::new(simple(&exporter))
+                    .with_template("{http.request.method} {http.route}")
+                    .with_template("query {db.collection.name}"),
+            )
+            .build();
+
+        let tracer = provider.tracer("test");
+        for attributes in [
+            vec![
+                KeyValue::new("http.request.method", "GET"),
+                KeyValue::new("http.route", "/users/{id}"),
+            ],
+            vec![
+                KeyValue::new("http.request.method", "GET"),
+                KeyValue::new("db.collection.name", "users"),
+            ],
+            vec![KeyValue::new("http.request.method", "GET")],
+        ] {
+            tracer
+                .span_builder("original")
+                .with_attributes(attributes)
+                .start(&tracer)
+                .end();
+        }
+
+        let names: Vec<_> = exporter
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .map(|span| span.name)
+            .collect();
+        assert_eq!(names, ["GET /users/{id}", "query users", "original"]);
+    }
+
+    #[cfg(feature = "redaction")]
+    #[test]
+    fn attribute_redaction_applies_rules() {
+        use crate::trace::Redaction;
+        use opentelemetry::{Array, Value};
+        use regex::Regex;
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_span_processor(
+                AttributeRedactionProcessor::new(simple(&exporter))
+                    .with_dropped_keys(["password"])
+                    .with_key_rule(Regex::new(r"^user\.").unwrap(), Redaction::Hash)
+                    .with_value_rule(
+                        Regex::new(r"\d{4}-\d{4}-\d{4}-\d{4}").unwrap(),
+                        Redaction::Mask("****".into()),
+                    ),
+            )
+            .build();
+
+        let tracer = provider.tracer("test");
+        let mut span = tracer
+            .span_builder("checkout")
+            .with_attributes([
+                KeyValue::new("user.id", "alice"),
+                KeyValue::new("card", "paid with 4111-1111-1111-1111"),
+                KeyValue::new("password", "secret"),
+                KeyValue::new(
+                    "tags",
+                    Value::Array(Array::String(vec![
+                        "4111-1111-1111-1111".into(),
+                        "checkout".into(),
+                    ])),
+                ),
+            ])
+            .start(&tracer);
+        span.add_event("retry", vec![KeyValue::new("password", "secret")]);
+        span.end();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(
+            spans[0].attributes,
+            [
+                // sha256("alice")
+                KeyValue::new(
+                    "user.id",
+                    "2bd806c97f0e00af1a1fc3328fa763a9269723c8db8fac4f93af71db186d6e90"
+                ),
+                KeyValue::new("card", "paid with ****"),
+                KeyValue::new(
+                    "tags",
+                    Value::Array(Array::String(vec!["****".into(), "checkout".into()])),
+                ),
+            ]
+        );
+        assert!(spans[0].events[0].attributes.is_empty());
+    }
+}
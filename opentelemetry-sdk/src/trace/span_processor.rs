@@ -34,9 +34,11 @@
 //! [`is_recording`]: opentelemetry::trace::Span::is_recording()
 //! [`TracerProvider`]: opentelemetry::trace::TracerProvider
 
+use crate::batch_queue::{BatchQueue, QueueFullPolicy, QueueStats};
 use crate::export::trace::{ExportResult, SpanData, SpanExporter};
 use crate::resource::Resource;
 use crate::runtime::{RuntimeChannel, TrySend};
+use crate::self_metrics::{ComponentMetrics, Signal};
 use crate::trace::Span;
 use futures_channel::oneshot;
 use futures_util::{
@@ -106,6 +108,7 @@ pub trait SpanProcessor: Send + Sync + std::fmt::Debug {
 #[derive(Debug)]
 pub struct SimpleSpanProcessor {
     exporter: Mutex<Box<dyn SpanExporter>>,
+    metrics: ComponentMetrics,
 }
 
 impl SimpleSpanProcessor {
@@ -113,6 +116,7 @@ impl SimpleSpanProcessor {
     pub fn new(exporter: Box<dyn SpanExporter>) -> Self {
         Self {
             exporter: Mutex::new(exporter),
+            metrics: ComponentMetrics::new(Signal::Traces, "simple_span_processor"),
         }
     }
 }
@@ -127,11 +131,13 @@ impl SpanProcessor for SimpleSpanProcessor {
             return;
         }
 
+        let timer = self.metrics.start_export();
         let result = self
             .exporter
             .lock()
             .map_err(|_| TraceError::Other("SimpleSpanProcessor mutex poison".into()))
             .and_then(|mut exporter| futures_executor::block_on(exporter.export(vec![span])));
+        timer.finish(1, &result);
 
         if let Err(err) = result {
             // TODO: check error type, and log `error` only if the error is user-actiobable, else log `debug`
@@ -227,12 +233,15 @@ impl SpanProcessor for SimpleSpanProcessor {
 /// [`async-std`]: https://async.rs
 pub struct BatchSpanProcessor<R: RuntimeChannel> {
     message_sender: R::Sender<BatchMessage>,
+    queue: Arc<BatchQueue<SpanData>>,
+    max_export_batch_size: usize,
 }
 
 impl<R: RuntimeChannel> fmt::Debug for BatchSpanProcessor<R> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("BatchSpanProcessor")
             .field("message_sender", &self.message_sender)
+            .field("queue_stats", self.queue.stats())
             .finish()
     }
 }
@@ -247,9 +256,17 @@ impl<R: RuntimeChannel> SpanProcessor for BatchSpanProcessor<R> {
             return;
         }
 
-        let result = self.message_sender.try_send(BatchMessage::ExportSpan(span));
+        let Some(len) = self.queue.push(span) else {
+            return;
+        };
+        if len < self.max_export_batch_size || !self.queue.request_export() {
+            return;
+        }
+
+        let result = self.message_sender.try_send(BatchMessage::ExportSpans);
 
         if let Err(err) = result {
+            self.queue.clear_export_request();
             otel_debug!(
                 name: "BatchSpanProcessor.OnEnd.ExportQueueingFailed",
                 reason = format!("{:?}", TraceError::Other(err.into()))
@@ -288,14 +305,11 @@ impl<R: RuntimeChannel> SpanProcessor for BatchSpanProcessor<R> {
 }
 
 /// Messages sent between application thread and batch span processor's work thread.
-// In this enum the size difference is not a concern because:
-// 1. If we wrap SpanData into a pointer, it will add overhead when processing.
-// 2. Most of the messages will be ExportSpan.
-#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 enum BatchMessage {
-    /// Export spans, usually called when span ends
-    ExportSpan(SpanData),
+    /// Export the full batches waiting in the queue, sent when a span ending
+    /// fills a batch.
+    ExportSpans,
     /// Flush the current buffer to the backend, it can be triggered by
     /// pre configured interval or a call to `force_push` function.
     Flush(Option<oneshot::Sender<ExportResult>>),
@@ -307,6 +321,7 @@ enum BatchMessage {
 
 struct BatchSpanProcessorInternal<R> {
     spans: Vec<SpanData>,
+    queue: Arc<BatchQueue<SpanData>>,
     export_tasks: FuturesUnordered<BoxFuture<'static, ExportResult>>,
     runtime: R,
     exporter: Box<dyn SpanExporter>,
@@ -349,41 +364,49 @@ impl<R: RuntimeChannel> BatchSpanProcessorInternal<R> {
         }
     }
 
+    /// Export the queued spans one full batch at a time, leaving the remainder
+    /// for the next tick.
+    async fn export_full_batches(&mut self) {
+        self.queue.clear_export_request();
+        let batch_size = self.config.max_export_batch_size.max(1);
+        while self.queue.len() >= batch_size {
+            self.spans = self.queue.pop_batch(batch_size);
+
+            // If concurrent exports are saturated, wait for one to complete.
+            if !self.export_tasks.is_empty()
+                && self.export_tasks.len() == self.config.max_concurrent_exports
+            {
+                self.export_tasks.next().await;
+            }
+
+            let export_task = self.export();
+            let task = async move {
+                if let Err(err) = export_task.await {
+                    otel_error!(
+                        name: "BatchSpanProcessor.Export.Error",
+                        reason = format!("{}", err)
+                    );
+                }
+
+                Ok(())
+            };
+            // Special case when not using concurrent exports
+            if self.config.max_concurrent_exports == 1 {
+                let _ = task.await;
+            } else {
+                self.export_tasks.push(Box::pin(task));
+            }
+        }
+    }
+
     /// Process a single message
     ///
     /// A return value of false indicates shutdown
     async fn process_message(&mut self, message: BatchMessage) -> bool {
         match message {
-            // Span has finished, add to buffer of pending spans.
-            BatchMessage::ExportSpan(span) => {
-                self.spans.push(span);
-
-                if self.spans.len() == self.config.max_export_batch_size {
-                    // If concurrent exports are saturated, wait for one to complete.
-                    if !self.export_tasks.is_empty()
-                        && self.export_tasks.len() == self.config.max_concurrent_exports
-                    {
-                        self.export_tasks.next().await;
-                    }
-
-                    let export_task = self.export();
-                    let task = async move {
-                        if let Err(err) = export_task.await {
-                            otel_error!(
-                                name: "BatchSpanProcessor.Export.Error",
-                                reason = format!("{}", err)
-                            );
-                        }
-
-                        Ok(())
-                    };
-                    // Special case when not using concurrent exports
-                    if self.config.max_concurrent_exports == 1 {
-                        let _ = task.await;
-                    } else {
-                        self.export_tasks.push(Box::pin(task));
-                    }
-                }
+            // A batch of spans is ready in the queue.
+            BatchMessage::ExportSpans => {
+                self.export_full_batches().await;
             }
             // Span batch interval time reached or a force flush has been invoked, export
             // current spans.
@@ -406,11 +429,16 @@ impl<R: RuntimeChannel> BatchSpanProcessorInternal<R> {
             // channel-coupled exporters, they will need a `force_flush` implementation to
             // properly block.
             BatchMessage::Flush(res_channel) => {
+                self.export_full_batches().await;
+                self.spans = self.queue.pop_batch(self.config.max_export_batch_size);
                 self.flush(res_channel).await;
             }
             // Stream has terminated or processor is shutdown, return to finish execution.
             BatchMessage::Shutdown(ch) => {
+                self.export_full_batches().await;
+                self.spans = self.queue.pop_batch(self.config.max_export_batch_size);
                 self.flush(Some(ch)).await;
+                self.queue.report_dropped();
                 self.exporter.shutdown();
                 return false;
             }
@@ -429,15 +457,19 @@ impl<R: RuntimeChannel> BatchSpanProcessorInternal<R> {
             return Box::pin(future::ready(Ok(())));
         }
 
+        let count = self.spans.len();
+        let timer = self.queue.metrics().start_export();
         let export = self.exporter.export(self.spans.split_off(0));
         let timeout = self.runtime.delay(self.config.max_export_timeout);
         let time_out = self.config.max_export_timeout;
 
         Box::pin(async move {
-            match future::select(export, timeout).await {
+            let result = match future::select(export, timeout).await {
                 Either::Left((export_res, _)) => export_res,
                 Either::Right((_, _)) => ExportResult::Err(TraceError::ExportTimedOut(time_out)),
-            }
+            };
+            timer.finish(count, &result);
+            result
         })
     }
 
@@ -468,6 +500,14 @@ impl<R: RuntimeChannel> BatchSpanProcessor<R> {
     pub(crate) fn new(exporter: Box<dyn SpanExporter>, config: BatchConfig, runtime: R) -> Self {
         let (message_sender, message_receiver) =
             runtime.batch_message_channel(config.max_queue_size);
+        let queue = Arc::new(BatchQueue::new(
+            "BatchSpanProcessor",
+            config.max_queue_size,
+            config.queue_full_policy,
+            ComponentMetrics::new(Signal::Traces, "batching_span_processor"),
+        ));
+        let max_export_batch_size = config.max_export_batch_size.max(1);
+        let worker_queue = queue.clone();
 
         let inner_runtime = runtime.clone();
         // Spawn worker process via user-defined spawn function.
@@ -483,6 +523,7 @@ impl<R: RuntimeChannel> BatchSpanProcessor<R> {
             let messages = Box::pin(stream::select(message_receiver, ticker));
             let processor = BatchSpanProcessorInternal {
                 spans: Vec::new(),
+                queue: worker_queue,
                 export_tasks: FuturesUnordered::new(),
                 runtime: timeout_runtime,
                 config,
@@ -493,7 +534,17 @@ impl<R: RuntimeChannel> BatchSpanProcessor<R> {
         }));
 
         // Return batch processor with link to worker
-        BatchSpanProcessor { message_sender }
+        BatchSpanProcessor {
+            message_sender,
+            queue,
+            max_export_batch_size,
+        }
+    }
+
+    /// Returns a handle on the counters of the span queue, such as the number
+    /// of spans dropped because it was full.
+    pub fn queue_stats(&self) -> QueueStats {
+        self.queue.stats().clone()
     }
 
     /// Create a new batch processor builder
@@ -515,27 +566,30 @@ impl<R: RuntimeChannel> BatchSpanProcessor<R> {
 pub struct BatchConfig {
     /// The maximum queue size to buffer spans for delayed processing. If the
     /// queue gets full it drops the spans. The default value of is 2048.
-    max_queue_size: usize,
+    pub(crate) max_queue_size: usize,
 
     /// The delay interval in milliseconds between two consecutive processing
     /// of batches. The default value is 5 seconds.
-    scheduled_delay: Duration,
+    pub(crate) scheduled_delay: Duration,
 
     /// The maximum number of spans to process in a single batch. If there are
     /// more than one batch worth of spans then it processes multiple batches
     /// of spans one batch after the other without any delay. The default value
     /// is 512.
-    max_export_batch_size: usize,
+    pub(crate) max_export_batch_size: usize,
 
     /// The maximum duration to export a batch of data.
-    max_export_timeout: Duration,
+    pub(crate) max_export_timeout: Duration,
 
     /// Maximum number of concurrent exports
     ///
     /// Limits the number of spawned tasks for exports and thus memory consumed
     /// by an exporter. A value of 1 will cause exports to be performed
     /// synchronously on the BatchSpanProcessor task.
-    max_concurrent_exports: usize,
+    pub(crate) max_concurrent_exports: usize,
+
+    /// What to do with a span when the queue is full. The default is to drop it.
+    pub(crate) queue_full_policy: QueueFullPolicy,
 }
 
 impl Default for BatchConfig {
@@ -552,6 +606,7 @@ pub struct BatchConfigBuilder {
     max_export_batch_size: usize,
     max_export_timeout: Duration,
     max_concurrent_exports: usize,
+    queue_full_policy: QueueFullPolicy,
 }
 
 impl Default for BatchConfigBuilder {
@@ -570,6 +625,7 @@ impl Default for BatchConfigBuilder {
             max_export_batch_size: OTEL_BSP_MAX_EXPORT_BATCH_SIZE_DEFAULT,
             max_export_timeout: Duration::from_millis(OTEL_BSP_EXPORT_TIMEOUT_DEFAULT),
             max_concurrent_exports: OTEL_BSP_MAX_CONCURRENT_EXPORTS_DEFAULT,
+            queue_full_policy: QueueFullPolicy::default(),
         }
         .init_from_env_vars()
     }
@@ -622,6 +678,14 @@ impl BatchConfigBuilder {
         self
     }
 
+    /// Set queue_full_policy for [`BatchConfigBuilder`].
+    /// It's what happens to a span that ends while the queue is full.
+    /// The default is [`QueueFullPolicy::DropNewest`].
+    pub fn with_queue_full_policy(mut self, queue_full_policy: QueueFullPolicy) -> Self {
+        self.queue_full_policy = queue_full_policy;
+        self
+    }
+
     /// Builds a `BatchConfig` enforcing the following invariants:
     /// * `max_export_batch_size` must be less than or equal to `max_queue_size`.
     pub fn build(self) -> BatchConfig {
@@ -634,6 +698,7 @@ impl BatchConfigBuilder {
             scheduled_delay: self.scheduled_delay,
             max_export_timeout: self.max_export_timeout,
             max_concurrent_exports: self.max_concurrent_exports,
+            queue_full_policy: self.queue_full_policy,
             max_export_batch_size,
         }
     }
@@ -726,7 +791,7 @@ mod tests {
         OTEL_BSP_EXPORT_TIMEOUT_DEFAULT, OTEL_BSP_MAX_CONCURRENT_EXPORTS,
         OTEL_BSP_MAX_CONCURRENT_EXPORTS_DEFAULT, OTEL_BSP_MAX_EXPORT_BATCH_SIZE_DEFAULT,
     };
-    use crate::trace::{BatchConfig, BatchConfigBuilder, SpanEvents, SpanLinks};
+    use crate::trace::{BatchConfig, BatchConfigBuilder, QueueFullPolicy, SpanEvents, SpanLinks};
     use async_trait::async_trait;
     use opentelemetry::trace::{SpanContext, SpanId, SpanKind, Status};
     use std::fmt::Debug;
@@ -869,12 +934,14 @@ mod tests {
             .with_max_export_timeout(Duration::from_millis(10))
             .with_max_concurrent_exports(10)
             .with_max_queue_size(10)
+            .with_queue_full_policy(QueueFullPolicy::Sample)
             .build();
         assert_eq!(batch.max_export_batch_size, 10);
         assert_eq!(batch.scheduled_delay, Duration::from_millis(10));
         assert_eq!(batch.max_export_timeout, Duration::from_millis(10));
         assert_eq!(batch.max_concurrent_exports, 10);
         assert_eq!(batch.max_queue_size, 10);
+        assert_eq!(batch.queue_full_policy, QueueFullPolicy::Sample);
     }
 
     #[test]
@@ -0,0 +1,536 @@
+use crate::batch_queue::{send_until, BatchQueue, QueueStats};
+use crate::export::trace::{ExportResult, SpanData, SpanExporter};
+use crate::resource::Resource;
+use crate::self_metrics::{ComponentMetrics, Signal};
+use crate::trace::{BatchConfig, Span, SpanProcessor};
+use opentelemetry::{otel_debug, otel_error, otel_info};
+use opentelemetry::{
+    trace::{TraceError, TraceResult},
+    Context,
+};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Messages sent between application threads and the processor's own thread.
+#[derive(Debug)]
+enum BatchMessage {
+    /// Export the full batches waiting in the queue, sent when a span ending
+    /// fills a batch.
+    ExportSpans,
+    /// Export the current buffer and report the result.
+    Flush(mpsc::SyncSender<ExportResult>),
+    /// Export the current buffer, shut down the exporter and stop the thread.
+    Shutdown(mpsc::SyncSender<ExportResult>),
+    /// Set the resource for the exporter.
+    SetResource(Arc<Resource>),
+}
+
+/// A [`SpanProcessor`] that buffers finished spans and exports them in
+/// batches from a dedicated thread, without requiring an async runtime.
+///
+/// It is configured with the same [`BatchConfig`] as [`BatchSpanProcessor`],
+/// except for `max_concurrent_exports`: exports are performed one at a time,
+/// on the processor thread, by blocking on the exporter's future with
+/// `futures_executor`. This suits binaries without an async runtime and
+/// plugins loaded through FFI, as long as the exporter itself does not need
+/// one.
+///
+/// [`force_flush`] and [`shutdown`] wait for the processor thread for at most
+/// `max_export_timeout`, and return [`TraceError::ExportTimedOut`] otherwise.
+/// An export already in progress is not interrupted. Spans still queued when
+/// the processor is dropped without shutdown are exported before its thread
+/// stops.
+///
+/// Spans ended while the queue is full are handled according to the
+/// configured [`QueueFullPolicy`]; the count of dropped spans is available
+/// from [`queue_stats`] and reported through internal logs on shutdown.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::testing::trace::NoopSpanExporter;
+/// use opentelemetry_sdk::trace::{
+///     BatchConfigBuilder, BatchSpanProcessorWithOwnThread, TracerProvider,
+/// };
+///
+/// let processor = BatchSpanProcessorWithOwnThread::builder(NoopSpanExporter::new())
+///     .with_batch_config(BatchConfigBuilder::default().with_max_queue_size(4096).build())
+///     .build();
+///
+/// let provider = TracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+/// # drop(provider);
+/// ```
+///
+/// [`BatchSpanProcessor`]: crate::trace::BatchSpanProcessor
+/// [`force_flush`]: SpanProcessor::force_flush
+/// [`shutdown`]: SpanProcessor::shutdown
+/// [`QueueFullPolicy`]: crate::trace::QueueFullPolicy
+/// [`queue_stats`]: BatchSpanProcessorWithOwnThread::queue_stats
+pub struct BatchSpanProcessorWithOwnThread {
+    message_sender: SyncSender<BatchMessage>,
+    handle: Mutex<Option<thread::JoinHandle<()>>>,
+    timeout: Duration,
+    queue: Arc<BatchQueue<SpanData>>,
+    max_export_batch_size: usize,
+    is_shutdown: AtomicBool,
+}
+
+impl fmt::Debug for BatchSpanProcessorWithOwnThread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchSpanProcessorWithOwnThread")
+            .field("timeout", &self.timeout)
+            .field("queue_stats", self.queue.stats())
+            .finish()
+    }
+}
+
+impl BatchSpanProcessorWithOwnThread {
+    /// Create a new batch processor builder
+    pub fn builder<E>(exporter: E) -> BatchSpanProcessorWithOwnThreadBuilder<E>
+    where
+        E: SpanExporter,
+    {
+        BatchSpanProcessorWithOwnThreadBuilder {
+            exporter,
+            config: Default::default(),
+        }
+    }
+
+    fn new(mut exporter: Box<dyn SpanExporter>, config: BatchConfig) -> Self {
+        let (message_sender, message_receiver) = mpsc::sync_channel(config.max_queue_size);
+        let timeout = config.max_export_timeout;
+        let queue = Arc::new(BatchQueue::new(
+            "BatchSpanProcessorWithOwnThread",
+            config.max_queue_size,
+            config.queue_full_policy,
+            ComponentMetrics::new(Signal::Traces, "batching_span_processor"),
+        ));
+        let max_export_batch_size = config.max_export_batch_size.max(1);
+        let worker_queue = queue.clone();
+
+        let result_thread_creation = thread::Builder::new()
+            .name("OpenTelemetry.Traces.BatchProcessor".to_string())
+            .spawn(move || {
+                otel_info!(
+                    name: "BatchSpanProcessor.ThreadStarted",
+                    interval_in_millisecs = config.scheduled_delay.as_millis(),
+                    max_export_batch_size = config.max_export_batch_size,
+                    max_queue_size = config.max_queue_size
+                );
+                let mut last_export_time = Instant::now();
+                let export = |exporter: &mut dyn SpanExporter, drain: bool| {
+                    export_queued(exporter, &worker_queue, max_export_batch_size, drain)
+                };
+
+                loop {
+                    let remaining_time = config
+                        .scheduled_delay
+                        .saturating_sub(last_export_time.elapsed());
+                    match message_receiver.recv_timeout(remaining_time) {
+                        Ok(BatchMessage::ExportSpans) => {
+                            if let Err(err) = export(exporter.as_mut(), false) {
+                                otel_error!(
+                                    name: "BatchSpanProcessor.Export.Error",
+                                    reason = format!("{}", err)
+                                );
+                            }
+                            last_export_time = Instant::now();
+                        }
+                        Ok(BatchMessage::Flush(sender)) => {
+                            let result = export(exporter.as_mut(), true);
+                            last_export_time = Instant::now();
+                            if let Err(result) = sender.send(result) {
+                                otel_debug!(
+                                    name: "BatchSpanProcessor.Flush.SendResultError",
+                                    reason = format!("{:?}", result)
+                                );
+                            }
+                        }
+                        Ok(BatchMessage::Shutdown(sender)) => {
+                            let result = export(exporter.as_mut(), true);
+                            exporter.shutdown();
+                            worker_queue.report_dropped();
+                            if let Err(result) = sender.send(result) {
+                                otel_debug!(
+                                    name: "BatchSpanProcessor.Shutdown.SendResultError",
+                                    reason = format!("{:?}", result)
+                                );
+                            }
+                            break;
+                        }
+                        Ok(BatchMessage::SetResource(resource)) => {
+                            exporter.set_resource(&resource);
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            if let Err(err) = export(exporter.as_mut(), true) {
+                                otel_error!(
+                                    name: "BatchSpanProcessor.Export.Error",
+                                    reason = format!("{}", err)
+                                );
+                            }
+                            last_export_time = Instant::now();
+                        }
+                        Err(RecvTimeoutError::Disconnected) => {
+                            // The processor was dropped without shutdown.
+                            if let Err(err) = export(exporter.as_mut(), true) {
+                                otel_error!(
+                                    name: "BatchSpanProcessor.Export.Error",
+                                    reason = format!("{}", err)
+                                );
+                            }
+                            break;
+                        }
+                    }
+                }
+                otel_info!(name: "BatchSpanProcessor.ThreadStopped");
+            });
+
+        let handle = match result_thread_creation {
+            Ok(handle) => Some(handle),
+            Err(err) => {
+                otel_error!(
+                    name: "BatchSpanProcessor.ThreadStartError",
+                    reason = format!("{:?}", err)
+                );
+                None
+            }
+        };
+
+        BatchSpanProcessorWithOwnThread {
+            message_sender,
+            handle: Mutex::new(handle),
+            timeout,
+            queue,
+            max_export_batch_size,
+            is_shutdown: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns a handle on the counters of the span queue, such as the number
+    /// of spans dropped because it was full.
+    pub fn queue_stats(&self) -> QueueStats {
+        self.queue.stats().clone()
+    }
+
+    /// Sends a control message and waits for the processor thread to answer,
+    /// for at most the export timeout in total.
+    fn request(
+        &self,
+        message: impl FnOnce(SyncSender<ExportResult>) -> BatchMessage,
+    ) -> TraceResult<()> {
+        let deadline = Instant::now() + self.timeout;
+        let (sender, receiver) = mpsc::sync_channel(1);
+        match send_until(&self.message_sender, message(sender), deadline) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => return Err(TraceError::ExportTimedOut(self.timeout)),
+            Err(err) => return Err(TraceError::Other(err.to_string().into())),
+        }
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(TraceError::ExportTimedOut(self.timeout)),
+            Err(err) => Err(TraceError::Other(err.into())),
+        }
+    }
+}
+
+/// Exports the queued spans one batch at a time, blocking on the exporter's
+/// future. Unless `drain` is set, a partial batch is left in the queue.
+/// Returns the last export error, if any.
+fn export_queued(
+    exporter: &mut dyn SpanExporter,
+    queue: &BatchQueue<SpanData>,
+    batch_size: usize,
+    drain: bool,
+) -> ExportResult {
+    queue.clear_export_request();
+    let mut result = Ok(());
+    while queue.len() >= batch_size || (drain && queue.len() > 0) {
+        let batch = queue.pop_batch(batch_size);
+        let count = batch.len();
+        let timer = queue.metrics().start_export();
+        let export_result = futures_executor::block_on(exporter.export(batch));
+        timer.finish(count, &export_result);
+        if let Err(err) = export_result {
+            result = Err(err);
+        }
+    }
+    result
+}
+
+impl SpanProcessor for BatchSpanProcessorWithOwnThread {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        // Ignored
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if !span.span_context.is_sampled() || self.is_shutdown.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let Some(len) = self.queue.push(span) else {
+            return;
+        };
+        if len < self.max_export_batch_size || !self.queue.request_export() {
+            return;
+        }
+
+        match self.message_sender.try_send(BatchMessage::ExportSpans) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                // the thread is busy, it exports the queue on its next message
+                self.queue.clear_export_request();
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                otel_debug!(name: "BatchSpanProcessor.OnEnd.ThreadStopped");
+            }
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        if self.is_shutdown.load(Ordering::Relaxed) {
+            return Err(TraceError::Other("processor is shut down".into()));
+        }
+        self.request(BatchMessage::Flush)
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        if self.is_shutdown.swap(true, Ordering::Relaxed) {
+            return Err(TraceError::Other("processor is already shut down".into()));
+        }
+
+        let result = self.request(BatchMessage::Shutdown);
+        if result.is_ok() {
+            if let Some(handle) = self.handle.lock().ok().and_then(|mut handle| handle.take()) {
+                let _ = handle.join();
+            }
+        }
+        result
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        let resource = Arc::new(resource.clone());
+        let _ = self
+            .message_sender
+            .send(BatchMessage::SetResource(resource));
+    }
+}
+
+/// A builder for creating [`BatchSpanProcessorWithOwnThread`] instances.
+#[derive(Debug)]
+pub struct BatchSpanProcessorWithOwnThreadBuilder<E> {
+    exporter: E,
+    config: BatchConfig,
+}
+
+impl<E> BatchSpanProcessorWithOwnThreadBuilder<E>
+where
+    E: SpanExporter + 'static,
+{
+    /// Set the BatchConfig for [BatchSpanProcessorWithOwnThreadBuilder]
+    pub fn with_batch_config(self, config: BatchConfig) -> Self {
+        BatchSpanProcessorWithOwnThreadBuilder { config, ..self }
+    }
+
+    /// Build a batch processor and start its thread.
+    pub fn build(self) -> BatchSpanProcessorWithOwnThread {
+        BatchSpanProcessorWithOwnThread::new(Box::new(self.exporter), self.config)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    // cargo test trace::span_processor_with_own_thread::tests --features=testing,experimental_trace_batch_span_processor_no_runtime
+    use super::*;
+    use crate::testing::trace::{new_test_export_span_data, InMemorySpanExporter};
+    use crate::trace::BatchConfigBuilder;
+    use crate::trace::QueueFullPolicy;
+    use std::sync::atomic::AtomicUsize;
+
+    fn processor(
+        exporter: &InMemorySpanExporter,
+        config: BatchConfigBuilder,
+    ) -> BatchSpanProcessorWithOwnThread {
+        BatchSpanProcessorWithOwnThread::builder(exporter.clone())
+            .with_batch_config(config.build())
+            .build()
+    }
+
+    fn exported(exporter: &InMemorySpanExporter) -> usize {
+        exporter.get_finished_spans().unwrap().len()
+    }
+
+    #[test]
+    fn exports_full_batches_and_flushes_the_rest() {
+        let exporter = InMemorySpanExporter::default();
+        let processor = processor(
+            &exporter,
+            BatchConfigBuilder::default()
+                .with_max_export_batch_size(2)
+                .with_scheduled_delay(Duration::from_secs(3600)),
+        );
+
+        for _ in 0..3 {
+            processor.on_end(new_test_export_span_data());
+        }
+        // the first batch is exported by the processor thread without flush
+        let start = Instant::now();
+        while exported(&exporter) < 2 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(exported(&exporter), 2);
+
+        processor.force_flush().unwrap();
+        assert_eq!(exported(&exporter), 3);
+    }
+
+    #[test]
+    fn exports_on_scheduled_delay() {
+        let exporter = InMemorySpanExporter::default();
+        let processor = processor(
+            &exporter,
+            BatchConfigBuilder::default().with_scheduled_delay(Duration::from_millis(10)),
+        );
+
+        processor.on_end(new_test_export_span_data());
+
+        let start = Instant::now();
+        while exported(&exporter) == 0 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(exported(&exporter), 1);
+    }
+
+    // InMemorySpanExporter clears its spans on shutdown
+    #[derive(Debug, Default)]
+    struct CountingExporter {
+        exported: Arc<AtomicUsize>,
+        is_shutdown: Arc<AtomicBool>,
+    }
+
+    impl SpanExporter for CountingExporter {
+        fn export(
+            &mut self,
+            batch: Vec<SpanData>,
+        ) -> futures_util::future::BoxFuture<'static, ExportResult> {
+            self.exported.fetch_add(batch.len(), Ordering::SeqCst);
+            Box::pin(std::future::ready(Ok(())))
+        }
+
+        fn shutdown(&mut self) {
+            self.is_shutdown.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn shutdown_exports_and_stops() {
+        let exporter = CountingExporter::default();
+        let exported = exporter.exported.clone();
+        let is_shutdown = exporter.is_shutdown.clone();
+        let processor = BatchSpanProcessorWithOwnThread::builder(exporter)
+            .with_batch_config(
+                BatchConfigBuilder::default()
+                    .with_scheduled_delay(Duration::from_secs(3600))
+                    .build(),
+            )
+            .build();
+
+        processor.on_end(new_test_export_span_data());
+        processor.shutdown().unwrap();
+        assert_eq!(exported.load(Ordering::SeqCst), 1);
+        assert!(is_shutdown.load(Ordering::SeqCst));
+
+        processor.on_end(new_test_export_span_data());
+        assert!(processor.force_flush().is_err());
+        assert!(processor.shutdown().is_err());
+        assert_eq!(exported.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn drop_exports_queued_spans() {
+        let exporter = CountingExporter::default();
+        let exported = exporter.exported.clone();
+        let processor = BatchSpanProcessorWithOwnThread::builder(exporter)
+            .with_batch_config(
+                BatchConfigBuilder::default()
+                    .with_scheduled_delay(Duration::from_secs(3600))
+                    .build(),
+            )
+            .build();
+
+        processor.on_end(new_test_export_span_data());
+        drop(processor);
+
+        let start = Instant::now();
+        while exported.load(Ordering::SeqCst) == 0 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(exported.load(Ordering::SeqCst), 1);
+    }
+
+    // Holds exports until the gate is released.
+    #[derive(Debug)]
+    struct GatedExporter {
+        gate: Arc<Mutex<()>>,
+        exported: Arc<AtomicUsize>,
+    }
+
+    impl SpanExporter for GatedExporter {
+        fn export(
+            &mut self,
+            batch: Vec<SpanData>,
+        ) -> futures_util::future::BoxFuture<'static, ExportResult> {
+            let _gate = self.gate.lock().unwrap();
+            self.exported.fetch_add(batch.len(), Ordering::SeqCst);
+            Box::pin(std::future::ready(Ok(())))
+        }
+    }
+
+    #[test]
+    fn queue_stats_count_dropped_spans() {
+        let gate = Arc::new(Mutex::new(()));
+        let exported = Arc::new(AtomicUsize::new(0));
+        let exporter = GatedExporter {
+            gate: gate.clone(),
+            exported: exported.clone(),
+        };
+        let processor = BatchSpanProcessorWithOwnThread::builder(exporter)
+            .with_batch_config(
+                BatchConfigBuilder::default()
+                    .with_max_queue_size(2)
+                    .with_max_export_batch_size(1)
+                    .with_scheduled_delay(Duration::from_secs(3600))
+                    .with_queue_full_policy(QueueFullPolicy::DropOldest)
+                    .build(),
+            )
+            .build();
+        let stats = processor.queue_stats();
+
+        let guard = gate.lock().unwrap();
+        // the processor thread takes the first span and waits on the gate
+        processor.on_end(new_test_export_span_data());
+        let start = Instant::now();
+        while stats.queued() > 0 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        for _ in 0..3 {
+            processor.on_end(new_test_export_span_data());
+        }
+        assert_eq!(stats.queued(), 2);
+        assert_eq!(stats.dropped(), 1);
+
+        drop(guard);
+        processor.force_flush().unwrap();
+        assert_eq!(exported.load(Ordering::SeqCst), 3);
+        assert_eq!(stats.queued(), 0);
+    }
+}
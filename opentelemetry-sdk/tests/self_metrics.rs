@@ -0,0 +1,44 @@
+use opentelemetry::metrics::MeterProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::metrics::{data, PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::self_metrics::set_meter_provider;
+use opentelemetry_sdk::testing::metrics::InMemoryMetricExporter;
+
+// The self-metrics provider is global: the overflows are counted in a test
+// binary of their own, so that no other test overflows a stream meanwhile.
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn counts_cardinality_overflow() {
+    let exporter = InMemoryMetricExporter::default();
+    let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+    let provider = SdkMeterProvider::builder().with_reader(reader).build();
+    set_meter_provider(&provider);
+
+    // overflowing a stream of the self-metrics provider is counted once per
+    // measurement, without recursing
+    let counter = provider.meter("test").u64_counter("test.counter").build();
+    for i in 0..2010 {
+        counter.add(1, &[KeyValue::new("i", i)]);
+    }
+
+    provider.force_flush().unwrap();
+    let resource_metrics = exporter.get_finished_metrics().unwrap();
+    let overflow = resource_metrics[0]
+        .scope_metrics
+        .iter()
+        .find(|sm| sm.scope.name() == "opentelemetry_sdk")
+        .and_then(|sm| {
+            sm.metrics
+                .iter()
+                .find(|m| m.name == "otel.sdk.metric.cardinality_overflow")
+        })
+        .unwrap()
+        .data
+        .as_any()
+        .downcast_ref::<data::Sum<u64>>()
+        .unwrap();
+    // 2000 streams fit under the cardinality limit
+    assert_eq!(overflow.data_points[0].value, 10);
+
+    provider.shutdown().unwrap();
+}
@@ -0,0 +1,281 @@
+use std::{
+    fmt, io,
+    net::{ToSocketAddrs, UdpSocket},
+    sync::atomic::{AtomicBool, Ordering},
+};
+#[cfg(unix)]
+use std::{os::unix::net::UnixDatagram, path::PathBuf};
+
+use async_trait::async_trait;
+use opentelemetry::{Key, KeyValue};
+use opentelemetry_sdk::metrics::{
+    data::ResourceMetrics, exporter::PushMetricExporter, MetricError, MetricResult, Temporality,
+};
+
+use crate::format::{packets, HistogramKind, LineFormat};
+
+const DEFAULT_UDP_ADDR: &str = "127.0.0.1:8125";
+// fits an Ethernet MTU with IP and UDP headers, like the DogStatsD clients
+const DEFAULT_UDP_PACKET_SIZE: usize = 1432;
+#[cfg(unix)]
+const DEFAULT_UNIX_PACKET_SIZE: usize = 8192;
+
+enum Endpoint {
+    Udp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+enum Transport {
+    Udp(UdpSocket),
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+}
+
+impl Transport {
+    fn send(&self, packet: &[u8]) -> io::Result<usize> {
+        match self {
+            Transport::Udp(socket) => socket.send(packet),
+            #[cfg(unix)]
+            Transport::Unix(socket) => socket.send(packet),
+        }
+    }
+}
+
+/// Configuration for a [StatsdExporter].
+pub struct StatsdExporterBuilder {
+    endpoint: Endpoint,
+    format: LineFormat,
+    resource_keys: Vec<Key>,
+    max_packet_size: Option<usize>,
+}
+
+impl Default for StatsdExporterBuilder {
+    fn default() -> Self {
+        StatsdExporterBuilder {
+            endpoint: Endpoint::Udp(DEFAULT_UDP_ADDR.to_string()),
+            format: LineFormat {
+                tags: true,
+                ..Default::default()
+            },
+            resource_keys: Vec::new(),
+            max_packet_size: None,
+        }
+    }
+}
+
+impl fmt::Debug for StatsdExporterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatsdExporterBuilder")
+            .field("format", &self.format)
+            .field("resource_keys", &self.resource_keys)
+            .field("max_packet_size", &self.max_packet_size)
+            .finish()
+    }
+}
+
+impl StatsdExporterBuilder {
+    /// Sends metrics over UDP to `addr`.
+    ///
+    /// Default is `127.0.0.1:8125`.
+    pub fn with_udp(mut self, addr: impl Into<String>) -> Self {
+        self.endpoint = Endpoint::Udp(addr.into());
+        self
+    }
+
+    /// Sends metrics to the Unix datagram socket at `path`, such as the
+    /// DogStatsD agent's `/var/run/datadog/dsd.socket`.
+    #[cfg(unix)]
+    pub fn with_unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.endpoint = Endpoint::Unix(path.into());
+        self
+    }
+
+    /// Prefixes metric names with `prefix` and a `.`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.format.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Sets the StatsD metric type histograms are sent as.
+    ///
+    /// Default is [HistogramKind::Timing].
+    pub fn with_histogram_kind(mut self, kind: HistogramKind) -> Self {
+        self.format.histogram_kind = kind;
+        self
+    }
+
+    /// Adds the resource attributes with the given keys as tags to every line.
+    ///
+    /// By default no resource attributes are sent.
+    pub fn with_resource_tags<K: Into<Key>>(mut self, keys: impl IntoIterator<Item = K>) -> Self {
+        self.resource_keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Disables DogStatsD tags, for StatsD servers that do not support them.
+    ///
+    /// Data points that only differ in their attributes are then sent as the
+    /// same metric.
+    pub fn without_tags(mut self) -> Self {
+        self.format.tags = false;
+        self
+    }
+
+    /// Sets the maximum size of a datagram in bytes.
+    ///
+    /// Default is 1432 bytes for UDP, which fits an Ethernet MTU, and 8192
+    /// bytes for Unix sockets.
+    pub fn with_max_packet_size(mut self, size: usize) -> Self {
+        self.max_packet_size = Some(size);
+        self
+    }
+
+    /// Creates a new [StatsdExporter] from this configuration, connecting its
+    /// socket.
+    pub fn build(self) -> MetricResult<StatsdExporter> {
+        let (transport, default_packet_size) = match self.endpoint {
+            Endpoint::Udp(addr) => (connect_udp(&addr)?, DEFAULT_UDP_PACKET_SIZE),
+            #[cfg(unix)]
+            Endpoint::Unix(path) => {
+                let socket = UnixDatagram::unbound()
+                    .and_then(|socket| socket.connect(&path).map(|_| socket))
+                    .map_err(|err| {
+                        MetricError::Config(format!(
+                            "failed to connect to {}: {err}",
+                            path.display()
+                        ))
+                    })?;
+                (Transport::Unix(socket), DEFAULT_UNIX_PACKET_SIZE)
+            }
+        };
+
+        Ok(StatsdExporter {
+            transport,
+            format: self.format,
+            resource_keys: self.resource_keys,
+            max_packet_size: self.max_packet_size.unwrap_or(default_packet_size),
+            is_shutdown: AtomicBool::new(false),
+        })
+    }
+}
+
+fn connect_udp(addr: &str) -> MetricResult<Transport> {
+    let connect = || {
+        let addr = addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address"))?;
+        let local: &str = if addr.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok::<_, io::Error>(socket)
+    };
+
+    connect()
+        .map(Transport::Udp)
+        .map_err(|err| MetricError::Config(format!("failed to connect to {addr}: {err}")))
+}
+
+/// Sends metrics to a StatsD or DogStatsD server.
+///
+/// Metrics are collected with [Temporality::Delta]: delta sums are sent as
+/// counters, gauges and cumulative sums as gauges, and every populated
+/// histogram bucket as a sampled timing, histogram or distribution line.
+/// Attributes are sent as DogStatsD tags. Lines are packed into datagrams of at
+/// most the configured packet size.
+///
+/// # Examples
+///
+/// ```no_run
+/// use opentelemetry_sdk::{
+///     metrics::{PeriodicReader, SdkMeterProvider},
+///     runtime,
+/// };
+/// use opentelemetry_statsd::{HistogramKind, StatsdExporter};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let exporter = StatsdExporter::builder()
+///     .with_udp("127.0.0.1:8125")
+///     .with_histogram_kind(HistogramKind::Distribution)
+///     .with_resource_tags(["service.name"])
+///     .build()?;
+/// let reader = PeriodicReader::builder(exporter, runtime::Tokio).build();
+/// let provider = SdkMeterProvider::builder().with_reader(reader).build();
+/// # Ok(())
+/// # }
+/// ```
+pub struct StatsdExporter {
+    transport: Transport,
+    format: LineFormat,
+    resource_keys: Vec<Key>,
+    max_packet_size: usize,
+    is_shutdown: AtomicBool,
+}
+
+impl StatsdExporter {
+    /// Create a builder to configure this exporter.
+    pub fn builder() -> StatsdExporterBuilder {
+        StatsdExporterBuilder::default()
+    }
+}
+
+impl fmt::Debug for StatsdExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StatsdExporter")
+    }
+}
+
+#[async_trait]
+impl PushMetricExporter for StatsdExporter {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> MetricResult<()> {
+        if self.is_shutdown.load(Ordering::SeqCst) {
+            return Err(MetricError::Other("exporter is shut down".into()));
+        }
+
+        let resource_tags = self
+            .resource_keys
+            .iter()
+            .filter_map(|key| {
+                let value = metrics.resource.get(key.clone())?;
+                Some(KeyValue::new(key.clone(), value))
+            })
+            .collect::<Vec<_>>();
+
+        let mut lines = Vec::new();
+        for scope_metrics in &metrics.scope_metrics {
+            for metric in &scope_metrics.metrics {
+                self.format.metric_lines(metric, &resource_tags, &mut lines);
+            }
+        }
+
+        // keep sending after a failure, the other packets may still arrive
+        let mut result = Ok(());
+        for packet in packets(&lines, self.max_packet_size) {
+            if let Err(err) = self.transport.send(packet.as_bytes()) {
+                result = Err(MetricError::Other(format!(
+                    "failed to send statsd packet: {err}"
+                )));
+            }
+        }
+        result
+    }
+
+    async fn force_flush(&self) -> MetricResult<()> {
+        // lines are sent on export, nothing is buffered
+        Ok(())
+    }
+
+    fn shutdown(&self) -> MetricResult<()> {
+        self.is_shutdown.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn temporality(&self) -> Temporality {
+        Temporality::Delta
+    }
+}
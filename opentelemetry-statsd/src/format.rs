@@ -0,0 +1,412 @@
+//! Conversion of metrics to StatsD lines.
+use std::fmt::Write;
+
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::metrics::{
+    data::{self, Metric},
+    Temporality,
+};
+
+/// The StatsD metric type histogram data points are sent as.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HistogramKind {
+    /// Timing lines (`|ms`), understood by every StatsD server.
+    #[default]
+    Timing,
+    /// DogStatsD histogram lines (`|h`), aggregated by the agent.
+    Histogram,
+    /// DogStatsD distribution lines (`|d`), aggregated by the backend.
+    Distribution,
+}
+
+impl HistogramKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HistogramKind::Timing => "ms",
+            HistogramKind::Histogram => "h",
+            HistogramKind::Distribution => "d",
+        }
+    }
+}
+
+/// How metrics are rendered as lines.
+#[derive(Debug, Default)]
+pub(crate) struct LineFormat {
+    pub(crate) prefix: Option<String>,
+    pub(crate) histogram_kind: HistogramKind,
+    pub(crate) tags: bool,
+}
+
+impl LineFormat {
+    /// Appends the lines of all data points of `metric`, tagged with
+    /// `constant_tags` in addition to their attributes.
+    ///
+    /// Metrics of unsupported types and non-finite values are skipped.
+    pub(crate) fn metric_lines(
+        &self,
+        metric: &Metric,
+        constant_tags: &[KeyValue],
+        lines: &mut Vec<String>,
+    ) {
+        let name = self.metric_name(&metric.name);
+        let data = metric.data.as_any();
+
+        if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
+            self.sum_lines(&name, sum, constant_tags, lines);
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
+            self.sum_lines(&name, sum, constant_tags, lines);
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
+            self.sum_lines(&name, sum, constant_tags, lines);
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<u64>>() {
+            self.gauge_lines(&name, &gauge.data_points, constant_tags, lines);
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<i64>>() {
+            self.gauge_lines(&name, &gauge.data_points, constant_tags, lines);
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<f64>>() {
+            self.gauge_lines(&name, &gauge.data_points, constant_tags, lines);
+        } else if let Some(histogram) = data.downcast_ref::<data::Histogram<u64>>() {
+            self.histogram_lines(&name, histogram, constant_tags, lines);
+        } else if let Some(histogram) = data.downcast_ref::<data::Histogram<i64>>() {
+            self.histogram_lines(&name, histogram, constant_tags, lines);
+        } else if let Some(histogram) = data.downcast_ref::<data::Histogram<f64>>() {
+            self.histogram_lines(&name, histogram, constant_tags, lines);
+        }
+    }
+
+    fn metric_name(&self, name: &str) -> String {
+        let name = sanitize(name, &[':', '|', '@', '#']);
+        match &self.prefix {
+            Some(prefix) => format!("{}.{name}", sanitize(prefix, &[':', '|', '@', '#'])),
+            None => name,
+        }
+    }
+
+    fn sum_lines<T: Value>(
+        &self,
+        name: &str,
+        sum: &data::Sum<T>,
+        constant_tags: &[KeyValue],
+        lines: &mut Vec<String>,
+    ) {
+        // cumulative sums, such as those of up-down counters, are absolute values
+        if sum.temporality == Temporality::Cumulative {
+            return self.gauge_lines(name, &sum.data_points, constant_tags, lines);
+        }
+
+        for dp in &sum.data_points {
+            let tags = self.tags(&dp.attributes, constant_tags);
+            if let Some(value) = format_value(dp.value.as_f64()) {
+                lines.push(format!("{name}:{value}|c{tags}"));
+            }
+        }
+    }
+
+    fn gauge_lines<T: Value>(
+        &self,
+        name: &str,
+        data_points: &[data::DataPoint<T>],
+        constant_tags: &[KeyValue],
+        lines: &mut Vec<String>,
+    ) {
+        for dp in data_points {
+            let tags = self.tags(&dp.attributes, constant_tags);
+            let value = dp.value.as_f64();
+            let Some(formatted) = format_value(value) else {
+                continue;
+            };
+            // a signed gauge value is a relative change, so negative values
+            // are sent as a decrement from zero, in the same packet so that
+            // the server never sees only one of the two lines
+            if value < 0.0 {
+                lines.push(format!("{name}:0|g{tags}\n{name}:{formatted}|g{tags}"));
+            } else {
+                lines.push(format!("{name}:{formatted}|g{tags}"));
+            }
+        }
+    }
+
+    /// Sends one line per populated bucket, with the bucket's midpoint as
+    /// value and a sample rate of `1 / count`, so the server counts the value
+    /// as many times as the bucket holds measurements.
+    fn histogram_lines<T: Value>(
+        &self,
+        name: &str,
+        histogram: &data::Histogram<T>,
+        constant_tags: &[KeyValue],
+        lines: &mut Vec<String>,
+    ) {
+        let kind = self.histogram_kind.as_str();
+
+        for dp in &histogram.data_points {
+            let tags = self.tags(&dp.attributes, constant_tags);
+            let min = dp.min.map(Value::as_f64);
+            let max = dp.max.map(Value::as_f64);
+
+            if dp.bucket_counts.is_empty() && dp.count > 0 {
+                let mean = dp.sum.as_f64() / dp.count as f64;
+                push_sampled(lines, name, mean, dp.count, kind, &tags);
+                continue;
+            }
+
+            for (i, &count) in dp.bucket_counts.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                let lower = i
+                    .checked_sub(1)
+                    .and_then(|i| dp.bounds.get(i))
+                    .copied()
+                    .unwrap_or(f64::NEG_INFINITY);
+                let upper = dp.bounds.get(i).copied().unwrap_or(f64::INFINITY);
+                let lower = min.map_or(lower, |min| lower.max(min));
+                let upper = max.map_or(upper, |max| upper.min(max));
+
+                let value = match (lower.is_finite(), upper.is_finite()) {
+                    (true, true) => lower + (upper - lower) / 2.0,
+                    (true, false) => lower,
+                    (false, true) => upper,
+                    (false, false) => dp.sum.as_f64() / dp.count as f64,
+                };
+                push_sampled(lines, name, value, count, kind, &tags);
+            }
+        }
+    }
+
+    fn tags(&self, attributes: &[KeyValue], constant_tags: &[KeyValue]) -> String {
+        if !self.tags {
+            return String::new();
+        }
+
+        let mut tags = String::new();
+        for kv in constant_tags.iter().chain(attributes) {
+            tags.push_str(if tags.is_empty() { "|#" } else { "," });
+            let key = sanitize(kv.key.as_str(), &[':', '|', '#', ',']);
+            let value = sanitize(&kv.value.as_str(), &['|', '#', ',']);
+            let _ = write!(tags, "{key}:{value}");
+        }
+        tags
+    }
+}
+
+fn push_sampled(
+    lines: &mut Vec<String>,
+    name: &str,
+    value: f64,
+    count: u64,
+    kind: &str,
+    tags: &str,
+) {
+    let Some(value) = format_value(value) else {
+        return;
+    };
+    if count == 1 {
+        lines.push(format!("{name}:{value}|{kind}{tags}"));
+    } else {
+        let rate = 1.0 / count as f64;
+        lines.push(format!("{name}:{value}|{kind}|@{rate}{tags}"));
+    }
+}
+
+fn format_value(value: f64) -> Option<String> {
+    value.is_finite().then(|| value.to_string())
+}
+
+/// Replaces the characters with a meaning in the line protocol, and
+/// whitespace, with `_`.
+fn sanitize(s: &str, reserved: &[char]) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_whitespace() || reserved.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Packs `lines` into newline separated packets of at most `max_size` bytes.
+///
+/// An entry of `lines` holding several newline separated lines is never split
+/// across packets. A line longer than `max_size` is sent in a packet of its
+/// own, as splitting it would corrupt it.
+pub(crate) fn packets(lines: &[String], max_size: usize) -> Vec<String> {
+    let mut packets = Vec::new();
+    let mut packet = String::new();
+
+    for line in lines {
+        if !packet.is_empty() && packet.len() + 1 + line.len() > max_size {
+            packets.push(std::mem::take(&mut packet));
+        }
+        if !packet.is_empty() {
+            packet.push('\n');
+        }
+        packet.push_str(line);
+    }
+    if !packet.is_empty() {
+        packets.push(packet);
+    }
+
+    packets
+}
+
+/// Numeric data point values.
+trait Value: Copy {
+    fn as_f64(self) -> f64;
+}
+
+impl Value for u64 {
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Value for i64 {
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Value for f64 {
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn metric(name: &str, data: impl data::Aggregation) -> Metric {
+        Metric {
+            name: name.to_string().into(),
+            description: "".into(),
+            unit: "".into(),
+            data: Box::new(data),
+        }
+    }
+
+    fn data_point<T>(value: T, attributes: Vec<KeyValue>) -> data::DataPoint<T> {
+        data::DataPoint {
+            attributes,
+            start_time: None,
+            time: None,
+            value,
+            exemplars: vec![],
+        }
+    }
+
+    fn lines(format: &LineFormat, metric: &Metric) -> Vec<String> {
+        let mut lines = Vec::new();
+        format.metric_lines(metric, &[KeyValue::new("service.name", "svc")], &mut lines);
+        lines
+    }
+
+    fn tagged() -> LineFormat {
+        LineFormat {
+            tags: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn delta_sum_is_a_counter() {
+        let sum = data::Sum {
+            data_points: vec![data_point(3u64, vec![KeyValue::new("http.method", "GET")])],
+            temporality: Temporality::Delta,
+            is_monotonic: true,
+        };
+
+        assert_eq!(
+            lines(&tagged(), &metric("http.requests", sum)),
+            vec!["http.requests:3|c|#service.name:svc,http.method:GET"]
+        );
+    }
+
+    #[test]
+    fn cumulative_sum_and_gauge_are_gauges() {
+        let sum = data::Sum {
+            data_points: vec![data_point(-2i64, vec![])],
+            temporality: Temporality::Cumulative,
+            is_monotonic: false,
+        };
+        let gauge = data::Gauge {
+            data_points: vec![data_point(0.5f64, vec![])],
+        };
+        let format = LineFormat::default();
+
+        assert_eq!(
+            lines(&format, &metric("queue.size", sum)),
+            vec!["queue.size:0|g\nqueue.size:-2|g"]
+        );
+        assert_eq!(
+            lines(&format, &metric("cpu.load", gauge)),
+            vec!["cpu.load:0.5|g"]
+        );
+    }
+
+    #[test]
+    fn histogram_buckets_are_sampled_values() {
+        let histogram = data::Histogram {
+            data_points: vec![data::HistogramDataPoint {
+                attributes: vec![],
+                start_time: SystemTime::UNIX_EPOCH,
+                time: SystemTime::UNIX_EPOCH,
+                count: 7,
+                bounds: vec![10.0, 20.0],
+                bucket_counts: vec![1, 4, 2],
+                min: Some(2.0),
+                max: Some(50.0),
+                sum: 150.0,
+                exemplars: vec![],
+            }],
+            temporality: Temporality::Delta,
+        };
+        let format = LineFormat {
+            prefix: Some("app".into()),
+            histogram_kind: HistogramKind::Distribution,
+            tags: false,
+        };
+
+        assert_eq!(
+            lines(&format, &metric("latency", histogram)),
+            vec![
+                "app.latency:6|d",
+                "app.latency:15|d|@0.25",
+                "app.latency:35|d|@0.5",
+            ]
+        );
+    }
+
+    #[test]
+    fn reserved_characters_are_replaced() {
+        let gauge = data::Gauge {
+            data_points: vec![data_point(1u64, vec![KeyValue::new("a:b", "c,d|e#f")])],
+        };
+
+        assert_eq!(
+            lines(&tagged(), &metric("my metric|x", gauge)),
+            vec!["my_metric_x:1|g|#service.name:svc,a_b:c_d_e_f"]
+        );
+    }
+
+    #[test]
+    fn lines_are_packed_up_to_max_size() {
+        let lines = ["a:1|c", "b:2|c", "c:3|c", "a_very_long_line:1|c"]
+            .map(String::from)
+            .to_vec();
+
+        assert_eq!(
+            packets(&lines, 11),
+            vec!["a:1|c\nb:2|c", "c:3|c", "a_very_long_line:1|c"]
+        );
+    }
+
+    #[test]
+    fn negative_gauge_lines_share_a_packet() {
+        let lines = ["a:1|c", "g:0|g\ng:-2|g"].map(String::from).to_vec();
+
+        assert_eq!(packets(&lines, 12), vec!["a:1|c", "g:0|g\ng:-2|g"]);
+    }
+}
@@ -0,0 +1,70 @@
+//! An OpenTelemetry exporter for [StatsD] and [DogStatsD] metrics.
+//!
+//! [StatsdExporter] is a [PushMetricExporter] converting the collected metrics
+//! to StatsD lines on every export, and sending them over UDP or a Unix
+//! datagram socket:
+//!
+//! * delta sums are sent as counters, e.g. `http.requests:3|c`,
+//! * gauges and cumulative sums, such as those of up-down counters, as gauges,
+//!   e.g. `queue.size:12|g`. Negative values are sent as a reset to zero
+//!   followed by the value, in the same packet,
+//! * histograms as one timing, histogram or distribution line per populated
+//!   bucket, with the bucket's midpoint as value and a sample rate standing for
+//!   the bucket count, e.g. `latency:15|ms|@0.25`.
+//!
+//! Attributes, and the selected resource attributes, are sent as DogStatsD tags,
+//! e.g. `http.requests:3|c|#http.method:GET`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use opentelemetry::{metrics::MeterProvider, KeyValue};
+//! use opentelemetry_sdk::{
+//!     metrics::{PeriodicReader, SdkMeterProvider},
+//!     runtime,
+//! };
+//! use opentelemetry_statsd::StatsdExporter;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let exporter = StatsdExporter::builder()
+//!     .with_udp("127.0.0.1:8125")
+//!     .with_prefix("my_app")
+//!     .build()?;
+//! let reader = PeriodicReader::builder(exporter, runtime::Tokio).build();
+//! let provider = SdkMeterProvider::builder().with_reader(reader).build();
+//!
+//! let counter = provider.meter("my-app").u64_counter("requests").build();
+//! counter.add(1, &[KeyValue::new("route", "/")]);
+//! // sent as `my_app.requests:1|c|#route:/`
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [StatsD]: https://github.com/statsd/statsd
+//! [DogStatsD]: https://docs.datadoghq.com/developers/dogstatsd/
+//! [PushMetricExporter]: opentelemetry_sdk::metrics::exporter::PushMetricExporter
+#![warn(
+    future_incompatible,
+    missing_debug_implementations,
+    missing_docs,
+    nonstandard_style,
+    rust_2018_idioms,
+    unreachable_pub,
+    unused
+)]
+#![cfg_attr(
+    docsrs,
+    feature(doc_cfg, doc_auto_cfg),
+    deny(rustdoc::broken_intra_doc_links)
+)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/open-telemetry/opentelemetry-rust/main/assets/logo.svg"
+)]
+#![cfg_attr(test, deny(warnings))]
+
+mod exporter;
+mod format;
+
+pub use exporter::{StatsdExporter, StatsdExporterBuilder};
+pub use format::HistogramKind;
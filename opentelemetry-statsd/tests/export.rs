@@ -0,0 +1,152 @@
+use std::{net::UdpSocket, time::Duration};
+
+use opentelemetry::{
+    metrics::{MeterProvider, UpDownCounter},
+    KeyValue,
+};
+use opentelemetry_sdk::{
+    metrics::{
+        data::ResourceMetrics, exporter::PushMetricExporter, PeriodicReader, SdkMeterProvider,
+    },
+    runtime, Resource,
+};
+use opentelemetry_statsd::StatsdExporter;
+
+fn listener() -> UdpSocket {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    socket
+}
+
+fn receive(socket: &UdpSocket) -> String {
+    let mut buf = [0; 65536];
+    let len = socket.recv(&mut buf).unwrap();
+    String::from_utf8(buf[..len].to_vec()).unwrap()
+}
+
+/// Records a counter, an up-down counter and a histogram, and exports them
+/// with `exporter`.
+fn export(exporter: StatsdExporter) {
+    let reader = PeriodicReader::builder(exporter, runtime::Tokio).build();
+    let provider = SdkMeterProvider::builder()
+        .with_resource(Resource::new([KeyValue::new("service.name", "checkout")]))
+        .with_reader(reader)
+        .build();
+    let meter = provider.meter("test");
+
+    meter
+        .u64_counter("http.requests")
+        .build()
+        .add(3, &[KeyValue::new("http.method", "GET")]);
+    let queue: UpDownCounter<i64> = meter.i64_up_down_counter("queue.size").build();
+    queue.add(5, &[]);
+    let histogram = meter
+        .f64_histogram("latency")
+        .with_boundaries(vec![10.0, 20.0])
+        .build();
+    for value in [12.0, 14.0] {
+        histogram.record(value, &[]);
+    }
+
+    provider.force_flush().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sends_lines_over_udp() {
+    let listener = listener();
+    let exporter = StatsdExporter::builder()
+        .with_udp(listener.local_addr().unwrap().to_string())
+        .with_prefix("shop")
+        .with_resource_tags(["service.name"])
+        .build()
+        .unwrap();
+
+    export(exporter);
+
+    let mut lines = receive(&listener)
+        .lines()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    lines.sort();
+    assert_eq!(
+        lines,
+        vec![
+            "shop.http.requests:3|c|#service.name:checkout,http.method:GET",
+            "shop.latency:13|ms|@0.5|#service.name:checkout",
+            "shop.queue.size:5|g|#service.name:checkout",
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn batches_lines_to_packet_size() {
+    let listener = listener();
+    let exporter = StatsdExporter::builder()
+        .with_udp(listener.local_addr().unwrap().to_string())
+        .without_tags()
+        .with_max_packet_size(40)
+        .build()
+        .unwrap();
+
+    export(exporter);
+
+    let mut lines = Vec::new();
+    for _ in 0..2 {
+        let packet = receive(&listener);
+        assert!(packet.len() <= 40, "{packet}");
+        lines.extend(packet.lines().map(str::to_string));
+    }
+    lines.sort();
+    assert_eq!(
+        lines,
+        vec!["http.requests:3|c", "latency:13|ms|@0.5", "queue.size:5|g"]
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn sends_lines_over_unix_socket() {
+    use std::os::unix::net::UnixDatagram;
+
+    let dir = std::env::temp_dir().join(format!("otel-statsd-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("dsd.socket");
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixDatagram::bind(&path).unwrap();
+    listener
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    let exporter = StatsdExporter::builder()
+        .with_unix_socket(&path)
+        .without_tags()
+        .build()
+        .unwrap();
+    export(exporter);
+
+    let mut buf = [0; 8192];
+    let len = listener.recv(&mut buf).unwrap();
+    let packet = std::str::from_utf8(&buf[..len]).unwrap();
+    assert_eq!(packet.lines().count(), 3, "{packet}");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn export_after_shutdown_fails() {
+    let listener = listener();
+    let exporter = StatsdExporter::builder()
+        .with_udp(listener.local_addr().unwrap().to_string())
+        .build()
+        .unwrap();
+
+    exporter.shutdown().unwrap();
+
+    let mut metrics = ResourceMetrics {
+        resource: Resource::empty(),
+        scope_metrics: vec![],
+    };
+    assert!(exporter.export(&mut metrics).await.is_err());
+}
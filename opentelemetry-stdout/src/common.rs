@@ -1,25 +1,84 @@
+//! Serde models of the OTLP JSON encoding, shared by the signals, and
+//! helpers for the human-oriented formats.
 use std::{
     borrow::Cow,
-    collections::BTreeMap,
+    fmt,
     hash::{Hash, Hasher},
+    io::{self, Write},
+    sync::Mutex,
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use chrono::{LocalResult, TimeZone, Utc};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
 use ordered_float::OrderedFloat;
-use serde::{Serialize, Serializer};
+use serde::{ser::SerializeMap, Serialize, Serializer};
 
-#[derive(Debug, Serialize, Clone, Hash, Eq, PartialEq)]
-pub(crate) struct AttributeSet(pub BTreeMap<Key, Value>);
+/// Output format of the stdout exporters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// Multi-line, indented output meant to be read while debugging.
+    #[default]
+    Pretty,
+    /// One line per log record, span or metric data point.
+    Compact,
+    /// One OTLP JSON request per export, on a single line, as written by the
+    /// OpenTelemetry Collector's file exporter.
+    OtlpJson,
+}
 
-impl From<&opentelemetry_sdk::Resource> for AttributeSet {
-    fn from(value: &opentelemetry_sdk::Resource) -> Self {
-        AttributeSet(
-            value
-                .iter()
-                .map(|(key, value)| (Key::from(key.clone()), Value::from(value.clone())))
-                .collect(),
-        )
+/// The destination of an exporter's output.
+pub(crate) struct Output {
+    writer: Mutex<Box<dyn Write + Send>>,
+    pub(crate) format: Format,
+}
+
+impl Output {
+    pub(crate) fn new(writer: Box<dyn Write + Send>, format: Format) -> Self {
+        Output {
+            writer: Mutex::new(writer),
+            format,
+        }
+    }
+
+    /// Runs `write` with the locked writer, then flushes it.
+    pub(crate) fn write<F>(&self, write: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "writer lock poisoned"))?;
+        write(writer.as_mut())?;
+        writer.flush()
+    }
+
+    /// Writes `value` as a JSON line.
+    pub(crate) fn write_json_line<T: Serialize>(&self, value: &T) -> io::Result<()> {
+        self.write(|writer| {
+            serde_json::to_writer(&mut *writer, value)?;
+            writer.write_all(b"\n")
+        })
+    }
+
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        self.write(|_| Ok(()))
+    }
+}
+
+impl fmt::Debug for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Output")
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output::new(Box::new(io::stdout()), Format::default())
     }
 }
 
@@ -65,25 +124,41 @@ impl From<opentelemetry::Key> for Key {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Clone)]
 #[allow(dead_code, clippy::enum_variant_names)] // we want to emphasize the *Values are collection
 pub(crate) enum Value {
-    #[serde(rename = "boolValue")]
     Bool(bool),
-    #[serde(rename = "intValue")]
     Int(i64),
-    #[serde(rename = "doubleValue")]
     Double(f64),
-    #[serde(rename = "stringValue")]
     String(String),
-    #[serde(rename = "arrayValue")]
     Array(Vec<Value>),
-    #[serde(rename = "kvListValue")]
     KeyValues(Vec<KeyValue>),
-    #[serde(rename = "bytesValue")]
     BytesValue(Vec<u8>),
 }
 
+/// The `values` wrapper of OTLP JSON arrays and key-value lists.
+#[derive(Serialize)]
+struct Values<'a, T> {
+    values: &'a [T],
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        match self {
+            Value::Bool(b) => map.serialize_entry("boolValue", b)?,
+            // 64-bit integers are strings in OTLP JSON
+            Value::Int(i) => map.serialize_entry("intValue", &i.to_string())?,
+            Value::Double(f) => map.serialize_entry("doubleValue", f)?,
+            Value::String(s) => map.serialize_entry("stringValue", s)?,
+            Value::Array(a) => map.serialize_entry("arrayValue", &Values { values: a })?,
+            Value::KeyValues(kv) => map.serialize_entry("kvlistValue", &Values { values: kv })?,
+            Value::BytesValue(b) => map.serialize_entry("bytesValue", &STANDARD.encode(b))?,
+        }
+        map.end()
+    }
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -176,6 +251,40 @@ impl From<opentelemetry::logs::AnyValue> for Value {
     }
 }
 
+/// Formats values the way they would be written in code, e.g. strings
+/// without quotes and lists in brackets.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Double(d) => write!(f, "{}", d),
+            Value::String(s) => f.write_str(s),
+            Value::Array(a) => {
+                f.write_str("[")?;
+                for (i, value) in a.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            Value::KeyValues(kv) => {
+                f.write_str("{")?;
+                for (i, kv) in kv.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}={}", kv.key.0, kv.value)?;
+                }
+                f.write_str("}")
+            }
+            Value::BytesValue(b) => write!(f, "{:?}", b),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct KeyValue {
@@ -233,8 +342,8 @@ pub(crate) struct Scope {
     dropped_attributes_count: u64,
 }
 
-impl From<opentelemetry::InstrumentationScope> for Scope {
-    fn from(value: opentelemetry::InstrumentationScope) -> Self {
+impl From<&opentelemetry::InstrumentationScope> for Scope {
+    fn from(value: &opentelemetry::InstrumentationScope) -> Self {
         Scope {
             name: value.name().to_owned().into(),
             version: value.version().map(ToOwned::to_owned).map(Into::into),
@@ -244,29 +353,23 @@ impl From<opentelemetry::InstrumentationScope> for Scope {
     }
 }
 
-pub(crate) fn as_human_readable<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+/// Serializes a time as the string of its nanoseconds since the Unix epoch,
+/// as OTLP JSON encodes 64-bit integers.
+pub(crate) fn as_unix_nano<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    let duration_since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
-
-    match Utc.timestamp_opt(
-        duration_since_epoch.as_secs() as i64,
-        duration_since_epoch.subsec_nanos(),
-    ) {
-        LocalResult::Single(datetime) => serializer.serialize_str(
-            datetime
-                .format("%Y-%m-%d %H:%M:%S.%3f")
-                .to_string()
-                .as_ref(),
-        ),
-        _ => Err(serde::ser::Error::custom("Invalid Timestamp.")),
-    }
+    let nanos = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    serializer.serialize_str(&nanos.to_string())
 }
 
 #[allow(dead_code)]
 // Used for serde serialization. Not used in traces.
-pub(crate) fn as_opt_human_readable<S>(
+pub(crate) fn as_opt_unix_nano<S>(
     time: &Option<SystemTime>,
     serializer: S,
 ) -> Result<S::Ok, S::Error>
@@ -274,33 +377,76 @@ where
     S: Serializer,
 {
     match time {
-        None => serializer.serialize_none(),
-        Some(time) => as_human_readable(time, serializer),
+        None => serializer.serialize_str("0"),
+        Some(time) => as_unix_nano(time, serializer),
     }
 }
 
-pub(crate) fn as_unix_nano<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+/// Serializes a 64-bit unsigned integer as a string, as OTLP JSON does.
+#[allow(dead_code)]
+pub(crate) fn as_string<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
 where
+    T: ToString,
     S: Serializer,
 {
-    let nanos = time
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
+    serializer.serialize_str(&value.to_string())
+}
 
-    serializer.serialize_u128(nanos)
+/// Formats a time with microseconds, in UTC.
+pub(crate) fn format_time(time: SystemTime) -> impl fmt::Display {
+    let datetime: DateTime<Utc> = time.into();
+    datetime.format("%Y-%m-%d %H:%M:%S%.6f")
 }
 
+/// Formats a time in RFC 3339 with microseconds, for single-line output.
 #[allow(dead_code)]
-pub(crate) fn as_opt_unix_nano<S>(
-    time: &Option<SystemTime>,
-    serializer: S,
-) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    match time {
-        None => serializer.serialize_none(),
-        Some(time) => as_unix_nano(time, serializer),
+pub(crate) fn format_time_rfc3339(time: SystemTime) -> impl fmt::Display {
+    let datetime: DateTime<Utc> = time.into();
+    datetime.format("%Y-%m-%dT%H:%M:%S%.6fZ")
+}
+
+/// A writer to an in-memory buffer shared by its clones.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub(crate) struct SharedBuffer(std::sync::Arc<Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl SharedBuffer {
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[cfg(test)]
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_use_otlp_json_encoding() {
+        let value = Value::KeyValues(vec![KeyValue {
+            key: Key("list".into()),
+            value: Value::Array(vec![
+                Value::Int(1),
+                Value::Double(1.5),
+                Value::BytesValue(b"otel".to_vec()),
+            ]),
+        }]);
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"kvlistValue":{"values":[{"key":"list","value":{"arrayValue":{"values":[{"intValue":"1"},{"doubleValue":1.5},{"bytesValue":"b3RlbA=="}]}}}]}}"#
+        );
+        assert_eq!(value.to_string(), "{list=[1, 1.5, [111, 116, 101, 108]]}");
     }
 }
@@ -13,7 +13,7 @@
 //!
 //! The following feature flags generate additional code and types:
 //! * `populate-logs-event-name`: Enables sending `LogRecord::event_name` as an attribute
-//!    with the key `name`
+//!   with the key `event.name` in the OTLP JSON output
 //!
 //! # Examples
 //!
@@ -58,6 +58,31 @@
 //!
 //! # }
 //! ```
+//!
+//! # Writers and formats
+//!
+//! Each exporter has a builder to write to any [`Write`](std::io::Write)
+//! target instead of stdout, such as stderr, a file or an in-memory buffer,
+//! and to choose the output [`Format`]: the default multi-line
+//! [`Format::Pretty`] output, one line per record with [`Format::Compact`], or
+//! one OTLP JSON line per export with [`Format::OtlpJson`], as written by the
+//! OpenTelemetry Collector's file exporter.
+//!
+//! ```no_run
+//! # #[cfg(feature = "trace")]
+//! # {
+//! use opentelemetry_sdk::trace::TracerProvider;
+//! use opentelemetry_stdout::{Format, SpanExporter};
+//!
+//! let exporter = SpanExporter::builder()
+//!     .with_writer(std::io::stderr())
+//!     .with_format(Format::Compact)
+//!     .build();
+//! let tracer_provider = TracerProvider::builder()
+//!     .with_simple_exporter(exporter)
+//!     .build();
+//! # }
+//! ```
 #![warn(missing_debug_implementations, missing_docs)]
 #![cfg_attr(
     docsrs,
@@ -66,6 +91,7 @@
 )]
 
 pub(crate) mod common;
+pub use common::Format;
 
 #[cfg(feature = "metrics")]
 mod metrics;
@@ -1,25 +1,33 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use core::fmt;
 use opentelemetry_sdk::export::logs::LogBatch;
-use opentelemetry_sdk::logs::LogResult;
+use opentelemetry_sdk::logs::{LogError, LogResult};
 use opentelemetry_sdk::Resource;
+use std::io::{self, Write};
 use std::sync::atomic;
+use std::time::Duration;
+
+use crate::common::{format_time, format_time_rfc3339, Format, Output, Value};
+use crate::logs::transform::LogData;
 
 /// An OpenTelemetry exporter that writes Logs to stdout on export.
 pub struct LogExporter {
     resource: Resource,
     is_shutdown: atomic::AtomicBool,
     resource_emitted: bool,
+    output: Output,
+}
+
+impl LogExporter {
+    /// Create a builder to configure this exporter.
+    pub fn builder() -> LogExporterBuilder {
+        LogExporterBuilder::default()
+    }
 }
 
 impl Default for LogExporter {
     fn default() -> Self {
-        LogExporter {
-            resource: Resource::default(),
-            is_shutdown: atomic::AtomicBool::new(false),
-            resource_emitted: false,
-        }
+        LogExporterBuilder::default().build()
     }
 }
 
@@ -31,29 +39,44 @@ impl fmt::Debug for LogExporter {
 
 #[async_trait]
 impl opentelemetry_sdk::export::logs::LogExporter for LogExporter {
-    /// Export spans to stdout
+    /// Export logs to the configured writer
     async fn export(&mut self, batch: LogBatch<'_>) -> LogResult<()> {
         if self.is_shutdown.load(atomic::Ordering::SeqCst) {
             return Err("exporter is shut down".into());
-        } else {
-            println!("Logs");
-            if self.resource_emitted {
-                print_logs(batch);
-            } else {
+        }
+
+        let result = match self.output.format {
+            Format::Compact => self.output.write(|w| write_compact_logs(w, &batch)),
+            Format::OtlpJson => self
+                .output
+                .write_json_line(&LogData::new(&batch, &self.resource)),
+            Format::Pretty => {
+                let emit_resource = !self.resource_emitted;
                 self.resource_emitted = true;
-                println!("Resource");
-                if let Some(schema_url) = self.resource.schema_url() {
-                    println!("\t Resource SchemaUrl: {:?}", schema_url);
-                }
-                self.resource.iter().for_each(|(k, v)| {
-                    println!("\t ->  {}={:?}", k, v);
-                });
-
-                print_logs(batch);
+                let resource = &self.resource;
+                self.output.write(|w| {
+                    writeln!(w, "Logs")?;
+                    if emit_resource {
+                        writeln!(w, "Resource")?;
+                        if let Some(schema_url) = resource.schema_url() {
+                            writeln!(w, "\t Resource SchemaUrl: {:?}", schema_url)?;
+                        }
+                        for (k, v) in resource.iter() {
+                            writeln!(w, "\t ->  {}={:?}", k, v)?;
+                        }
+                    }
+                    write_logs(w, &batch)
+                })
             }
+        };
 
-            Ok(())
-        }
+        result.map_err(|err| LogError::Other(err.into()))
+    }
+
+    fn force_flush(&mut self, _timeout: Duration) -> LogResult<()> {
+        self.output
+            .flush()
+            .map_err(|err| LogError::Other(err.into()))
     }
 
     fn shutdown(&mut self) {
@@ -65,44 +88,175 @@ impl opentelemetry_sdk::export::logs::LogExporter for LogExporter {
     }
 }
 
-fn print_logs(batch: LogBatch<'_>) {
+fn write_logs(w: &mut dyn Write, batch: &LogBatch<'_>) -> io::Result<()> {
     for (i, log) in batch.iter().enumerate() {
-        println!("Log #{}", i);
+        writeln!(w, "Log #{}", i)?;
         let (record, _library) = log;
         if let Some(event_name) = record.event_name {
-            println!("\t EventName: {:?}", event_name);
+            writeln!(w, "\t EventName: {:?}", event_name)?;
         }
         if let Some(target) = &record.target {
-            println!("\t Target (Scope): {:?}", target);
+            writeln!(w, "\t Target (Scope): {:?}", target)?;
         }
         if let Some(trace_context) = &record.trace_context {
-            println!("\t TraceId: {:?}", trace_context.trace_id);
-            println!("\t SpanId: {:?}", trace_context.span_id);
+            writeln!(w, "\t TraceId: {:?}", trace_context.trace_id)?;
+            writeln!(w, "\t SpanId: {:?}", trace_context.span_id)?;
         }
         if let Some(timestamp) = record.timestamp {
-            let datetime: DateTime<Utc> = timestamp.into();
-            println!("\t Timestamp: {}", datetime.format("%Y-%m-%d %H:%M:%S%.6f"));
+            writeln!(w, "\t Timestamp: {}", format_time(timestamp))?;
         }
         if let Some(timestamp) = record.observed_timestamp {
-            let datetime: DateTime<Utc> = timestamp.into();
-            println!(
-                "\t Observed Timestamp: {}",
-                datetime.format("%Y-%m-%d %H:%M:%S%.6f")
-            );
+            writeln!(w, "\t Observed Timestamp: {}", format_time(timestamp))?;
         }
         if let Some(severity) = record.severity_text {
-            println!("\t SeverityText: {:?}", severity);
+            writeln!(w, "\t SeverityText: {:?}", severity)?;
         }
         if let Some(severity) = record.severity_number {
-            println!("\t SeverityNumber: {:?}", severity);
+            writeln!(w, "\t SeverityNumber: {:?}", severity)?;
         }
         if let Some(body) = &record.body {
-            println!("\t Body: {:?}", body);
+            writeln!(w, "\t Body: {:?}", body)?;
         }
 
-        println!("\t Attributes:");
+        writeln!(w, "\t Attributes:")?;
         for (k, v) in record.attributes_iter() {
-            println!("\t\t ->  {}: {:?}", k, v);
+            writeln!(w, "\t\t ->  {}: {:?}", k, v)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes one line per log record, e.g.
+/// `2024-11-12T10:00:00.000000Z INFO my_crate::db: connected event_name=db.connect key=value`
+fn write_compact_logs(w: &mut dyn Write, batch: &LogBatch<'_>) -> io::Result<()> {
+    for (record, scope) in batch.iter() {
+        let timestamp = record.timestamp.or(record.observed_timestamp);
+        match timestamp {
+            Some(timestamp) => write!(w, "{} ", format_time_rfc3339(timestamp))?,
+            None => write!(w, "- ")?,
+        }
+        match (record.severity_text, record.severity_number) {
+            (Some(text), _) => write!(w, "{}", text)?,
+            (None, Some(severity)) => write!(w, "{}", severity.name())?,
+            (None, None) => write!(w, "-")?,
+        }
+        let target = record.target.as_deref().unwrap_or_else(|| scope.name());
+        write!(w, " {}:", target)?;
+        if let Some(body) = &record.body {
+            write!(w, " {}", Value::from(body.clone()))?;
+        }
+        if let Some(event_name) = record.event_name {
+            write!(w, " event_name={}", event_name)?;
+        }
+        if let Some(trace_context) = &record.trace_context {
+            write!(
+                w,
+                " trace_id={} span_id={}",
+                trace_context.trace_id, trace_context.span_id
+            )?;
+        }
+        for (key, value) in record.attributes_iter() {
+            write!(w, " {}={}", key, Value::from(value.clone()))?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Configuration for the stdout log exporter.
+#[derive(Default)]
+pub struct LogExporterBuilder {
+    writer: Option<Box<dyn Write + Send>>,
+    format: Format,
+}
+
+impl LogExporterBuilder {
+    /// Write the logs to `writer` instead of stdout, e.g. to stderr, a file
+    /// or an in-memory buffer.
+    pub fn with_writer<W: Write + Send + 'static>(mut self, writer: W) -> Self {
+        self.writer = Some(Box::new(writer));
+        self
+    }
+
+    /// Set the output [Format] of the exporter. Defaults to [Format::Pretty].
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Create a log exporter with the current configuration
+    pub fn build(self) -> LogExporter {
+        LogExporter {
+            resource: Resource::default(),
+            is_shutdown: atomic::AtomicBool::new(false),
+            resource_emitted: false,
+            output: Output::new(
+                self.writer.unwrap_or_else(|| Box::new(io::stdout())),
+                self.format,
+            ),
         }
     }
 }
+
+impl fmt::Debug for LogExporterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogExporterBuilder")
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SharedBuffer;
+    use opentelemetry::logs::{LogRecord as _, Logger as _, LoggerProvider as _, Severity};
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::logs::LoggerProvider;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn emit(format: Format) -> String {
+        let buffer = SharedBuffer::default();
+        let exporter = LogExporter::builder()
+            .with_writer(buffer.clone())
+            .with_format(format)
+            .build();
+        let provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter)
+            .with_resource(Resource::new(vec![KeyValue::new("service.name", "test")]))
+            .build();
+
+        let logger = provider.logger("my_app");
+        let mut record = logger.create_log_record();
+        record.set_timestamp(UNIX_EPOCH + Duration::from_secs(1));
+        record.set_severity_number(Severity::Warn);
+        record.set_target("my_app::db");
+        record.set_body("connection lost".into());
+        record.add_attribute("db.system", "postgresql");
+        logger.emit(record);
+
+        buffer.contents()
+    }
+
+    #[test]
+    fn writes_logs_in_each_format() {
+        let pretty = emit(Format::Pretty);
+        assert!(pretty.starts_with("Logs\nResource\n"));
+        assert!(pretty.contains("\t Target (Scope): \"my_app::db\"\n"));
+
+        assert_eq!(
+            emit(Format::Compact),
+            "1970-01-01T00:00:01.000000Z WARN my_app::db: connection lost db.system=postgresql\n"
+        );
+
+        let json = emit(Format::OtlpJson);
+        assert_eq!(json.lines().count(), 1);
+        assert!(json.starts_with(
+            r#"{"resourceLogs":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"test"}}]},"scopeLogs":[{"scope":{"name":"my_app"},"logRecords":[{"timeUnixNano":"1000000000","observedTimeUnixNano":""#
+        ));
+        assert!(json.ends_with(
+            r#""severityNumber":13,"body":{"stringValue":"connection lost"},"attributes":[{"key":"db.system","value":{"stringValue":"postgresql"}}]}]}]}]}
+"#
+        ));
+    }
+}
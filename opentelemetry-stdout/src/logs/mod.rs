@@ -1,8 +1,12 @@
 //! # Stdout Log Exporter
 //!
-//! The stdout [`LogExporter`] writes debug printed [`LogRecord`]s to Stdout.
+//! The stdout [`LogExporter`] writes [`LogRecord`]s to stdout, or to any configured
+//! writer, in one of the supported [`Format`]s.
 //!
+//! [`Format`]: crate::Format
 //! [`LogExporter`]: opentelemetry_sdk::export::logs::LogExporter
 //! [`LogRecord`]: opentelemetry::logs::LogRecord
 mod exporter;
+mod transform;
+
 pub use exporter::*;
@@ -0,0 +1,122 @@
+use std::{borrow::Cow, time::SystemTime};
+
+use opentelemetry::InstrumentationScope;
+use opentelemetry_sdk::export::logs::LogBatch;
+use serde::Serialize;
+
+use crate::common::{as_opt_unix_nano, KeyValue, Resource, Scope, Value};
+
+/// Transformed logs data that can be serialized, as OTLP JSON.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogData {
+    resource_logs: Vec<ResourceLogs>,
+}
+
+impl LogData {
+    pub(crate) fn new(batch: &LogBatch<'_>, resource: &opentelemetry_sdk::Resource) -> Self {
+        let mut scope_logs: Vec<(&InstrumentationScope, Vec<LogRecord>)> = Vec::new();
+        for (record, scope) in batch.iter() {
+            match scope_logs.iter_mut().find(|(s, _)| *s == scope) {
+                Some((_, records)) => records.push(record.into()),
+                None => scope_logs.push((scope, vec![record.into()])),
+            }
+        }
+
+        LogData {
+            resource_logs: vec![ResourceLogs {
+                resource: resource.into(),
+                scope_logs: scope_logs
+                    .into_iter()
+                    .map(|(scope, log_records)| ScopeLogs {
+                        scope: scope.into(),
+                        log_records,
+                        schema_url: scope.schema_url().map(|s| s.to_owned().into()),
+                    })
+                    .collect(),
+                schema_url: resource.schema_url().map(|s| s.to_owned().into()),
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceLogs {
+    resource: Resource,
+    scope_logs: Vec<ScopeLogs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_url: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScopeLogs {
+    scope: Scope,
+    log_records: Vec<LogRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_url: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogRecord {
+    #[serde(serialize_with = "as_opt_unix_nano")]
+    time_unix_nano: Option<SystemTime>,
+    #[serde(serialize_with = "as_opt_unix_nano")]
+    observed_time_unix_nano: Option<SystemTime>,
+    #[serde(skip_serializing_if = "is_zero")]
+    severity_number: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity_text: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped_attributes_count: u32,
+    #[serde(skip_serializing_if = "is_zero")]
+    flags: u32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    trace_id: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    span_id: String,
+}
+
+fn is_zero(v: &u32) -> bool {
+    *v == 0
+}
+
+impl From<&opentelemetry_sdk::logs::LogRecord> for LogRecord {
+    fn from(record: &opentelemetry_sdk::logs::LogRecord) -> Self {
+        let trace_context = record.trace_context.as_ref();
+        #[allow(unused_mut)]
+        let mut attributes: Vec<KeyValue> = record
+            .attributes_iter()
+            .map(|(key, value)| (key.clone(), value.clone()).into())
+            .collect();
+        #[cfg(feature = "populate-logs-event-name")]
+        if let Some(event_name) = record.event_name {
+            attributes.push(opentelemetry::KeyValue::new("event.name", event_name).into());
+        }
+
+        LogRecord {
+            time_unix_nano: record.timestamp,
+            observed_time_unix_nano: record.observed_timestamp,
+            severity_number: record.severity_number.map_or(0, |severity| severity as u32),
+            severity_text: record.severity_text,
+            body: record.body.clone().map(Into::into),
+            attributes,
+            dropped_attributes_count: record.dropped_attributes_count(),
+            flags: trace_context
+                .and_then(|ctx| ctx.trace_flags)
+                .map_or(0, |flags| u32::from(flags.to_u8())),
+            trace_id: trace_context
+                .map(|ctx| ctx.trace_id.to_string())
+                .unwrap_or_default(),
+            span_id: trace_context
+                .map(|ctx| ctx.span_id.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
 use core::{f64, fmt};
 use opentelemetry_sdk::metrics::{
     data::{self, ScopeMetrics},
@@ -7,12 +6,18 @@ use opentelemetry_sdk::metrics::{
 };
 use opentelemetry_sdk::metrics::{MetricError, MetricResult, Temporality};
 use std::fmt::Debug;
+use std::io::{self, Write};
 use std::sync::atomic;
+use std::time::SystemTime;
+
+use crate::common::{format_time, format_time_rfc3339, Format, Output, Value};
+use crate::metrics::transform::MetricsData;
 
 /// An OpenTelemetry exporter that writes to stdout on export.
 pub struct MetricExporter {
     is_shutdown: atomic::AtomicBool,
     temporality: Temporality,
+    output: Output,
 }
 
 impl MetricExporter {
@@ -35,28 +40,38 @@ impl fmt::Debug for MetricExporter {
 
 #[async_trait]
 impl PushMetricExporter for MetricExporter {
-    /// Write Metrics to stdout
+    /// Write Metrics to the configured writer
     async fn export(&self, metrics: &mut data::ResourceMetrics) -> MetricResult<()> {
         if self.is_shutdown.load(atomic::Ordering::SeqCst) {
-            Err(MetricError::Other("exporter is shut down".into()))
-        } else {
-            println!("Metrics");
-            println!("Resource");
-            if let Some(schema_url) = metrics.resource.schema_url() {
-                println!("\tResource SchemaUrl: {:?}", schema_url);
-            }
-
-            metrics.resource.iter().for_each(|(k, v)| {
-                println!("\t ->  {}={:?}", k, v);
-            });
-            print_metrics(&metrics.scope_metrics);
-            Ok(())
+            return Err(MetricError::Other("exporter is shut down".into()));
         }
+
+        let result = match self.output.format {
+            Format::Compact => self
+                .output
+                .write(|w| write_compact_metrics(w, &metrics.scope_metrics)),
+            Format::OtlpJson => self.output.write_json_line(&MetricsData::from(&*metrics)),
+            Format::Pretty => self.output.write(|w| {
+                writeln!(w, "Metrics")?;
+                writeln!(w, "Resource")?;
+                if let Some(schema_url) = metrics.resource.schema_url() {
+                    writeln!(w, "\tResource SchemaUrl: {:?}", schema_url)?;
+                }
+
+                for (k, v) in metrics.resource.iter() {
+                    writeln!(w, "\t ->  {}={:?}", k, v)?;
+                }
+                write_metrics(w, &metrics.scope_metrics)
+            }),
+        };
+
+        result.map_err(|err| MetricError::Other(err.to_string()))
     }
 
     async fn force_flush(&self) -> MetricResult<()> {
-        // exporter holds no state, nothing to flush
-        Ok(())
+        self.output
+            .flush()
+            .map_err(|err| MetricError::Other(err.to_string()))
     }
 
     fn shutdown(&self) -> MetricResult<()> {
@@ -69,156 +84,266 @@ impl PushMetricExporter for MetricExporter {
     }
 }
 
-fn print_metrics(metrics: &[ScopeMetrics]) {
+fn write_metrics(w: &mut dyn Write, metrics: &[ScopeMetrics]) -> io::Result<()> {
     for (i, metric) in metrics.iter().enumerate() {
-        println!("\tInstrumentation Scope #{}", i);
-        println!("\t\tName         : {}", &metric.scope.name());
+        writeln!(w, "\tInstrumentation Scope #{}", i)?;
+        writeln!(w, "\t\tName         : {}", &metric.scope.name())?;
         if let Some(version) = &metric.scope.version() {
-            println!("\t\tVersion  : {:?}", version);
+            writeln!(w, "\t\tVersion  : {:?}", version)?;
         }
         if let Some(schema_url) = &metric.scope.schema_url() {
-            println!("\t\tSchemaUrl: {:?}", schema_url);
-        }
-        metric
-            .scope
-            .attributes()
-            .enumerate()
-            .for_each(|(index, kv)| {
-                if index == 0 {
-                    println!("\t\tScope Attributes:");
-                }
-                println!("\t\t\t ->  {}: {}", kv.key, kv.value);
-            });
+            writeln!(w, "\t\tSchemaUrl: {:?}", schema_url)?;
+        }
+        for (index, kv) in metric.scope.attributes().enumerate() {
+            if index == 0 {
+                writeln!(w, "\t\tScope Attributes:")?;
+            }
+            writeln!(w, "\t\t\t ->  {}: {}", kv.key, kv.value)?;
+        }
 
-        metric.metrics.iter().enumerate().for_each(|(i, metric)| {
-            println!("Metric #{}", i);
-            println!("\t\tName         : {}", &metric.name);
-            println!("\t\tDescription  : {}", &metric.description);
-            println!("\t\tUnit         : {}", &metric.unit);
+        for (i, metric) in metric.metrics.iter().enumerate() {
+            writeln!(w, "Metric #{}", i)?;
+            writeln!(w, "\t\tName         : {}", &metric.name)?;
+            writeln!(w, "\t\tDescription  : {}", &metric.description)?;
+            writeln!(w, "\t\tUnit         : {}", &metric.unit)?;
 
             let data = metric.data.as_any();
             if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
-                println!("\t\tType         : Histogram");
-                print_histogram(hist);
+                writeln!(w, "\t\tType         : Histogram")?;
+                write_histogram(w, hist)?;
             } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
-                println!("\t\tType         : Histogram");
-                print_histogram(hist);
+                writeln!(w, "\t\tType         : Histogram")?;
+                write_histogram(w, hist)?;
             } else if let Some(_hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
-                println!("\t\tType         : Exponential Histogram");
+                writeln!(w, "\t\tType         : Exponential Histogram")?;
                 // TODO
             } else if let Some(_hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
-                println!("\t\tType         : Exponential Histogram");
+                writeln!(w, "\t\tType         : Exponential Histogram")?;
                 // TODO
             } else if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
-                println!("\t\tType         : Sum");
-                print_sum(sum);
+                writeln!(w, "\t\tType         : Sum")?;
+                write_sum(w, sum)?;
             } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
-                println!("\t\tType         : Sum");
-                print_sum(sum);
+                writeln!(w, "\t\tType         : Sum")?;
+                write_sum(w, sum)?;
             } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
-                println!("\t\tType         : Sum");
-                print_sum(sum);
+                writeln!(w, "\t\tType         : Sum")?;
+                write_sum(w, sum)?;
             } else if let Some(gauge) = data.downcast_ref::<data::Gauge<u64>>() {
-                println!("\t\tType         : Gauge");
-                print_gauge(gauge);
+                writeln!(w, "\t\tType         : Gauge")?;
+                write_gauge(w, gauge)?;
             } else if let Some(gauge) = data.downcast_ref::<data::Gauge<i64>>() {
-                println!("\t\tType         : Gauge");
-                print_gauge(gauge);
+                writeln!(w, "\t\tType         : Gauge")?;
+                write_gauge(w, gauge)?;
             } else if let Some(gauge) = data.downcast_ref::<data::Gauge<f64>>() {
-                println!("\t\tType         : Gauge");
-                print_gauge(gauge);
+                writeln!(w, "\t\tType         : Gauge")?;
+                write_gauge(w, gauge)?;
             } else {
-                println!("Unsupported data type");
+                writeln!(w, "Unsupported data type")?;
             }
-        });
+        }
     }
+    Ok(())
 }
 
-fn print_sum<T: Debug>(sum: &data::Sum<T>) {
-    println!("\t\tSum DataPoints");
-    println!("\t\tMonotonic    : {}", sum.is_monotonic);
+fn write_sum<T: Debug>(w: &mut dyn Write, sum: &data::Sum<T>) -> io::Result<()> {
+    writeln!(w, "\t\tSum DataPoints")?;
+    writeln!(w, "\t\tMonotonic    : {}", sum.is_monotonic)?;
     if sum.temporality == Temporality::Cumulative {
-        println!("\t\tTemporality  : Cumulative");
+        writeln!(w, "\t\tTemporality  : Cumulative")?;
     } else {
-        println!("\t\tTemporality  : Delta");
+        writeln!(w, "\t\tTemporality  : Delta")?;
     }
-    print_data_points(&sum.data_points);
+    write_data_points(w, &sum.data_points)
 }
 
-fn print_gauge<T: Debug>(gauge: &data::Gauge<T>) {
-    println!("\t\tGauge DataPoints");
-    print_data_points(&gauge.data_points);
+fn write_gauge<T: Debug>(w: &mut dyn Write, gauge: &data::Gauge<T>) -> io::Result<()> {
+    writeln!(w, "\t\tGauge DataPoints")?;
+    write_data_points(w, &gauge.data_points)
 }
 
-fn print_histogram<T: Debug>(histogram: &data::Histogram<T>) {
+fn write_histogram<T: Debug>(w: &mut dyn Write, histogram: &data::Histogram<T>) -> io::Result<()> {
     if histogram.temporality == Temporality::Cumulative {
-        println!("\t\tTemporality  : Cumulative");
+        writeln!(w, "\t\tTemporality  : Cumulative")?;
     } else {
-        println!("\t\tTemporality  : Delta");
+        writeln!(w, "\t\tTemporality  : Delta")?;
     }
-    println!("\t\tHistogram DataPoints");
-    print_hist_data_points(&histogram.data_points);
+    writeln!(w, "\t\tHistogram DataPoints")?;
+    write_hist_data_points(w, &histogram.data_points)
 }
 
-fn print_data_points<T: Debug>(data_points: &[data::DataPoint<T>]) {
+fn write_data_points<T: Debug>(
+    w: &mut dyn Write,
+    data_points: &[data::DataPoint<T>],
+) -> io::Result<()> {
     for (i, data_point) in data_points.iter().enumerate() {
-        println!("\t\tDataPoint #{}", i);
+        writeln!(w, "\t\tDataPoint #{}", i)?;
         if let Some(start_time) = data_point.start_time {
-            let datetime: DateTime<Utc> = start_time.into();
-            println!(
-                "\t\t\tStartTime    : {}",
-                datetime.format("%Y-%m-%d %H:%M:%S%.6f")
-            );
+            writeln!(w, "\t\t\tStartTime    : {}", format_time(start_time))?;
         }
         if let Some(end_time) = data_point.time {
-            let datetime: DateTime<Utc> = end_time.into();
-            println!(
-                "\t\t\tEndTime      : {}",
-                datetime.format("%Y-%m-%d %H:%M:%S%.6f")
-            );
-        }
-        println!("\t\t\tValue        : {:#?}", data_point.value);
-        println!("\t\t\tAttributes   :");
+            writeln!(w, "\t\t\tEndTime      : {}", format_time(end_time))?;
+        }
+        writeln!(w, "\t\t\tValue        : {:#?}", data_point.value)?;
+        writeln!(w, "\t\t\tAttributes   :")?;
         for kv in data_point.attributes.iter() {
-            println!("\t\t\t\t ->  {}: {}", kv.key, kv.value.as_str());
+            writeln!(w, "\t\t\t\t ->  {}: {}", kv.key, kv.value.as_str())?;
         }
     }
+    Ok(())
 }
 
-fn print_hist_data_points<T: Debug>(data_points: &[data::HistogramDataPoint<T>]) {
+fn write_hist_data_points<T: Debug>(
+    w: &mut dyn Write,
+    data_points: &[data::HistogramDataPoint<T>],
+) -> io::Result<()> {
     for (i, data_point) in data_points.iter().enumerate() {
-        println!("\t\tDataPoint #{}", i);
-        let datetime: DateTime<Utc> = data_point.start_time.into();
-        println!(
+        writeln!(w, "\t\tDataPoint #{}", i)?;
+        writeln!(
+            w,
             "\t\t\tStartTime    : {}",
-            datetime.format("%Y-%m-%d %H:%M:%S%.6f")
-        );
-        let datetime: DateTime<Utc> = data_point.time.into();
-        println!(
-            "\t\t\tEndTime      : {}",
-            datetime.format("%Y-%m-%d %H:%M:%S%.6f")
-        );
-        println!("\t\t\tCount        : {}", data_point.count);
-        println!("\t\t\tSum          : {:?}", data_point.sum);
+            format_time(data_point.start_time)
+        )?;
+        writeln!(w, "\t\t\tEndTime      : {}", format_time(data_point.time))?;
+        writeln!(w, "\t\t\tCount        : {}", data_point.count)?;
+        writeln!(w, "\t\t\tSum          : {:?}", data_point.sum)?;
         if let Some(min) = &data_point.min {
-            println!("\t\t\tMin          : {:?}", min);
+            writeln!(w, "\t\t\tMin          : {:?}", min)?;
         }
 
         if let Some(max) = &data_point.max {
-            println!("\t\t\tMax          : {:?}", max);
+            writeln!(w, "\t\t\tMax          : {:?}", max)?;
         }
 
-        println!("\t\t\tAttributes   :");
+        writeln!(w, "\t\t\tAttributes   :")?;
         for kv in data_point.attributes.iter() {
-            println!("\t\t\t\t ->  {}: {}", kv.key, kv.value.as_str());
+            writeln!(w, "\t\t\t\t ->  {}: {}", kv.key, kv.value.as_str())?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes one line per data point, e.g.
+/// `2024-11-12T10:00:00.000000Z METRIC http.requests sum value=42 unit=1 key=value`
+fn write_compact_metrics(w: &mut dyn Write, metrics: &[ScopeMetrics]) -> io::Result<()> {
+    for scope_metrics in metrics {
+        for metric in &scope_metrics.metrics {
+            let data = metric.data.as_any();
+            if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
+                write_compact_histogram(w, metric, hist)?;
+            } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
+                write_compact_histogram(w, metric, hist)?;
+            } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
+                write_compact_exponential_histogram(w, metric, hist)?;
+            } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
+                write_compact_exponential_histogram(w, metric, hist)?;
+            } else if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
+                write_compact_data_points(w, metric, "sum", &sum.data_points)?;
+            } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
+                write_compact_data_points(w, metric, "sum", &sum.data_points)?;
+            } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
+                write_compact_data_points(w, metric, "sum", &sum.data_points)?;
+            } else if let Some(gauge) = data.downcast_ref::<data::Gauge<u64>>() {
+                write_compact_data_points(w, metric, "gauge", &gauge.data_points)?;
+            } else if let Some(gauge) = data.downcast_ref::<data::Gauge<i64>>() {
+                write_compact_data_points(w, metric, "gauge", &gauge.data_points)?;
+            } else if let Some(gauge) = data.downcast_ref::<data::Gauge<f64>>() {
+                write_compact_data_points(w, metric, "gauge", &gauge.data_points)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_compact_data_points<T: fmt::Display>(
+    w: &mut dyn Write,
+    metric: &data::Metric,
+    kind: &str,
+    data_points: &[data::DataPoint<T>],
+) -> io::Result<()> {
+    for data_point in data_points {
+        write_compact_prefix(w, metric, kind, data_point.time)?;
+        write!(w, " value={}", data_point.value)?;
+        write_compact_suffix(w, metric, &data_point.attributes)?;
+    }
+    Ok(())
+}
+
+fn write_compact_histogram<T: Debug>(
+    w: &mut dyn Write,
+    metric: &data::Metric,
+    histogram: &data::Histogram<T>,
+) -> io::Result<()> {
+    for data_point in &histogram.data_points {
+        write_compact_prefix(w, metric, "histogram", Some(data_point.time))?;
+        write!(w, " count={} sum={:?}", data_point.count, data_point.sum)?;
+        if let Some(min) = &data_point.min {
+            write!(w, " min={:?}", min)?;
         }
+        if let Some(max) = &data_point.max {
+            write!(w, " max={:?}", max)?;
+        }
+        write_compact_suffix(w, metric, &data_point.attributes)?;
     }
+    Ok(())
+}
+
+fn write_compact_exponential_histogram<T: Debug>(
+    w: &mut dyn Write,
+    metric: &data::Metric,
+    histogram: &data::ExponentialHistogram<T>,
+) -> io::Result<()> {
+    for data_point in &histogram.data_points {
+        write_compact_prefix(w, metric, "exponential_histogram", Some(data_point.time))?;
+        write!(
+            w,
+            " count={} sum={:?} scale={}",
+            data_point.count, data_point.sum, data_point.scale
+        )?;
+        if let Some(min) = &data_point.min {
+            write!(w, " min={:?}", min)?;
+        }
+        if let Some(max) = &data_point.max {
+            write!(w, " max={:?}", max)?;
+        }
+        write_compact_suffix(w, metric, &data_point.attributes)?;
+    }
+    Ok(())
+}
+
+fn write_compact_prefix(
+    w: &mut dyn Write,
+    metric: &data::Metric,
+    kind: &str,
+    time: Option<SystemTime>,
+) -> io::Result<()> {
+    match time {
+        Some(time) => write!(w, "{} ", format_time_rfc3339(time))?,
+        None => write!(w, "- ")?,
+    }
+    write!(w, "METRIC {} {}", metric.name, kind)
+}
+
+fn write_compact_suffix(
+    w: &mut dyn Write,
+    metric: &data::Metric,
+    attributes: &[opentelemetry::KeyValue],
+) -> io::Result<()> {
+    if !metric.unit.is_empty() {
+        write!(w, " unit={}", metric.unit)?;
+    }
+    for kv in attributes {
+        write!(w, " {}={}", kv.key, Value::from(kv.value.clone()))?;
+    }
+    writeln!(w)
 }
 
 /// Configuration for the stdout metrics exporter
 #[derive(Default)]
 pub struct MetricExporterBuilder {
     temporality: Option<Temporality>,
+    writer: Option<Box<dyn Write + Send>>,
+    format: Format,
 }
 
 impl MetricExporterBuilder {
@@ -228,11 +353,28 @@ impl MetricExporterBuilder {
         self
     }
 
+    /// Write the metrics to `writer` instead of stdout, e.g. to stderr, a file
+    /// or an in-memory buffer.
+    pub fn with_writer<W: Write + Send + 'static>(mut self, writer: W) -> Self {
+        self.writer = Some(Box::new(writer));
+        self
+    }
+
+    /// Set the output [Format] of the exporter. Defaults to [Format::Pretty].
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Create a metrics exporter with the current configuration
     pub fn build(self) -> MetricExporter {
         MetricExporter {
             temporality: self.temporality.unwrap_or_default(),
             is_shutdown: atomic::AtomicBool::new(false),
+            output: Output::new(
+                self.writer.unwrap_or_else(|| Box::new(io::stdout())),
+                self.format,
+            ),
         }
     }
 }
@@ -242,3 +384,70 @@ impl fmt::Debug for MetricExporterBuilder {
         f.write_str("MetricExporterBuilder")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SharedBuffer;
+    use opentelemetry::{InstrumentationScope, KeyValue};
+    use opentelemetry_sdk::Resource;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn metrics() -> data::ResourceMetrics {
+        data::ResourceMetrics {
+            resource: Resource::new(vec![KeyValue::new("service.name", "test")]),
+            scope_metrics: vec![data::ScopeMetrics {
+                scope: InstrumentationScope::builder("my_app").build(),
+                metrics: vec![data::Metric {
+                    name: "requests".into(),
+                    description: "".into(),
+                    unit: "1".into(),
+                    data: Box::new(data::Sum {
+                        data_points: vec![data::DataPoint {
+                            attributes: vec![KeyValue::new("route", "/")],
+                            start_time: Some(UNIX_EPOCH),
+                            time: Some(UNIX_EPOCH + Duration::from_secs(1)),
+                            value: 42u64,
+                            exemplars: vec![],
+                        }],
+                        temporality: Temporality::Cumulative,
+                        is_monotonic: true,
+                    }),
+                }],
+            }],
+        }
+    }
+
+    async fn export(format: Format) -> String {
+        let buffer = SharedBuffer::default();
+        let exporter = MetricExporter::builder()
+            .with_writer(buffer.clone())
+            .with_format(format)
+            .build();
+        exporter.export(&mut metrics()).await.unwrap();
+        buffer.contents()
+    }
+
+    #[tokio::test]
+    async fn writes_metrics_in_each_format() {
+        let pretty = export(Format::Pretty).await;
+        assert!(pretty.starts_with("Metrics\nResource\n"));
+        assert!(pretty.contains("\t\tName         : requests\n"));
+
+        assert_eq!(
+            export(Format::Compact).await,
+            "1970-01-01T00:00:01.000000Z METRIC requests sum value=42 unit=1 route=/\n"
+        );
+
+        assert_eq!(
+            export(Format::OtlpJson).await,
+            concat!(
+                r#"{"resourceMetrics":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"test"}}]},"#,
+                r#""scopeMetrics":[{"scope":{"name":"my_app"},"metrics":[{"name":"requests","unit":"1","sum":{"dataPoints":[{"#,
+                r#""attributes":[{"key":"route","value":{"stringValue":"/"}}],"startTimeUnixNano":"0","timeUnixNano":"1000000000","#,
+                r#""asInt":"42"}],"aggregationTemporality":2,"isMonotonic":true}}]}]}]}"#,
+                "\n"
+            )
+        );
+    }
+}
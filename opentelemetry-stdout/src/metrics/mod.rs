@@ -1,3 +1,4 @@
 mod exporter;
+mod transform;
 
 pub use exporter::*;
@@ -0,0 +1,386 @@
+use std::{borrow::Cow, time::SystemTime};
+
+use opentelemetry_sdk::metrics::{data, Temporality};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::common::{as_opt_unix_nano, as_string, as_unix_nano, KeyValue, Resource, Scope};
+
+/// Transformed metrics data that can be serialized, as OTLP JSON.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MetricsData {
+    resource_metrics: Vec<ResourceMetrics>,
+}
+
+impl From<&data::ResourceMetrics> for MetricsData {
+    fn from(value: &data::ResourceMetrics) -> Self {
+        MetricsData {
+            resource_metrics: vec![ResourceMetrics {
+                resource: (&value.resource).into(),
+                scope_metrics: value.scope_metrics.iter().map(Into::into).collect(),
+                schema_url: value.resource.schema_url().map(|s| s.to_owned().into()),
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceMetrics {
+    resource: Resource,
+    scope_metrics: Vec<ScopeMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_url: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScopeMetrics {
+    scope: Scope,
+    metrics: Vec<Metric>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_url: Option<Cow<'static, str>>,
+}
+
+impl From<&data::ScopeMetrics> for ScopeMetrics {
+    fn from(value: &data::ScopeMetrics) -> Self {
+        ScopeMetrics {
+            scope: (&value.scope).into(),
+            metrics: value.metrics.iter().map(Into::into).collect(),
+            schema_url: value.scope.schema_url().map(|s| s.to_owned().into()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Metric {
+    name: Cow<'static, str>,
+    description: Cow<'static, str>,
+    unit: Cow<'static, str>,
+    data: Option<MetricData>,
+}
+
+impl Serialize for Metric {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &self.name)?;
+        if !self.description.is_empty() {
+            map.serialize_entry("description", &self.description)?;
+        }
+        if !self.unit.is_empty() {
+            map.serialize_entry("unit", &self.unit)?;
+        }
+        match &self.data {
+            Some(MetricData::Gauge(gauge)) => map.serialize_entry("gauge", gauge)?,
+            Some(MetricData::Sum(sum)) => map.serialize_entry("sum", sum)?,
+            Some(MetricData::Histogram(hist)) => map.serialize_entry("histogram", hist)?,
+            Some(MetricData::ExponentialHistogram(hist)) => {
+                map.serialize_entry("exponentialHistogram", hist)?
+            }
+            None => {}
+        }
+        map.end()
+    }
+}
+
+impl From<&data::Metric> for Metric {
+    fn from(value: &data::Metric) -> Self {
+        Metric {
+            name: value.name.clone(),
+            description: value.description.clone(),
+            unit: value.unit.clone(),
+            data: MetricData::from_aggregation(value.data.as_ref()),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum MetricData {
+    Gauge(Gauge),
+    Sum(Sum),
+    Histogram(Histogram),
+    ExponentialHistogram(ExponentialHistogram),
+}
+
+impl MetricData {
+    fn from_aggregation(aggregation: &dyn data::Aggregation) -> Option<Self> {
+        let data = aggregation.as_any();
+        if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
+            Some(MetricData::Histogram(hist.into()))
+        } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
+            Some(MetricData::Histogram(hist.into()))
+        } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
+            Some(MetricData::ExponentialHistogram(hist.into()))
+        } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
+            Some(MetricData::ExponentialHistogram(hist.into()))
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
+            Some(MetricData::Sum(sum.into()))
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
+            Some(MetricData::Sum(sum.into()))
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
+            Some(MetricData::Sum(sum.into()))
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<u64>>() {
+            Some(MetricData::Gauge(gauge.into()))
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<i64>>() {
+            Some(MetricData::Gauge(gauge.into()))
+        } else {
+            data.downcast_ref::<data::Gauge<f64>>()
+                .map(|gauge| MetricData::Gauge(gauge.into()))
+        }
+    }
+}
+
+/// The numeric types recorded by the SDK instruments.
+trait Numeric: Copy {
+    fn number(self) -> Number;
+    fn as_f64(self) -> f64;
+}
+
+impl Numeric for u64 {
+    fn number(self) -> Number {
+        Number::Int(self as i64)
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Numeric for i64 {
+    fn number(self) -> Number {
+        Number::Int(self)
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Numeric for f64 {
+    fn number(self) -> Number {
+        Number::Double(self)
+    }
+
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+/// The value of a number data point, serialized as its `asInt` or `asDouble`
+/// field.
+#[derive(Debug)]
+enum Number {
+    Int(i64),
+    Double(f64),
+}
+
+/// The OTLP `AggregationTemporality` enum value.
+fn temporality(temporality: Temporality) -> u32 {
+    match temporality {
+        Temporality::Delta => 1,
+        _ => 2,
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Gauge {
+    data_points: Vec<NumberDataPoint>,
+}
+
+impl<T: Numeric> From<&data::Gauge<T>> for Gauge {
+    fn from(value: &data::Gauge<T>) -> Self {
+        Gauge {
+            data_points: value.data_points.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Sum {
+    data_points: Vec<NumberDataPoint>,
+    aggregation_temporality: u32,
+    is_monotonic: bool,
+}
+
+impl<T: Numeric> From<&data::Sum<T>> for Sum {
+    fn from(value: &data::Sum<T>) -> Self {
+        Sum {
+            data_points: value.data_points.iter().map(Into::into).collect(),
+            aggregation_temporality: temporality(value.temporality),
+            is_monotonic: value.is_monotonic,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NumberDataPoint {
+    attributes: Vec<KeyValue>,
+    start_time_unix_nano: Option<SystemTime>,
+    time_unix_nano: Option<SystemTime>,
+    value: Number,
+}
+
+impl Serialize for NumberDataPoint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// Serializes an optional time with [`as_opt_unix_nano`].
+        struct Time<'a>(&'a Option<SystemTime>);
+
+        impl Serialize for Time<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                as_opt_unix_nano(self.0, serializer)
+            }
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        if !self.attributes.is_empty() {
+            map.serialize_entry("attributes", &self.attributes)?;
+        }
+        map.serialize_entry("startTimeUnixNano", &Time(&self.start_time_unix_nano))?;
+        map.serialize_entry("timeUnixNano", &Time(&self.time_unix_nano))?;
+        match self.value {
+            Number::Int(i) => map.serialize_entry("asInt", &i.to_string())?,
+            Number::Double(d) => map.serialize_entry("asDouble", &d)?,
+        }
+        map.end()
+    }
+}
+
+impl<T: Numeric> From<&data::DataPoint<T>> for NumberDataPoint {
+    fn from(value: &data::DataPoint<T>) -> Self {
+        NumberDataPoint {
+            attributes: value.attributes.iter().map(Into::into).collect(),
+            start_time_unix_nano: value.start_time,
+            time_unix_nano: value.time,
+            value: value.value.number(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Histogram {
+    data_points: Vec<HistogramDataPoint>,
+    aggregation_temporality: u32,
+}
+
+impl<T: Numeric> From<&data::Histogram<T>> for Histogram {
+    fn from(value: &data::Histogram<T>) -> Self {
+        Histogram {
+            data_points: value.data_points.iter().map(Into::into).collect(),
+            aggregation_temporality: temporality(value.temporality),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistogramDataPoint {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<KeyValue>,
+    #[serde(serialize_with = "as_unix_nano")]
+    start_time_unix_nano: SystemTime,
+    #[serde(serialize_with = "as_unix_nano")]
+    time_unix_nano: SystemTime,
+    #[serde(serialize_with = "as_string")]
+    count: u64,
+    sum: f64,
+    bucket_counts: Vec<String>,
+    explicit_bounds: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+}
+
+impl<T: Numeric> From<&data::HistogramDataPoint<T>> for HistogramDataPoint {
+    fn from(value: &data::HistogramDataPoint<T>) -> Self {
+        HistogramDataPoint {
+            attributes: value.attributes.iter().map(Into::into).collect(),
+            start_time_unix_nano: value.start_time,
+            time_unix_nano: value.time,
+            count: value.count,
+            sum: value.sum.as_f64(),
+            bucket_counts: value.bucket_counts.iter().map(u64::to_string).collect(),
+            explicit_bounds: value.bounds.clone(),
+            min: value.min.map(Numeric::as_f64),
+            max: value.max.map(Numeric::as_f64),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExponentialHistogram {
+    data_points: Vec<ExponentialHistogramDataPoint>,
+    aggregation_temporality: u32,
+}
+
+impl<T: Numeric> From<&data::ExponentialHistogram<T>> for ExponentialHistogram {
+    fn from(value: &data::ExponentialHistogram<T>) -> Self {
+        ExponentialHistogram {
+            data_points: value.data_points.iter().map(Into::into).collect(),
+            aggregation_temporality: temporality(value.temporality),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExponentialHistogramDataPoint {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<KeyValue>,
+    #[serde(serialize_with = "as_unix_nano")]
+    start_time_unix_nano: SystemTime,
+    #[serde(serialize_with = "as_unix_nano")]
+    time_unix_nano: SystemTime,
+    #[serde(serialize_with = "as_string")]
+    count: usize,
+    sum: f64,
+    scale: i8,
+    #[serde(serialize_with = "as_string")]
+    zero_count: u64,
+    positive: ExponentialBuckets,
+    negative: ExponentialBuckets,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+    zero_threshold: f64,
+}
+
+impl<T: Numeric> From<&data::ExponentialHistogramDataPoint<T>> for ExponentialHistogramDataPoint {
+    fn from(value: &data::ExponentialHistogramDataPoint<T>) -> Self {
+        ExponentialHistogramDataPoint {
+            attributes: value.attributes.iter().map(Into::into).collect(),
+            start_time_unix_nano: value.start_time,
+            time_unix_nano: value.time,
+            count: value.count,
+            sum: value.sum.as_f64(),
+            scale: value.scale,
+            zero_count: value.zero_count,
+            positive: (&value.positive_bucket).into(),
+            negative: (&value.negative_bucket).into(),
+            min: value.min.map(Numeric::as_f64),
+            max: value.max.map(Numeric::as_f64),
+            zero_threshold: value.zero_threshold,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExponentialBuckets {
+    offset: i32,
+    bucket_counts: Vec<String>,
+}
+
+impl From<&data::ExponentialBucket> for ExponentialBuckets {
+    fn from(value: &data::ExponentialBucket) -> Self {
+        ExponentialBuckets {
+            offset: value.offset,
+            bucket_counts: value.counts.iter().map(u64::to_string).collect(),
+        }
+    }
+}
@@ -1,17 +1,28 @@
-use chrono::{DateTime, Utc};
 use core::fmt;
 use futures_util::future::BoxFuture;
 use opentelemetry::trace::TraceError;
 use opentelemetry_sdk::export::{self, trace::ExportResult};
+use std::io::{self, Write};
 use std::sync::atomic;
 
 use opentelemetry_sdk::resource::Resource;
 
+use crate::common::{format_time, format_time_rfc3339, Format, Output, Value};
+use crate::trace::transform::SpanData;
+
 /// An OpenTelemetry exporter that writes Spans to stdout on export.
 pub struct SpanExporter {
     resource: Resource,
     is_shutdown: atomic::AtomicBool,
     resource_emitted: bool,
+    output: Output,
+}
+
+impl SpanExporter {
+    /// Create a builder to configure this exporter.
+    pub fn builder() -> SpanExporterBuilder {
+        SpanExporterBuilder::default()
+    }
 }
 
 impl fmt::Debug for SpanExporter {
@@ -22,129 +33,298 @@ impl fmt::Debug for SpanExporter {
 
 impl Default for SpanExporter {
     fn default() -> Self {
-        SpanExporter {
-            resource: Resource::default(),
-            is_shutdown: atomic::AtomicBool::new(false),
-            resource_emitted: false,
-        }
+        SpanExporterBuilder::default().build()
     }
 }
 
 impl opentelemetry_sdk::export::trace::SpanExporter for SpanExporter {
-    /// Write Spans to stdout
+    /// Write Spans to the configured writer
     fn export(&mut self, batch: Vec<export::trace::SpanData>) -> BoxFuture<'static, ExportResult> {
         if self.is_shutdown.load(atomic::Ordering::SeqCst) {
-            Box::pin(std::future::ready(Err(TraceError::from(
+            return Box::pin(std::future::ready(Err(TraceError::from(
                 "exporter is shut down",
-            ))))
-        } else {
-            println!("Spans");
-            if self.resource_emitted {
-                print_spans(batch);
-            } else {
-                self.resource_emitted = true;
-                println!("Resource");
-                if let Some(schema_url) = self.resource.schema_url() {
-                    println!("\tResource SchemaUrl: {:?}", schema_url);
-                }
-
-                self.resource.iter().for_each(|(k, v)| {
-                    println!("\t ->  {}={:?}", k, v);
-                });
+            ))));
+        }
 
-                print_spans(batch);
+        let result = match self.output.format {
+            Format::Compact => self.output.write(|w| write_compact_spans(w, &batch)),
+            Format::OtlpJson => self
+                .output
+                .write_json_line(&SpanData::new(&batch, &self.resource)),
+            Format::Pretty => {
+                let emit_resource = !self.resource_emitted;
+                self.resource_emitted = true;
+                let resource = &self.resource;
+                self.output.write(|w| {
+                    writeln!(w, "Spans")?;
+                    if emit_resource {
+                        writeln!(w, "Resource")?;
+                        if let Some(schema_url) = resource.schema_url() {
+                            writeln!(w, "\tResource SchemaUrl: {:?}", schema_url)?;
+                        }
+                        for (k, v) in resource.iter() {
+                            writeln!(w, "\t ->  {}={:?}", k, v)?;
+                        }
+                    }
+                    write_spans(w, &batch)
+                })
             }
+        };
 
-            Box::pin(std::future::ready(Ok(())))
-        }
+        Box::pin(std::future::ready(
+            result.map_err(|err| TraceError::Other(Box::new(err))),
+        ))
     }
 
     fn shutdown(&mut self) {
         self.is_shutdown.store(true, atomic::Ordering::SeqCst);
     }
 
+    fn force_flush(&mut self) -> BoxFuture<'static, ExportResult> {
+        let result = self
+            .output
+            .flush()
+            .map_err(|err| TraceError::Other(Box::new(err)));
+        Box::pin(std::future::ready(result))
+    }
+
     fn set_resource(&mut self, res: &opentelemetry_sdk::Resource) {
         self.resource = res.clone();
     }
 }
 
-fn print_spans(batch: Vec<export::trace::SpanData>) {
-    for (i, span) in batch.into_iter().enumerate() {
-        println!("Span #{}", i);
-        println!("\tInstrumentation Scope");
-        println!(
+fn write_spans(w: &mut dyn Write, batch: &[export::trace::SpanData]) -> io::Result<()> {
+    for (i, span) in batch.iter().enumerate() {
+        writeln!(w, "Span #{}", i)?;
+        writeln!(w, "\tInstrumentation Scope")?;
+        writeln!(
+            w,
             "\t\tName         : {:?}",
             &span.instrumentation_scope.name()
-        );
+        )?;
         if let Some(version) = &span.instrumentation_scope.version() {
-            println!("\t\tVersion  : {:?}", version);
+            writeln!(w, "\t\tVersion  : {:?}", version)?;
         }
         if let Some(schema_url) = &span.instrumentation_scope.schema_url() {
-            println!("\t\tSchemaUrl: {:?}", schema_url);
+            writeln!(w, "\t\tSchemaUrl: {:?}", schema_url)?;
         }
-        span.instrumentation_scope
-            .attributes()
-            .enumerate()
-            .for_each(|(index, kv)| {
-                if index == 0 {
-                    println!("\t\tScope Attributes:");
-                }
-                println!("\t\t\t ->  {}: {}", kv.key, kv.value);
-            });
-
-        println!();
-        println!("\tName        : {}", &span.name);
-        println!("\tTraceId     : {}", &span.span_context.trace_id());
-        println!("\tSpanId      : {}", &span.span_context.span_id());
-        println!("\tParentSpanId: {}", &span.parent_span_id);
-        println!("\tKind        : {:?}", &span.span_kind);
-
-        let datetime: DateTime<Utc> = span.start_time.into();
-        println!("\tStart time: {}", datetime.format("%Y-%m-%d %H:%M:%S%.6f"));
-        let datetime: DateTime<Utc> = span.end_time.into();
-        println!("\tEnd time: {}", datetime.format("%Y-%m-%d %H:%M:%S%.6f"));
-        println!("\tStatus: {:?}", &span.status);
-
-        let mut print_header = true;
-        for kv in span.attributes.iter() {
-            if print_header {
-                println!("\tAttributes:");
-                print_header = false;
+        for (index, kv) in span.instrumentation_scope.attributes().enumerate() {
+            if index == 0 {
+                writeln!(w, "\t\tScope Attributes:")?;
             }
-            println!("\t\t ->  {}: {:?}", kv.key, kv.value);
+            writeln!(w, "\t\t\t ->  {}: {}", kv.key, kv.value)?;
         }
 
-        span.events.iter().enumerate().for_each(|(index, event)| {
+        writeln!(w)?;
+        writeln!(w, "\tName        : {}", &span.name)?;
+        writeln!(w, "\tTraceId     : {}", &span.span_context.trace_id())?;
+        writeln!(w, "\tSpanId      : {}", &span.span_context.span_id())?;
+        writeln!(w, "\tParentSpanId: {}", &span.parent_span_id)?;
+        writeln!(w, "\tKind        : {:?}", &span.span_kind)?;
+        writeln!(w, "\tStart time: {}", format_time(span.start_time))?;
+        writeln!(w, "\tEnd time: {}", format_time(span.end_time))?;
+        writeln!(w, "\tStatus: {:?}", &span.status)?;
+
+        for (index, kv) in span.attributes.iter().enumerate() {
             if index == 0 {
-                println!("\tEvents:");
+                writeln!(w, "\tAttributes:")?;
             }
-            println!("\tEvent #{}", index);
-            println!("\tName      : {}", event.name);
-            let datetime: DateTime<Utc> = event.timestamp.into();
-            println!("\tTimestamp : {}", datetime.format("%Y-%m-%d %H:%M:%S%.6f"));
+            writeln!(w, "\t\t ->  {}: {:?}", kv.key, kv.value)?;
+        }
 
-            event.attributes.iter().enumerate().for_each(|(index, kv)| {
+        for (index, event) in span.events.iter().enumerate() {
+            if index == 0 {
+                writeln!(w, "\tEvents:")?;
+            }
+            writeln!(w, "\tEvent #{}", index)?;
+            writeln!(w, "\tName      : {}", event.name)?;
+            writeln!(w, "\tTimestamp : {}", format_time(event.timestamp))?;
+
+            for (index, kv) in event.attributes.iter().enumerate() {
                 if index == 0 {
-                    println!("\tAttributes:");
+                    writeln!(w, "\tAttributes:")?;
                 }
-                println!("\t\t ->  {}: {:?}", kv.key, kv.value);
-            });
-        });
+                writeln!(w, "\t\t ->  {}: {:?}", kv.key, kv.value)?;
+            }
+        }
 
-        span.links.iter().enumerate().for_each(|(index, link)| {
+        for (index, link) in span.links.iter().enumerate() {
             if index == 0 {
-                println!("\tLinks:");
+                writeln!(w, "\tLinks:")?;
             }
-            println!("\tLink #{}", index);
-            println!("\tTraceId: {}", link.span_context.trace_id());
-            println!("\tSpanId : {}", link.span_context.span_id());
+            writeln!(w, "\tLink #{}", index)?;
+            writeln!(w, "\tTraceId: {}", link.span_context.trace_id())?;
+            writeln!(w, "\tSpanId : {}", link.span_context.span_id())?;
 
-            link.attributes.iter().enumerate().for_each(|(index, kv)| {
+            for (index, kv) in link.attributes.iter().enumerate() {
                 if index == 0 {
-                    println!("\tAttributes:");
+                    writeln!(w, "\tAttributes:")?;
                 }
-                println!("\t\t ->  {}: {:?}", kv.key, kv.value);
-            });
-        });
+                writeln!(w, "\t\t ->  {}: {:?}", kv.key, kv.value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes one line per span, e.g.
+/// `2024-11-12T10:00:00.000000Z SPAN checkout trace_id=… span_id=… kind=Server duration=1.2ms status=Ok key=value`
+fn write_compact_spans(w: &mut dyn Write, batch: &[export::trace::SpanData]) -> io::Result<()> {
+    for span in batch {
+        write!(
+            w,
+            "{} SPAN {} trace_id={} span_id={}",
+            format_time_rfc3339(span.start_time),
+            span.name,
+            span.span_context.trace_id(),
+            span.span_context.span_id(),
+        )?;
+        if span.parent_span_id != opentelemetry::trace::SpanId::INVALID {
+            write!(w, " parent_span_id={}", span.parent_span_id)?;
+        }
+        let duration = span
+            .end_time
+            .duration_since(span.start_time)
+            .unwrap_or_default();
+        write!(
+            w,
+            " kind={:?} duration={:?} scope={}",
+            span.span_kind,
+            duration,
+            span.instrumentation_scope.name()
+        )?;
+        match &span.status {
+            opentelemetry::trace::Status::Unset => {}
+            opentelemetry::trace::Status::Ok => write!(w, " status=Ok")?,
+            opentelemetry::trace::Status::Error { description } => {
+                write!(w, " status=Error({:?})", description)?
+            }
+        }
+        for kv in &span.attributes {
+            write!(w, " {}={}", kv.key, Value::from(kv.value.clone()))?;
+        }
+        if !span.events.is_empty() {
+            write!(w, " events={}", span.events.len())?;
+        }
+        if !span.links.is_empty() {
+            write!(w, " links={}", span.links.len())?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Configuration for the stdout span exporter.
+#[derive(Default)]
+pub struct SpanExporterBuilder {
+    writer: Option<Box<dyn Write + Send>>,
+    format: Format,
+}
+
+impl SpanExporterBuilder {
+    /// Write the spans to `writer` instead of stdout, e.g. to stderr, a file
+    /// or an in-memory buffer.
+    pub fn with_writer<W: Write + Send + 'static>(mut self, writer: W) -> Self {
+        self.writer = Some(Box::new(writer));
+        self
+    }
+
+    /// Set the output [Format] of the exporter. Defaults to [Format::Pretty].
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Create a span exporter with the current configuration
+    pub fn build(self) -> SpanExporter {
+        SpanExporter {
+            resource: Resource::default(),
+            is_shutdown: atomic::AtomicBool::new(false),
+            resource_emitted: false,
+            output: Output::new(
+                self.writer.unwrap_or_else(|| Box::new(io::stdout())),
+                self.format,
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for SpanExporterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpanExporterBuilder")
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SharedBuffer;
+    use opentelemetry::trace::{
+        SpanContext, SpanId, SpanKind, Status, TraceFlags, TraceId, TraceState,
+    };
+    use opentelemetry::{InstrumentationScope, KeyValue};
+    use opentelemetry_sdk::export::trace::SpanExporter as _;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn span() -> export::trace::SpanData {
+        export::trace::SpanData {
+            span_context: SpanContext::new(
+                TraceId::from(1),
+                SpanId::from(2),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            ),
+            parent_span_id: SpanId::INVALID,
+            span_kind: SpanKind::Server,
+            name: "checkout".into(),
+            start_time: UNIX_EPOCH + Duration::from_secs(1),
+            end_time: UNIX_EPOCH + Duration::from_millis(1500),
+            attributes: vec![KeyValue::new("http.status_code", 200)],
+            dropped_attributes_count: 0,
+            events: Default::default(),
+            links: Default::default(),
+            status: Status::Ok,
+            instrumentation_scope: InstrumentationScope::builder("my_app").build(),
+        }
+    }
+
+    async fn export(format: Format) -> String {
+        let buffer = SharedBuffer::default();
+        let mut exporter = SpanExporter::builder()
+            .with_writer(buffer.clone())
+            .with_format(format)
+            .build();
+        exporter.set_resource(&Resource::new(vec![KeyValue::new("service.name", "test")]));
+        exporter.export(vec![span()]).await.unwrap();
+        buffer.contents()
+    }
+
+    #[tokio::test]
+    async fn writes_spans_in_each_format() {
+        let pretty = export(Format::Pretty).await;
+        assert!(pretty.starts_with("Spans\nResource\n"));
+        assert!(pretty.contains("\tName        : checkout\n"));
+
+        assert_eq!(
+            export(Format::Compact).await,
+            "1970-01-01T00:00:01.000000Z SPAN checkout \
+             trace_id=00000000000000000000000000000001 span_id=0000000000000002 \
+             kind=Server duration=500ms scope=my_app status=Ok http.status_code=200\n"
+        );
+
+        assert_eq!(
+            export(Format::OtlpJson).await,
+            concat!(
+                r#"{"resourceSpans":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"test"}}]},"#,
+                r#""scopeSpans":[{"scope":{"name":"my_app"},"spans":[{"traceId":"00000000000000000000000000000001","#,
+                r#""spanId":"0000000000000002","flags":1,"name":"checkout","kind":2,"startTimeUnixNano":"1000000000","#,
+                r#""endTimeUnixNano":"1500000000","attributes":[{"key":"http.status_code","value":{"intValue":"200"}}],"#,
+                r#""status":{"code":1}}]}]}]}"#,
+                "\n"
+            )
+        );
     }
 }
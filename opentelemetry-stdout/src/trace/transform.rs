@@ -0,0 +1,210 @@
+use std::{borrow::Cow, time::SystemTime};
+
+use opentelemetry::trace::{SpanId, SpanKind, Status};
+use opentelemetry_sdk::export::trace::SpanData as SdkSpanData;
+use serde::Serialize;
+
+use crate::common::{as_unix_nano, KeyValue, Resource, Scope};
+
+/// Transformed trace data that can be serialized, as OTLP JSON.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SpanData {
+    resource_spans: Vec<ResourceSpans>,
+}
+
+impl SpanData {
+    pub(crate) fn new(spans: &[SdkSpanData], resource: &opentelemetry_sdk::Resource) -> Self {
+        let mut scope_spans: Vec<(&opentelemetry::InstrumentationScope, Vec<Span>)> = Vec::new();
+        for span in spans {
+            let scope = &span.instrumentation_scope;
+            match scope_spans.iter_mut().find(|(s, _)| *s == scope) {
+                Some((_, spans)) => spans.push(span.into()),
+                None => scope_spans.push((scope, vec![span.into()])),
+            }
+        }
+
+        SpanData {
+            resource_spans: vec![ResourceSpans {
+                resource: resource.into(),
+                scope_spans: scope_spans
+                    .into_iter()
+                    .map(|(scope, spans)| ScopeSpans {
+                        scope: scope.into(),
+                        schema_url: scope.schema_url().map(|s| s.to_owned().into()),
+                        spans,
+                    })
+                    .collect(),
+                schema_url: resource.schema_url().map(|s| s.to_owned().into()),
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceSpans {
+    resource: Resource,
+    scope_spans: Vec<ScopeSpans>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_url: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScopeSpans {
+    scope: Scope,
+    spans: Vec<Span>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_url: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Span {
+    trace_id: String,
+    span_id: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    trace_state: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    parent_span_id: String,
+    #[serde(skip_serializing_if = "is_zero")]
+    flags: u32,
+    name: Cow<'static, str>,
+    kind: u32,
+    #[serde(serialize_with = "as_unix_nano")]
+    start_time_unix_nano: SystemTime,
+    #[serde(serialize_with = "as_unix_nano")]
+    end_time_unix_nano: SystemTime,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped_attributes_count: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    events: Vec<Event>,
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped_events_count: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    links: Vec<Link>,
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped_links_count: u32,
+    status: SpanStatus,
+}
+
+fn is_zero(v: &u32) -> bool {
+    *v == 0
+}
+
+impl From<&SdkSpanData> for Span {
+    fn from(value: &SdkSpanData) -> Self {
+        Span {
+            trace_id: value.span_context.trace_id().to_string(),
+            span_id: value.span_context.span_id().to_string(),
+            trace_state: value.span_context.trace_state().header(),
+            parent_span_id: if value.parent_span_id == SpanId::INVALID {
+                String::new()
+            } else {
+                value.parent_span_id.to_string()
+            },
+            flags: u32::from(value.span_context.trace_flags().to_u8()),
+            name: value.name.clone(),
+            kind: span_kind(&value.span_kind),
+            start_time_unix_nano: value.start_time,
+            end_time_unix_nano: value.end_time,
+            attributes: value.attributes.iter().map(Into::into).collect(),
+            dropped_attributes_count: value.dropped_attributes_count,
+            events: value.events.iter().map(Into::into).collect(),
+            dropped_events_count: value.events.dropped_count,
+            links: value.links.iter().map(Into::into).collect(),
+            dropped_links_count: value.links.dropped_count,
+            status: (&value.status).into(),
+        }
+    }
+}
+
+/// The OTLP `SpanKind` enum value.
+fn span_kind(kind: &SpanKind) -> u32 {
+    match kind {
+        SpanKind::Internal => 1,
+        SpanKind::Server => 2,
+        SpanKind::Client => 3,
+        SpanKind::Producer => 4,
+        SpanKind::Consumer => 5,
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpanStatus {
+    #[serde(skip_serializing_if = "str::is_empty")]
+    message: Cow<'static, str>,
+    #[serde(skip_serializing_if = "is_zero")]
+    code: u32,
+}
+
+impl From<&Status> for SpanStatus {
+    fn from(value: &Status) -> Self {
+        match value {
+            Status::Unset => SpanStatus {
+                message: "".into(),
+                code: 0,
+            },
+            Status::Ok => SpanStatus {
+                message: "".into(),
+                code: 1,
+            },
+            Status::Error { description } => SpanStatus {
+                message: description.clone(),
+                code: 2,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Event {
+    #[serde(serialize_with = "as_unix_nano")]
+    time_unix_nano: SystemTime,
+    name: Cow<'static, str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped_attributes_count: u32,
+}
+
+impl From<&opentelemetry::trace::Event> for Event {
+    fn from(value: &opentelemetry::trace::Event) -> Self {
+        Event {
+            time_unix_nano: value.timestamp,
+            name: value.name.clone(),
+            attributes: value.attributes.iter().map(Into::into).collect(),
+            dropped_attributes_count: value.dropped_attributes_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Link {
+    trace_id: String,
+    span_id: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    trace_state: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped_attributes_count: u32,
+}
+
+impl From<&opentelemetry::trace::Link> for Link {
+    fn from(value: &opentelemetry::trace::Link) -> Self {
+        Link {
+            trace_id: value.span_context.trace_id().to_string(),
+            span_id: value.span_context.span_id().to_string(),
+            trace_state: value.span_context.trace_state().header(),
+            attributes: value.attributes.iter().map(Into::into).collect(),
+            dropped_attributes_count: value.dropped_attributes_count,
+        }
+    }
+}
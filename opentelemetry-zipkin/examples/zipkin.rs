@@ -1,7 +1,9 @@
 use opentelemetry::{
-    global::{self, shutdown_tracer_provider},
-    trace::{Span, Tracer},
+    global,
+    trace::{Span, Tracer, TracerProvider as _},
+    KeyValue,
 };
+use opentelemetry_sdk::{trace::TracerProvider, Resource};
 use std::thread;
 use std::time::Duration;
 
@@ -13,16 +15,23 @@ fn bar() {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let tracer = opentelemetry_zipkin::new_pipeline()
-        .with_service_name("trace-demo")
-        .install_simple()?;
+    let exporter = opentelemetry_zipkin::ZipkinExporter::builder().build()?;
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            "trace-demo",
+        )]))
+        .build();
+    global::set_tracer_provider(provider.clone());
 
+    let tracer = provider.tracer("trace-demo");
     tracer.in_span("foo", |_cx| {
         thread::sleep(Duration::from_millis(6));
         bar();
         thread::sleep(Duration::from_millis(6));
     });
 
-    shutdown_tracer_provider();
+    provider.shutdown()?;
     Ok(())
 }
@@ -23,26 +23,233 @@ use std::time::Duration;
 
 /// Zipkin span exporter
 #[derive(Debug)]
-pub struct Exporter {
+pub struct ZipkinExporter {
     local_endpoint: Endpoint,
+    // set with the deprecated `ZipkinPipelineBuilder::with_service_name`,
+    // preferred over the resource's
+    service_name: Option<String>,
+    service_addr: Option<SocketAddr>,
     uploader: uploader::Uploader,
 }
 
-impl Exporter {
-    fn new(local_endpoint: Endpoint, client: Arc<dyn HttpClient>, collector_endpoint: Uri) -> Self {
-        Exporter {
+/// Zipkin span exporter
+#[deprecated(since = "0.28.0", note = "Use ZipkinExporter instead")]
+pub type Exporter = ZipkinExporter;
+
+impl ZipkinExporter {
+    /// Create a builder to configure a [`ZipkinExporter`].
+    pub fn builder() -> ZipkinExporterBuilder {
+        ZipkinExporterBuilder::default()
+    }
+
+    fn new(
+        local_endpoint: Endpoint,
+        service_name: Option<String>,
+        service_addr: Option<SocketAddr>,
+        client: Arc<dyn HttpClient>,
+        collector_endpoint: Uri,
+        encoding: Encoding,
+        compression: Option<Compression>,
+    ) -> Self {
+        let uploader = match encoding {
+            Encoding::Json => uploader::Uploader::new(client, collector_endpoint, compression),
+            Encoding::Protobuf => {
+                uploader::Uploader::new_protobuf(client, collector_endpoint, compression)
+            }
+        };
+        ZipkinExporter {
             local_endpoint,
-            uploader: uploader::Uploader::new(client, collector_endpoint),
+            service_name,
+            service_addr,
+            uploader,
         }
     }
 }
 
+/// Builder for [`ZipkinExporter`].
+///
+/// The service name reported to Zipkin is the `service.name` of the
+/// [`Resource`] of the `TracerProvider` the exporter is registered with.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use opentelemetry_sdk::{trace::TracerProvider, Resource};
+/// use opentelemetry::KeyValue;
+///
+/// # fn main() -> Result<(), opentelemetry::trace::TraceError> {
+/// let exporter = opentelemetry_zipkin::ZipkinExporter::builder()
+///     .with_collector_endpoint("http://localhost:9411/api/v2/spans")
+///     .build()?;
+/// let provider = TracerProvider::builder()
+///     .with_simple_exporter(exporter)
+///     .with_resource(Resource::new(vec![KeyValue::new("service.name", "my_app")]))
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ZipkinExporterBuilder {
+    service_addr: Option<SocketAddr>,
+    collector_endpoint: String,
+    client: Option<Arc<dyn HttpClient>>,
+    timeout: Duration,
+    encoding: Encoding,
+    compression: Option<Compression>,
+}
+
+impl Default for ZipkinExporterBuilder {
+    fn default() -> Self {
+        ZipkinExporterBuilder {
+            service_addr: None,
+            collector_endpoint: env::get_endpoint(),
+            client: None,
+            timeout: env::get_timeout(),
+            encoding: Encoding::default(),
+            compression: None,
+        }
+    }
+}
+
+impl ZipkinExporterBuilder {
+    /// Create a [`ZipkinExporter`] with the current configuration.
+    ///
+    /// Returns error if the endpoint is not valid or if no http client is provided.
+    pub fn build(self) -> Result<ZipkinExporter, TraceError> {
+        let client = self
+            .client
+            .or_else(|| default_http_client(self.timeout))
+            .ok_or(Error::NoHttpClient)?;
+        let collector_endpoint = self
+            .collector_endpoint
+            .parse()
+            .map_err::<Error, _>(Into::into)?;
+        Ok(ZipkinExporter::new(
+            Endpoint::new(default_service_name(), self.service_addr),
+            None,
+            self.service_addr,
+            client,
+            collector_endpoint,
+            self.encoding,
+            self.compression,
+        ))
+    }
+
+    /// Assign client implementation
+    pub fn with_http_client<T: HttpClient + 'static>(mut self, client: T) -> Self {
+        self.client = Some(Arc::new(client));
+        self
+    }
+
+    /// Assign the address of the service reported as the local endpoint of the spans.
+    pub fn with_service_address(mut self, addr: SocketAddr) -> Self {
+        self.service_addr = Some(addr);
+        self
+    }
+
+    /// Assign the Zipkin collector endpoint, defaults to the
+    /// `OTEL_EXPORTER_ZIPKIN_ENDPOINT` environment variable or
+    /// `http://127.0.0.1:9411/api/v2/spans`.
+    pub fn with_collector_endpoint<T: Into<String>>(mut self, endpoint: T) -> Self {
+        self.collector_endpoint = endpoint.into();
+        self
+    }
+
+    /// Assign the timeout of each export, defaults to the
+    /// `OTEL_EXPORTER_ZIPKIN_TIMEOUT` environment variable or 10 seconds.
+    ///
+    /// Only applies to the built-in reqwest client, a client set with
+    /// [`with_http_client`](Self::with_http_client) has to be configured
+    /// with its own timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Assign the encoding of the exported spans, defaults to [`Encoding::Json`].
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Compress the exported spans, they are sent uncompressed by default.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+}
+
+/// The http client enabled through the crate features, if any.
+#[allow(unused_variables)]
+fn default_http_client(timeout: Duration) -> Option<Arc<dyn HttpClient>> {
+    #[cfg(feature = "reqwest-blocking-client")]
+    return Some(Arc::new(
+        reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::blocking::Client::new()),
+    ));
+    #[cfg(all(not(feature = "reqwest-blocking-client"), feature = "reqwest-client"))]
+    return Some(Arc::new(
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new()),
+    ));
+    #[cfg(all(
+        not(feature = "reqwest-client"),
+        not(feature = "reqwest-blocking-client")
+    ))]
+    None
+}
+
+/// The service name detected from the environment, used until the exporter
+/// is given a resource.
+fn default_service_name() -> String {
+    SdkProvidedResourceDetector
+        .detect(Duration::from_secs(0))
+        .get(semcov::resource::SERVICE_NAME.into())
+        .map(|name| name.to_string())
+        .unwrap_or_default()
+}
+
+/// The format spans are encoded in when sent to the Zipkin collector.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// Zipkin v2 JSON, sent as `application/json`.
+    #[default]
+    Json,
+    /// Zipkin v2 `proto3`, sent as `application/x-protobuf`. It is more
+    /// compact and faster for the collector to decode than JSON.
+    Protobuf,
+}
+
+/// Compression applied to the request bodies sent to the Zipkin collector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Compression {
+    /// Compress with gzip and set `Content-Encoding: gzip`. Requires the
+    /// `gzip` feature.
+    #[cfg(feature = "gzip")]
+    Gzip,
+}
+
 /// Create a new Zipkin exporter pipeline builder.
+#[deprecated(
+    since = "0.28.0",
+    note = "Use ZipkinExporter::builder() and register the exporter with a TracerProvider instead"
+)]
+#[allow(deprecated)]
 pub fn new_pipeline() -> ZipkinPipelineBuilder {
     ZipkinPipelineBuilder::default()
 }
 
 /// Builder for `ExporterConfig` struct.
+#[deprecated(
+    since = "0.28.0",
+    note = "Use ZipkinExporter::builder() and register the exporter with a TracerProvider instead"
+)]
 #[derive(Debug)]
 pub struct ZipkinPipelineBuilder {
     service_name: Option<String>,
@@ -50,88 +257,76 @@ pub struct ZipkinPipelineBuilder {
     collector_endpoint: String,
     trace_config: Option<Config>,
     client: Option<Arc<dyn HttpClient>>,
+    encoding: Encoding,
+    compression: Option<Compression>,
 }
 
+#[allow(deprecated)]
 impl Default for ZipkinPipelineBuilder {
     fn default() -> Self {
-        let timeout = env::get_timeout();
         ZipkinPipelineBuilder {
-            #[cfg(feature = "reqwest-blocking-client")]
-            client: Some(Arc::new(
-                reqwest::blocking::Client::builder()
-                    .timeout(timeout)
-                    .build()
-                    .unwrap_or_else(|_| reqwest::blocking::Client::new()),
-            )),
-            #[cfg(all(not(feature = "reqwest-blocking-client"), feature = "reqwest-client"))]
-            client: Some(Arc::new(
-                reqwest::Client::builder()
-                    .timeout(timeout)
-                    .build()
-                    .unwrap_or_else(|_| reqwest::Client::new()),
-            )),
-            #[cfg(all(
-                not(feature = "reqwest-client"),
-                not(feature = "reqwest-blocking-client")
-            ))]
-            client: None,
-
+            client: default_http_client(env::get_timeout()),
             service_name: None,
             service_addr: None,
             collector_endpoint: env::get_endpoint(),
             trace_config: None,
+            encoding: Encoding::default(),
+            compression: None,
         }
     }
 }
 
+#[allow(deprecated)]
 impl ZipkinPipelineBuilder {
     /// Initial a Zipkin span exporter.
     ///
     /// Returns error if the endpoint is not valid or if no http client is provided.
-    pub fn init_exporter(mut self) -> Result<Exporter, TraceError> {
+    pub fn init_exporter(mut self) -> Result<ZipkinExporter, TraceError> {
         let (_, endpoint) = self.init_config_and_endpoint();
         self.init_exporter_with_endpoint(endpoint)
     }
 
     fn init_config_and_endpoint(&mut self) -> (Config, Endpoint) {
-        let service_name = self.service_name.take();
-        if let Some(service_name) = service_name {
+        if let Some(service_name) = self.service_name.clone() {
+            // the exporter picks the service name up from the provider's resource
+            let service_name_kv =
+                KeyValue::new(semcov::resource::SERVICE_NAME, service_name.clone());
             let config = if let Some(mut cfg) = self.trace_config.take() {
                 cfg.resource = Cow::Owned(Resource::new(
                     cfg.resource
                         .iter()
                         .filter(|(k, _v)| k.as_str() != semcov::resource::SERVICE_NAME)
                         .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
+                        .chain(std::iter::once(service_name_kv))
                         .collect::<Vec<KeyValue>>(),
                 ));
                 cfg
             } else {
                 #[allow(deprecated)]
-                Config::default().with_resource(Resource::empty())
+                Config::default().with_resource(Resource::new(vec![service_name_kv]))
             };
             (config, Endpoint::new(service_name, self.service_addr))
         } else {
-            let service_name = SdkProvidedResourceDetector
-                .detect(Duration::from_secs(0))
-                .get(semcov::resource::SERVICE_NAME.into())
-                .unwrap()
-                .to_string();
             (
                 #[allow(deprecated)]
                 Config::default().with_resource(Resource::empty()),
-                Endpoint::new(service_name, self.service_addr),
+                Endpoint::new(default_service_name(), self.service_addr),
             )
         }
     }
 
-    fn init_exporter_with_endpoint(self, endpoint: Endpoint) -> Result<Exporter, TraceError> {
+    fn init_exporter_with_endpoint(self, endpoint: Endpoint) -> Result<ZipkinExporter, TraceError> {
         if let Some(client) = self.client {
-            let exporter = Exporter::new(
+            let exporter = ZipkinExporter::new(
                 endpoint,
+                self.service_name,
+                self.service_addr,
                 client,
                 self.collector_endpoint
                     .parse()
                     .map_err::<Error, _>(Into::into)?,
+                self.encoding,
+                self.compression,
             );
             Ok(exporter)
         } else {
@@ -203,6 +398,18 @@ impl ZipkinPipelineBuilder {
         self.trace_config = Some(config);
         self
     }
+
+    /// Assign the encoding of the exported spans, defaults to [`Encoding::Json`].
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Compress the exported spans, they are sent uncompressed by default.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
 }
 
 async fn zipkin_export(
@@ -219,7 +426,7 @@ async fn zipkin_export(
 }
 
 #[async_trait]
-impl trace::SpanExporter for Exporter {
+impl trace::SpanExporter for ZipkinExporter {
     /// Export spans to Zipkin collector.
     fn export(&mut self, batch: Vec<trace::SpanData>) -> BoxFuture<'static, trace::ExportResult> {
         Box::pin(zipkin_export(
@@ -228,6 +435,21 @@ impl trace::SpanExporter for Exporter {
             self.local_endpoint.clone(),
         ))
     }
+
+    /// Report the `service.name` of the resource as the local endpoint of the
+    /// spans, unless the exporter was created by a pipeline with a service name.
+    fn set_resource(&mut self, resource: &Resource) {
+        let service_name = self
+            .service_name
+            .clone()
+            .or_else(|| {
+                resource
+                    .get(semcov::resource::SERVICE_NAME.into())
+                    .map(|name| name.to_string())
+            })
+            .unwrap_or_else(default_service_name);
+        self.local_endpoint = Endpoint::new(service_name, self.service_addr);
+    }
 }
 
 /// Wrap type for errors from opentelemetry zipkin
@@ -262,3 +484,58 @@ impl opentelemetry::trace::ExportError for Error {
         "zipkin"
     }
 }
+
+#[cfg(all(
+    test,
+    any(feature = "reqwest-blocking-client", feature = "reqwest-client")
+))]
+mod tests {
+    use super::*;
+    use opentelemetry_sdk::export::trace::SpanExporter as _;
+
+    #[test]
+    fn test_service_name_from_resource() {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let mut exporter = ZipkinExporter::builder()
+            .with_service_address(addr)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", exporter.local_endpoint),
+            format!("{:?}", Endpoint::new(default_service_name(), Some(addr)))
+        );
+
+        exporter.set_resource(&Resource::new(vec![KeyValue::new(
+            semcov::resource::SERVICE_NAME,
+            "my_app",
+        )]));
+        assert_eq!(
+            format!("{:?}", exporter.local_endpoint),
+            format!("{:?}", Endpoint::new("my_app".to_owned(), Some(addr)))
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_pipeline_service_name_is_kept() {
+        let mut exporter = crate::new_pipeline()
+            .with_service_name("my_app")
+            .init_exporter()
+            .unwrap();
+
+        // the default resource of a provider has an `unknown_service` name
+        exporter.set_resource(&Resource::default());
+        assert_eq!(
+            format!("{:?}", exporter.local_endpoint),
+            format!("{:?}", Endpoint::new("my_app".to_owned(), None))
+        );
+    }
+
+    #[test]
+    fn test_invalid_collector_endpoint() {
+        let result = ZipkinExporter::builder()
+            .with_collector_endpoint("not a uri")
+            .build();
+        assert!(result.is_err());
+    }
+}
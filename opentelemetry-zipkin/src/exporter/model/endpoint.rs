@@ -36,6 +36,23 @@ impl Endpoint {
     }
 }
 
+impl From<Endpoint> for super::proto::Endpoint {
+    fn from(endpoint: Endpoint) -> Self {
+        super::proto::Endpoint {
+            service_name: endpoint.service_name.unwrap_or_default(),
+            ipv4: endpoint
+                .ipv4
+                .map(|ip| ip.octets().to_vec())
+                .unwrap_or_default(),
+            ipv6: endpoint
+                .ipv6
+                .map(|ip| ip.octets().to_vec())
+                .unwrap_or_default(),
+            port: endpoint.port.map(i32::from).unwrap_or_default(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::exporter::model::endpoint::Endpoint;
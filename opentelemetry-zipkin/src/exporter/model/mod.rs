@@ -8,6 +8,7 @@ use std::time::{Duration, SystemTime};
 
 pub(crate) mod annotation;
 pub(crate) mod endpoint;
+pub(crate) mod proto;
 pub(crate) mod span;
 
 use endpoint::Endpoint;
@@ -0,0 +1,96 @@
+//! Zipkin v2 `proto3` messages, see
+//! <https://github.com/openzipkin/zipkin-api/blob/master/zipkin.proto>
+use std::collections::HashMap;
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ListOfSpans {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) spans: Vec<Span>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Span {
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) trace_id: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub(crate) parent_id: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub(crate) id: Vec<u8>,
+    #[prost(enumeration = "Kind", tag = "4")]
+    pub(crate) kind: i32,
+    #[prost(string, tag = "5")]
+    pub(crate) name: String,
+    #[prost(fixed64, tag = "6")]
+    pub(crate) timestamp: u64,
+    #[prost(uint64, tag = "7")]
+    pub(crate) duration: u64,
+    #[prost(message, optional, tag = "8")]
+    pub(crate) local_endpoint: Option<Endpoint>,
+    #[prost(message, optional, tag = "9")]
+    pub(crate) remote_endpoint: Option<Endpoint>,
+    #[prost(message, repeated, tag = "10")]
+    pub(crate) annotations: Vec<Annotation>,
+    #[prost(map = "string, string", tag = "11")]
+    pub(crate) tags: HashMap<String, String>,
+    #[prost(bool, tag = "12")]
+    pub(crate) debug: bool,
+    #[prost(bool, tag = "13")]
+    pub(crate) shared: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub(crate) enum Kind {
+    Unspecified = 0,
+    Client = 1,
+    Server = 2,
+    Producer = 3,
+    Consumer = 4,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Endpoint {
+    #[prost(string, tag = "1")]
+    pub(crate) service_name: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub(crate) ipv4: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub(crate) ipv6: Vec<u8>,
+    #[prost(int32, tag = "4")]
+    pub(crate) port: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Annotation {
+    #[prost(fixed64, tag = "1")]
+    pub(crate) timestamp: u64,
+    #[prost(string, tag = "2")]
+    pub(crate) value: String,
+}
+
+/// Decodes a lower hex trace or span id. Ids that are all zeros, i.e. the
+/// parent id of a root span, are left empty.
+pub(crate) fn decode_id(id: &str) -> Vec<u8> {
+    let bytes: Vec<u8> = (0..id.len() / 2)
+        .filter_map(|i| u8::from_str_radix(id.get(i * 2..i * 2 + 2)?, 16).ok())
+        .collect();
+    if bytes.iter().all(|b| *b == 0) {
+        Vec::new()
+    } else {
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_id;
+
+    #[test]
+    fn test_decode_id() {
+        assert_eq!(
+            decode_id("ffdc9bb9a6453df3"),
+            vec![0xff, 0xdc, 0x9b, 0xb9, 0xa6, 0x45, 0x3d, 0xf3]
+        );
+        assert_eq!(decode_id("0000000000000000"), Vec::<u8>::new());
+    }
+}
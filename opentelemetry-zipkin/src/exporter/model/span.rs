@@ -1,4 +1,4 @@
-use crate::exporter::model::{annotation::Annotation, endpoint::Endpoint};
+use crate::exporter::model::{annotation::Annotation, endpoint::Endpoint, proto};
 use serde::Serialize;
 use std::collections::HashMap;
 
@@ -53,6 +53,47 @@ pub(crate) struct Span {
     shared: bool,
 }
 
+impl From<Kind> for proto::Kind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Client => proto::Kind::Client,
+            Kind::Server => proto::Kind::Server,
+            Kind::Producer => proto::Kind::Producer,
+            Kind::Consumer => proto::Kind::Consumer,
+        }
+    }
+}
+
+impl From<Span> for proto::Span {
+    fn from(span: Span) -> Self {
+        let decode_id =
+            |id: Option<String>| id.as_deref().map(proto::decode_id).unwrap_or_default();
+        proto::Span {
+            trace_id: decode_id(span.trace_id),
+            parent_id: decode_id(span.parent_id),
+            id: decode_id(span.id),
+            kind: span
+                .kind
+                .map_or(proto::Kind::Unspecified, Into::into)
+                .into(),
+            name: span.name.unwrap_or_default(),
+            timestamp: span.timestamp.unwrap_or_default(),
+            duration: span.duration.unwrap_or_default(),
+            local_endpoint: span.local_endpoint.map(Into::into),
+            remote_endpoint: span.remote_endpoint.map(Into::into),
+            annotations: span
+                .annotations
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            tags: span.tags.unwrap_or_default(),
+            debug: span.debug,
+            shared: span.shared,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::exporter::model::annotation::Annotation;
@@ -1,23 +1,53 @@
 //! # Zipkin Span Exporter
-use crate::exporter::model::span::Span;
-use crate::exporter::Error;
+use crate::exporter::model::{proto, span::Span};
+use crate::exporter::{Compression, Error};
+#[cfg(feature = "gzip")]
+use flate2::{write::GzEncoder, Compression as GzLevel};
+#[cfg(feature = "gzip")]
+use http::header::CONTENT_ENCODING;
 use http::{header::CONTENT_TYPE, Method, Request, Uri};
 use opentelemetry_http::{HttpClient, ResponseExt};
 use opentelemetry_sdk::export::trace::ExportResult;
+use prost::Message;
 use std::fmt::Debug;
+#[cfg(feature = "gzip")]
+use std::io::Write;
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub(crate) enum Uploader {
     Http(JsonV2Client),
+    Protobuf(ProtoV2Client),
 }
 
 impl Uploader {
-    /// Create a new http uploader
-    pub(crate) fn new(client: Arc<dyn HttpClient>, collector_endpoint: Uri) -> Self {
+    /// Create a new http uploader sending JSON encoded spans
+    pub(crate) fn new(
+        client: Arc<dyn HttpClient>,
+        collector_endpoint: Uri,
+        compression: Option<Compression>,
+    ) -> Self {
         Uploader::Http(JsonV2Client {
-            client,
-            collector_endpoint,
+            client: HttpSender {
+                client,
+                collector_endpoint,
+                compression,
+            },
+        })
+    }
+
+    /// Create a new http uploader sending `proto3` encoded spans
+    pub(crate) fn new_protobuf(
+        client: Arc<dyn HttpClient>,
+        collector_endpoint: Uri,
+        compression: Option<Compression>,
+    ) -> Self {
+        Uploader::Protobuf(ProtoV2Client {
+            client: HttpSender {
+                client,
+                collector_endpoint,
+                compression,
+            },
         })
     }
 
@@ -25,25 +55,165 @@ impl Uploader {
     pub(crate) async fn upload(&self, spans: Vec<Span>) -> ExportResult {
         match self {
             Uploader::Http(client) => client.upload(spans).await,
+            Uploader::Protobuf(client) => client.upload(spans).await,
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct JsonV2Client {
-    client: Arc<dyn HttpClient>,
-    collector_endpoint: Uri,
+    client: HttpSender,
 }
 
 impl JsonV2Client {
     async fn upload(&self, spans: Vec<Span>) -> ExportResult {
-        let req = Request::builder()
+        let body = serde_json::to_vec(&spans).unwrap_or_default();
+        self.client.send("application/json", body).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ProtoV2Client {
+    client: HttpSender,
+}
+
+impl ProtoV2Client {
+    async fn upload(&self, spans: Vec<Span>) -> ExportResult {
+        let body = encode_proto(spans);
+        self.client.send("application/x-protobuf", body).await
+    }
+}
+
+fn encode_proto(spans: Vec<Span>) -> Vec<u8> {
+    proto::ListOfSpans {
+        spans: spans.into_iter().map(Into::into).collect(),
+    }
+    .encode_to_vec()
+}
+
+/// Posts encoded spans to the collector, compressing them if configured.
+#[derive(Debug, Clone)]
+struct HttpSender {
+    client: Arc<dyn HttpClient>,
+    collector_endpoint: Uri,
+    compression: Option<Compression>,
+}
+
+impl HttpSender {
+    async fn send(&self, content_type: &'static str, body: Vec<u8>) -> ExportResult {
+        let builder = Request::builder()
             .method(Method::POST)
             .uri(self.collector_endpoint.clone())
-            .header(CONTENT_TYPE, "application/json")
-            .body(serde_json::to_vec(&spans).unwrap_or_default())
-            .map_err::<Error, _>(Into::into)?;
+            .header(CONTENT_TYPE, content_type);
+        let (builder, body) = match self.compression {
+            #[cfg(feature = "gzip")]
+            Some(Compression::Gzip) => (
+                builder.header(CONTENT_ENCODING, "gzip"),
+                gzip(&body).map_err(|err| Error::Other(err.to_string()))?,
+            ),
+            #[cfg(not(feature = "gzip"))]
+            Some(compression) => match compression {},
+            None => (builder, body),
+        };
+        let req = builder.body(body).map_err::<Error, _>(Into::into)?;
         let _ = self.client.send(req).await?.error_for_status()?;
         Ok(())
     }
 }
+
+#[cfg(feature = "gzip")]
+fn gzip(body: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), GzLevel::default());
+    encoder.write_all(body)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporter::model::endpoint::Endpoint;
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    #[cfg(feature = "gzip")]
+    use flate2::read::GzDecoder;
+    use http::{header::CONTENT_ENCODING, Response};
+    use opentelemetry_http::HttpError;
+    #[cfg(feature = "gzip")]
+    use std::io::Read;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct RecordingClient(Mutex<Vec<Request<Vec<u8>>>>);
+
+    #[async_trait]
+    impl HttpClient for RecordingClient {
+        async fn send(&self, req: Request<Vec<u8>>) -> Result<Response<Bytes>, HttpError> {
+            self.0.lock().unwrap().push(req);
+            Ok(Response::new(Bytes::new()))
+        }
+    }
+
+    fn span() -> Span {
+        Span::builder()
+            .trace_id("4e441824ec2b6a44ffdc9bb9a6453df3".to_owned())
+            .id("efdc9cd9a1849df3".to_owned())
+            .name("main".to_owned())
+            .local_endpoint(Endpoint::new("my-service".to_owned(), None))
+            .build()
+    }
+
+    fn upload(
+        new: fn(Arc<dyn HttpClient>, Uri, Option<Compression>) -> Uploader,
+        compression: Option<Compression>,
+    ) -> Request<Vec<u8>> {
+        let client = Arc::new(RecordingClient::default());
+        let uploader = new(
+            client.clone(),
+            Uri::from_static("http://localhost:9411/api/v2/spans"),
+            compression,
+        );
+        futures_util::FutureExt::now_or_never(uploader.upload(vec![span()]))
+            .expect("upload is ready")
+            .unwrap();
+        let mut requests = client.0.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        requests.remove(0)
+    }
+
+    #[test]
+    fn test_protobuf_upload() {
+        let req = upload(Uploader::new_protobuf, None);
+        assert_eq!(req.headers()[CONTENT_TYPE], "application/x-protobuf");
+        assert!(req.headers().get(CONTENT_ENCODING).is_none());
+
+        let spans = proto::ListOfSpans::decode(req.body().as_slice()).unwrap();
+        assert_eq!(spans.spans.len(), 1);
+        let span = &spans.spans[0];
+        assert_eq!(span.trace_id.len(), 16);
+        assert_eq!(
+            span.id,
+            vec![0xef, 0xdc, 0x9c, 0xd9, 0xa1, 0x84, 0x9d, 0xf3]
+        );
+        assert_eq!(span.name, "main");
+        assert_eq!(
+            span.local_endpoint
+                .as_ref()
+                .map(|e| e.service_name.as_str()),
+            Some("my-service")
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_json_upload() {
+        let req = upload(Uploader::new, Some(Compression::Gzip));
+        assert_eq!(req.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(req.headers()[CONTENT_ENCODING], "gzip");
+
+        let mut body = String::new();
+        GzDecoder::new(req.body().as_slice())
+            .read_to_string(&mut body)
+            .unwrap();
+        assert_eq!(body, serde_json::to_string(&vec![span()]).unwrap());
+    }
+}
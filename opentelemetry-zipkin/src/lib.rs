@@ -17,22 +17,30 @@
 //! $ docker run -d -p 9411:9411 openzipkin/zipkin
 //! ```
 //!
-//! Then install a new pipeline with the recommended defaults to start exporting
-//! telemetry:
+//! Then register an exporter with the recommended defaults with a tracer
+//! provider to start exporting telemetry. The service name is taken from the
+//! `service.name` of the provider's resource:
 //!
 //! ```no_run
-//! use opentelemetry::trace::{Tracer, TraceError};
-//! use opentelemetry::global;
+//! use opentelemetry::trace::{Tracer, TracerProvider as _, TraceError};
+//! use opentelemetry::{global, KeyValue};
+//! use opentelemetry_sdk::{trace::TracerProvider, Resource};
 //!
 //! fn main() -> Result<(), TraceError> {
 //!     global::set_text_map_propagator(opentelemetry_zipkin::Propagator::new());
-//!     let tracer = opentelemetry_zipkin::new_pipeline().install_simple()?;
-//!
+//!     let exporter = opentelemetry_zipkin::ZipkinExporter::builder().build()?;
+//!     let provider = TracerProvider::builder()
+//!         .with_simple_exporter(exporter)
+//!         .with_resource(Resource::new(vec![KeyValue::new("service.name", "my_app")]))
+//!         .build();
+//!     global::set_tracer_provider(provider.clone());
+//!
+//!     let tracer = provider.tracer("my_app");
 //!     tracer.in_span("doing_work", |cx| {
 //!         // Traced app logic here...
 //!     });
 //!
-//!     global::shutdown_tracer_provider(); // sending remaining spans
+//!     provider.shutdown()?; // sending remaining spans
 //!
 //!     Ok(())
 //! }
@@ -42,9 +50,8 @@
 //!
 //! For optimal performance, a batch exporter is recommended as the simple exporter
 //! will export each span synchronously on drop. You can enable the [`rt-tokio`],
-//! [`rt-tokio-current-thread`] or [`rt-async-std`] features and specify a runtime
-//! on the pipeline builder to have a batch exporter configured for you
-//! automatically.
+//! [`rt-tokio-current-thread`] or [`rt-async-std`] features and register the
+//! exporter with a batch span processor on that runtime.
 //!
 //! ```toml
 //! [dependencies]
@@ -54,8 +61,10 @@
 //!
 //! ```no_run
 //! # fn main() -> Result<(), opentelemetry::trace::TraceError> {
-//! let tracer = opentelemetry_zipkin::new_pipeline()
-//!     .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+//! let exporter = opentelemetry_zipkin::ZipkinExporter::builder().build()?;
+//! let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+//!     .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+//!     .build();
 //! # Ok(())
 //! # }
 //! ```
@@ -81,11 +90,11 @@
 //! ## Kitchen Sink Full Configuration
 //!
 //! Example showing how to override all configuration options. See the
-//! [`ZipkinPipelineBuilder`] docs for details of each option.
+//! [`ZipkinExporterBuilder`] docs for details of each option.
 //!
 //!
 //! ```no_run
-//! use opentelemetry::{global, KeyValue, trace::Tracer};
+//! use opentelemetry::{global, KeyValue, trace::{Tracer, TracerProvider as _}};
 //! use opentelemetry_sdk::{trace::{self, RandomIdGenerator, Sampler}, Resource};
 //! use opentelemetry_sdk::export::trace::ExportResult;
 //! use opentelemetry_http::{HttpClient, HttpError};
@@ -131,32 +140,39 @@
 //!
 //! fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
 //!     global::set_text_map_propagator(opentelemetry_zipkin::Propagator::new());
-//!     let tracer = opentelemetry_zipkin::new_pipeline()
+//!     let builder = opentelemetry_zipkin::ZipkinExporter::builder()
 //!         .with_http_client(
 //!             HyperClient(
 //!                 Client::builder(TokioExecutor::new())
 //!                     .build_http()
 //!             )
 //!         )
-//!         .with_service_name("my_app")
 //!         .with_service_address("127.0.0.1:8080".parse()?)
 //!         .with_collector_endpoint("http://localhost:9411/api/v2/spans")
-//!         .with_trace_config(
-//!             trace::config()
-//!                 .with_sampler(Sampler::AlwaysOn)
-//!                 .with_id_generator(RandomIdGenerator::default())
-//!                 .with_max_events_per_span(64)
-//!                 .with_max_attributes_per_span(16)
-//!                 .with_max_events_per_span(16)
-//!                 .with_resource(Resource::new(vec![KeyValue::new("key", "value")])),
-//!         )
-//!         .install_batch(opentelemetry_sdk::runtime::Tokio)?;
-//!
+//!         .with_encoding(opentelemetry_zipkin::Encoding::Protobuf);
+//!     // requires the `gzip` feature
+//!     # #[cfg(feature = "gzip")]
+//!     let builder = builder.with_compression(opentelemetry_zipkin::Compression::Gzip);
+//!     let exporter = builder.build()?;
+//!     let provider = trace::TracerProvider::builder()
+//!         .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+//!         .with_sampler(Sampler::AlwaysOn)
+//!         .with_id_generator(RandomIdGenerator::default())
+//!         .with_max_events_per_span(64)
+//!         .with_max_attributes_per_span(16)
+//!         .with_resource(Resource::new(vec![
+//!             KeyValue::new("service.name", "my_app"),
+//!             KeyValue::new("key", "value"),
+//!         ]))
+//!         .build();
+//!     global::set_tracer_provider(provider.clone());
+//!
+//!     let tracer = provider.tracer("my_app");
 //!     tracer.in_span("doing_work", |cx| {
 //!         // Traced app logic here...
 //!     });
 //!
-//!     global::shutdown_tracer_provider(); // sending remaining spans
+//!     provider.shutdown()?; // sending remaining spans
 //!
 //!     Ok(())
 //! }
@@ -169,6 +185,7 @@
 //! * `reqwest-blocking-client`: Export spans using the reqwest blocking http
 //!   client (enabled by default).
 //! * `reqwest-client`: Export spans using the reqwest non-blocking http client.
+//! * `gzip`: Enable [`Compression::Gzip`] of the exported spans.
 //!
 //! ## Supported Rust Versions
 //!
@@ -208,5 +225,9 @@ extern crate typed_builder;
 mod exporter;
 mod propagator;
 
-pub use exporter::{new_pipeline, Error, Exporter, ZipkinPipelineBuilder};
+#[allow(deprecated)]
+pub use exporter::{
+    new_pipeline, Compression, Encoding, Error, Exporter, ZipkinExporter, ZipkinExporterBuilder,
+    ZipkinPipelineBuilder,
+};
 pub use propagator::{B3Encoding, Propagator};
@@ -0,0 +1,65 @@
+//! [zPages] for OpenTelemetry: in-process pages showing the spans of a
+//! process, for on-call engineers to inspect in-flight requests without an
+//! exporter or a backend.
+//!
+//! A [ZPagesSpanProcessor] keeps the running spans and, for each span name,
+//! the counts and last few samples of the ended spans per latency bucket and
+//! of the error spans. A [ZPagesServer] serves them over HTTP:
+//!
+//! * `/tracez` lists the span names with their counts, linking to the sampled
+//!   spans, as HTML or as the `tracez.v1` protobuf messages of
+//!   [opentelemetry_proto],
+//! * `/statusz` shows the process, the resource of the provider, custom
+//!   configuration entries and the `OTEL_*` environment variables.
+//!
+//! The aggregated spans can also be read directly through a [TracezQuerier].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use opentelemetry::trace::{Tracer, TracerProvider as _};
+//! use opentelemetry_sdk::trace::TracerProvider;
+//! use opentelemetry_zpages::{ZPagesServer, ZPagesSpanProcessor};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let processor = ZPagesSpanProcessor::new();
+//! let server = ZPagesServer::builder(processor.querier())
+//!     .with_address("127.0.0.1:55679")
+//!     .start()?;
+//!
+//! let provider = TracerProvider::builder()
+//!     .with_span_processor(processor)
+//!     .build();
+//! provider.tracer("my-app").in_span("handle_request", |_cx| {
+//!     // shown on http://127.0.0.1:55679/tracez while running
+//! });
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [zPages]: https://opencensus.io/zpages/
+#![warn(
+    future_incompatible,
+    missing_debug_implementations,
+    missing_docs,
+    nonstandard_style,
+    rust_2018_idioms,
+    unreachable_pub,
+    unused
+)]
+#![cfg_attr(
+    docsrs,
+    feature(doc_cfg, doc_auto_cfg),
+    deny(rustdoc::broken_intra_doc_links)
+)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/open-telemetry/opentelemetry-rust/main/assets/logo.svg"
+)]
+#![cfg_attr(test, deny(warnings))]
+
+mod page;
+mod server;
+mod tracez;
+
+pub use server::{ZPagesServer, ZPagesServerBuilder};
+pub use tracez::{TracezQuerier, ZPagesSpanProcessor, LATENCY_BUCKETS};
@@ -0,0 +1,334 @@
+//! HTML rendering of the zPages.
+use crate::tracez::{TracezQuerier, LATENCY_BUCKETS};
+use opentelemetry::trace::Status;
+use opentelemetry::KeyValue;
+use opentelemetry_proto::tonic::tracez::v1::TracezCounts;
+use opentelemetry_sdk::export::trace::SpanData;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#eee}td.count{text-align:right}";
+
+/// The kinds of spans listed on the tracez page.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SpanList {
+    Running,
+    Latency(usize),
+    Error,
+}
+
+pub(crate) fn index() -> String {
+    page(
+        "zPages",
+        "<ul><li><a href=\"/tracez\">tracez</a>: running spans, latency and error samples</li>\
+         <li><a href=\"/statusz\">statusz</a>: process and SDK configuration</li></ul>",
+    )
+}
+
+pub(crate) fn tracez_summary(counts: &[TracezCounts]) -> String {
+    let mut body = String::from("<table><tr><th>Span name</th><th>Running</th>");
+    for bound in LATENCY_BUCKETS {
+        let _ = write!(body, "<th>&ge; {}</th>", escape(&format!("{bound:?}")));
+    }
+    body.push_str("<th>Errors</th></tr>");
+
+    for span in counts {
+        let name = &span.spanname;
+        let _ = write!(body, "<tr><td>{}</td>", escape(name));
+        body.push_str(&count_cell(name, "running", span.running));
+        for (bucket, count) in span.latency.iter().enumerate() {
+            let kind = format!("latency&amp;bucket={bucket}");
+            body.push_str(&count_cell(name, &kind, *count));
+        }
+        body.push_str(&count_cell(name, "error", span.error));
+        body.push_str("</tr>");
+    }
+    body.push_str("</table>");
+    if counts.is_empty() {
+        body.push_str("<p>No spans recorded yet.</p>");
+    }
+    page("tracez", &body)
+}
+
+fn count_cell(name: &str, kind: &str, count: u32) -> String {
+    if count == 0 {
+        return "<td class=\"count\">0</td>".to_string();
+    }
+    format!(
+        "<td class=\"count\"><a href=\"/tracez?name={}&amp;type={kind}\">{count}</a></td>",
+        percent_encode(name)
+    )
+}
+
+pub(crate) fn span_list(name: &str, list: SpanList, spans: &[SpanData]) -> String {
+    let title = match list {
+        SpanList::Running => format!("Running spans of {}", escape(name)),
+        SpanList::Latency(bucket) => format!(
+            "Spans of {} taking {:?} or more",
+            escape(name),
+            LATENCY_BUCKETS[bucket]
+        ),
+        SpanList::Error => format!("Error spans of {}", escape(name)),
+    };
+
+    let mut body = format!("<p><a href=\"/tracez\">Back to all spans</a></p><h2>{title}</h2>");
+    body.push_str(
+        "<table><tr><th>Start time</th><th>Duration</th><th>Trace ID</th><th>Span ID</th>\
+         <th>Parent span ID</th><th>Kind</th><th>Status</th><th>Attributes</th><th>Events</th>\
+         <th>Links</th></tr>",
+    );
+    for span in spans {
+        let duration = match list {
+            SpanList::Running => "running".to_string(),
+            _ => format!(
+                "{:?}",
+                span.end_time
+                    .duration_since(span.start_time)
+                    .unwrap_or_default()
+            ),
+        };
+        let status = match &span.status {
+            Status::Unset => "Unset".to_string(),
+            Status::Ok => "Ok".to_string(),
+            Status::Error { description } => format!("Error: {description}"),
+        };
+        let events: Vec<String> = span
+            .events
+            .iter()
+            .map(|event| {
+                format!(
+                    "{} {} {}",
+                    format_time(event.timestamp),
+                    event.name,
+                    attributes(&event.attributes)
+                )
+            })
+            .collect();
+        let links: Vec<String> = span
+            .links
+            .iter()
+            .map(|link| {
+                format!(
+                    "{}/{}",
+                    link.span_context.trace_id(),
+                    link.span_context.span_id()
+                )
+            })
+            .collect();
+        let _ = write!(
+            body,
+            "<tr><td>{}</td><td>{}</td><td><code>{}</code></td><td><code>{}</code></td>\
+             <td><code>{}</code></td><td>{:?}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            format_time(span.start_time),
+            duration,
+            span.span_context.trace_id(),
+            span.span_context.span_id(),
+            span.parent_span_id,
+            span.span_kind,
+            escape(&status),
+            escape(&attributes(&span.attributes)).replace(", ", "<br>"),
+            escape(&events.join("\n")).replace('\n', "<br>"),
+            links.join("<br>"),
+        );
+    }
+    body.push_str("</table>");
+    if spans.is_empty() {
+        body.push_str("<p>No sampled spans.</p>");
+    }
+    page("tracez", &body)
+}
+
+fn attributes(attributes: &[KeyValue]) -> String {
+    attributes
+        .iter()
+        .map(|kv| format!("{}={}", kv.key, kv.value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn statusz(querier: &TracezQuerier, status: &[(String, String)]) -> String {
+    let now = opentelemetry::time::now();
+    let started = querier.started();
+    let mut body = String::new();
+
+    section(
+        &mut body,
+        "Process",
+        [
+            ("Process ID".to_string(), std::process::id().to_string()),
+            ("Started".to_string(), format_time(started)),
+            (
+                "Uptime".to_string(),
+                format!(
+                    "{}s",
+                    now.duration_since(started).unwrap_or_default().as_secs()
+                ),
+            ),
+        ],
+    );
+
+    let resource = querier.resource().map(|resource| {
+        let mut attributes: Vec<_> = resource
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        attributes.sort();
+        attributes
+    });
+    match resource {
+        Some(attributes) => section(&mut body, "Resource", attributes),
+        None => body.push_str("<h2>Resource</h2><p>The processor is not added to a provider.</p>"),
+    }
+
+    section(
+        &mut body,
+        "tracez",
+        [(
+            "Samples per latency bucket and for errors".to_string(),
+            querier.sample_size().to_string(),
+        )],
+    );
+
+    if !status.is_empty() {
+        section(&mut body, "Configuration", status.iter().cloned());
+    }
+
+    let mut variables: Vec<_> = std::env::vars()
+        .filter(|(name, _)| name.starts_with("OTEL_"))
+        .map(|(name, value)| {
+            // headers, keys, tokens, passwords and secrets hold credentials
+            let value = if is_sensitive(&name) {
+                "<redacted>".to_string()
+            } else {
+                value
+            };
+            (name, value)
+        })
+        .collect();
+    variables.sort();
+    section(&mut body, "Environment", variables);
+
+    page("statusz", &body)
+}
+
+fn is_sensitive(name: &str) -> bool {
+    ["HEADERS", "_KEY", "_TOKEN", "_PASSWORD", "_SECRET"]
+        .iter()
+        .any(|part| name.contains(part))
+}
+
+fn section(body: &mut String, title: &str, rows: impl IntoIterator<Item = (String, String)>) {
+    let _ = write!(body, "<h2>{title}</h2><table>");
+    for (name, value) in rows {
+        let _ = write!(
+            body,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape(&name),
+            escape(&value)
+        );
+    }
+    body.push_str("</table>");
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title>\
+         <style>{STYLE}</style></head><body><h1>{title}</h1>{body}</body></html>"
+    )
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Formats `time` as an RFC 3339 UTC timestamp with microseconds.
+fn format_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:06}Z",
+        secs / 3_600 % 24,
+        secs / 60 % 60,
+        secs % 60,
+        since_epoch.subsec_micros()
+    )
+}
+
+/// Converts days since the Unix epoch to a proleptic Gregorian date, after
+/// Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn times_are_formatted_as_rfc3339() {
+        assert_eq!(format_time(UNIX_EPOCH), "1970-01-01T00:00:00.000000Z");
+        let time = UNIX_EPOCH + Duration::from_micros(1_709_210_096_123_456);
+        assert_eq!(format_time(time), "2024-02-29T12:34:56.123456Z");
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        assert_eq!(
+            escape("<a href=\"x\">'&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+        assert_eq!(percent_encode("GET /users?id"), "GET%20%2Fusers%3Fid");
+    }
+
+    #[test]
+    fn credentials_are_recognized() {
+        for name in [
+            "OTEL_EXPORTER_OTLP_HEADERS",
+            "OTEL_EXPORTER_OTLP_CLIENT_KEY",
+            "OTEL_EXPORTER_API_TOKEN",
+            "OTEL_EXPORTER_BASIC_AUTH_PASSWORD",
+            "OTEL_EXPORTER_CLIENT_SECRET",
+        ] {
+            assert!(is_sensitive(name), "{name}");
+        }
+        assert!(!is_sensitive("OTEL_SERVICE_NAME"));
+        assert!(!is_sensitive("OTEL_TRACES_SAMPLER"));
+    }
+}
@@ -0,0 +1,405 @@
+//! A small HTTP server for the zPages, running on its own thread.
+use crate::page::{self, SpanList};
+use crate::tracez::{TracezQuerier, LATENCY_BUCKETS};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{
+    header::{self, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:55679";
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_CONNECTIONS: usize = 64;
+const PROTOBUF: &str = "application/x-protobuf";
+
+/// Configures and starts a [`ZPagesServer`].
+#[derive(Debug)]
+pub struct ZPagesServerBuilder {
+    querier: TracezQuerier,
+    address: String,
+    status: Vec<(String, String)>,
+}
+
+impl ZPagesServerBuilder {
+    /// Sets the address to listen on, `127.0.0.1:55679` by default.
+    ///
+    /// The pages expose span attributes and the process configuration, and
+    /// have no authentication: only listen on interfaces reachable by the
+    /// people allowed to see them.
+    pub fn with_address(mut self, address: impl Into<String>) -> Self {
+        self.address = address.into();
+        self
+    }
+
+    /// Adds an entry to the configuration section of `/statusz`, e.g. the
+    /// sampler or the exporter endpoint.
+    pub fn with_status(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.status.push((name.into(), value.into()));
+        self
+    }
+
+    /// Binds the address and starts serving the pages on a new thread.
+    pub fn start(self) -> io::Result<ZPagesServer> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let listener = std::net::TcpListener::bind(&self.address)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        let listener = {
+            let _guard = runtime.enter();
+            TcpListener::from_std(listener)?
+        };
+        let pages = Arc::new(Pages {
+            querier: self.querier,
+            status: self.status,
+        });
+        let (stop, stopped) = oneshot::channel();
+
+        let handle = thread::Builder::new()
+            .name("OpenTelemetry.ZPages".to_string())
+            .spawn(move || serve(runtime, listener, pages, stopped))?;
+
+        Ok(ZPagesServer {
+            local_addr,
+            stop: Some(stop),
+            handle: Some(handle),
+        })
+    }
+}
+
+/// Accepts connections until `stopped` resolves, serving each connection on
+/// its own task. Dropping the runtime closes the open connections.
+fn serve(
+    runtime: Runtime,
+    listener: TcpListener,
+    pages: Arc<Pages>,
+    stopped: oneshot::Receiver<()>,
+) {
+    runtime.block_on(async move {
+        let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        tokio::pin!(stopped);
+        loop {
+            let accepted = tokio::select! {
+                _ = &mut stopped => break,
+                accepted = accept(&listener, &connections) => accepted,
+            };
+            // a failing connection only affects its client
+            let Some((stream, permit)) = accepted else {
+                continue;
+            };
+            let pages = pages.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let response = pages.respond(&request);
+                    async move { Ok::<_, Infallible>(response) }
+                });
+                let connection = http1::Builder::new()
+                    .keep_alive(false)
+                    .serve_connection(TokioIo::new(stream), service);
+                let _ = tokio::time::timeout(CONNECTION_TIMEOUT, connection).await;
+                drop(permit);
+            });
+        }
+    });
+}
+
+async fn accept(
+    listener: &TcpListener,
+    connections: &Arc<Semaphore>,
+) -> Option<(TcpStream, OwnedSemaphorePermit)> {
+    // the semaphore is never closed
+    let permit = connections.clone().acquire_owned().await.ok()?;
+    let (stream, _) = listener.accept().await.ok()?;
+    Some((stream, permit))
+}
+
+/// An HTTP server for the zPages of a process.
+///
+/// It serves, for `GET` and `HEAD` requests:
+///
+/// * `/tracez`: the running, error and latency bucket span counts of every
+///   span name, each linking to the sampled spans with the `name`, `type`
+///   (`running`, `error` or `latency`) and, for latency buckets, `bucket`
+///   query parameters, e.g. `/tracez?name=GET%20%2Fusers&type=latency&bucket=3`.
+/// * `/statusz`: the process, the resource of the provider, the entries added
+///   with [`ZPagesServerBuilder::with_status`] and the `OTEL_*` environment
+///   variables, with the values of the variables holding headers, keys,
+///   tokens, passwords or secrets redacted.
+///
+/// `/tracez` responses are HTML pages, or with the `format=proto` query
+/// parameter or an `Accept: application/x-protobuf` header, a sequence of
+/// length-delimited [`TracezCounts`], [`RunningData`], [`ErrorData`] or
+/// [`LatencyData`] messages.
+///
+/// The server is a [hyper] HTTP/1 server on a single-threaded runtime of its
+/// own thread. Each connection serves one request, on its own task, and is
+/// closed after 10 seconds; at most 64 connections are served at once. The
+/// server stops on [`ZPagesServer::shutdown`] or when dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use opentelemetry_sdk::trace::TracerProvider;
+/// use opentelemetry_zpages::{ZPagesServer, ZPagesSpanProcessor};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let processor = ZPagesSpanProcessor::new();
+/// let server = ZPagesServer::builder(processor.querier())
+///     .with_address("127.0.0.1:8888")
+///     .with_status("sampler", "ParentBased(TraceIdRatioBased(0.1))")
+///     .start()?;
+/// println!("zPages on http://{}/tracez", server.local_addr());
+///
+/// let provider = TracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`TracezCounts`]: opentelemetry_proto::tonic::tracez::v1::TracezCounts
+/// [`RunningData`]: opentelemetry_proto::tonic::tracez::v1::RunningData
+/// [`ErrorData`]: opentelemetry_proto::tonic::tracez::v1::ErrorData
+/// [`LatencyData`]: opentelemetry_proto::tonic::tracez::v1::LatencyData
+#[derive(Debug)]
+pub struct ZPagesServer {
+    local_addr: SocketAddr,
+    stop: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ZPagesServer {
+    /// Create a [`ZPagesServerBuilder`] serving the spans read by `querier`.
+    pub fn builder(querier: TracezQuerier) -> ZPagesServerBuilder {
+        ZPagesServerBuilder {
+            querier,
+            address: DEFAULT_ADDRESS.to_string(),
+            status: Vec::new(),
+        }
+    }
+
+    /// The address the server listens on, useful when binding port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops the server, closing the open connections, and waits for its
+    /// thread to end.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            // the thread only ends once stopped
+            let _ = stop.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ZPagesServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn html(body: String) -> Response<Full<Bytes>> {
+    response(
+        StatusCode::OK,
+        "text/html; charset=utf-8",
+        body.into_bytes(),
+    )
+}
+
+fn protobuf<M: prost::Message>(messages: &[M]) -> Response<Full<Bytes>> {
+    let mut body = Vec::new();
+    for message in messages {
+        // writing to a Vec never fails
+        let _ = message.encode_length_delimited(&mut body);
+    }
+    response(StatusCode::OK, PROTOBUF, body)
+}
+
+fn error(status: StatusCode) -> Response<Full<Bytes>> {
+    response(
+        status,
+        "text/plain; charset=utf-8",
+        status.to_string().into_bytes(),
+    )
+}
+
+fn response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: Vec<u8>,
+) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+#[derive(Debug)]
+struct Pages {
+    querier: TracezQuerier,
+    status: Vec<(String, String)>,
+}
+
+impl Pages {
+    fn respond<B>(&self, request: &Request<B>) -> Response<Full<Bytes>> {
+        if request.method() != Method::GET && request.method() != Method::HEAD {
+            let mut response = error(StatusCode::METHOD_NOT_ALLOWED);
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static("GET, HEAD"));
+            return response;
+        }
+        match request.uri().path() {
+            "/" => html(page::index()),
+            "/tracez" => self.tracez(request),
+            "/statusz" => html(page::statusz(&self.querier, &self.status)),
+            _ => error(StatusCode::NOT_FOUND),
+        }
+    }
+
+    fn tracez<B>(&self, request: &Request<B>) -> Response<Full<Bytes>> {
+        let query = Query::parse(request.uri().query().unwrap_or_default());
+        let protobuf_requested = query.param("format") == Some("proto")
+            || request
+                .headers()
+                .get_all(header::ACCEPT)
+                .iter()
+                .filter_map(|accept| accept.to_str().ok())
+                .any(|accept| accept.contains(PROTOBUF));
+        let Some(name) = query.param("name") else {
+            let counts = self.querier.aggregations();
+            return if protobuf_requested {
+                protobuf(&counts)
+            } else {
+                html(page::tracez_summary(&counts))
+            };
+        };
+
+        let list = match query.param("type") {
+            Some("running") => SpanList::Running,
+            Some("error") => SpanList::Error,
+            Some("latency") => match query
+                .param("bucket")
+                .and_then(|bucket| bucket.parse().ok())
+                .filter(|bucket| *bucket < LATENCY_BUCKETS.len())
+            {
+                Some(bucket) => SpanList::Latency(bucket),
+                None => return error(StatusCode::BAD_REQUEST),
+            },
+            _ => return error(StatusCode::BAD_REQUEST),
+        };
+
+        match (list, protobuf_requested) {
+            (SpanList::Running, true) => protobuf(&self.querier.running(name)),
+            (SpanList::Error, true) => protobuf(&self.querier.errors(name)),
+            (SpanList::Latency(bucket), true) => protobuf(&self.querier.latencies(name, bucket)),
+            (SpanList::Running, false) => html(page::span_list(
+                name,
+                list,
+                &self.querier.running_spans(name),
+            )),
+            (SpanList::Error, false) => {
+                html(page::span_list(name, list, &self.querier.error_spans(name)))
+            }
+            (SpanList::Latency(bucket), false) => html(page::span_list(
+                name,
+                list,
+                &self.querier.latency_spans(name, bucket),
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Query(Vec<(String, String)>);
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        Query(
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    (
+                        percent_decode(name).into_owned(),
+                        percent_decode(value).into_owned(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn percent_decode(text: &str) -> Cow<'_, str> {
+    if !text.contains(['%', '+']) {
+        return Cow::Borrowed(text);
+    }
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = text
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match hex {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    Cow::Owned(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_values_are_percent_decoded() {
+        assert_eq!(percent_decode("GET%20%2Fusers"), "GET /users");
+        assert_eq!(percent_decode("a+b%"), "a b%");
+        assert_eq!(percent_decode("%zz%e2%82%ac"), "%zz€");
+    }
+}
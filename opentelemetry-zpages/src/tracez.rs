@@ -0,0 +1,453 @@
+//! The span processor aggregating the spans shown on the tracez page.
+use opentelemetry::trace::{SpanId, Status, TraceId, TraceResult};
+use opentelemetry::Context;
+use opentelemetry_proto::tonic::tracez::v1::{ErrorData, LatencyData, RunningData, TracezCounts};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::trace::{Span, SpanProcessor};
+use opentelemetry_sdk::Resource;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// The lower bounds of the latency buckets of the tracez page, each bucket
+/// ending where the next one starts.
+pub const LATENCY_BUCKETS: [Duration; 9] = [
+    Duration::ZERO,
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+    Duration::from_secs(100),
+];
+
+const DEFAULT_SAMPLE_SIZE: usize = 5;
+const MAX_SPAN_NAMES: usize = 1000;
+const OVERFLOW_SPAN_NAME: &str = "otel.zpages.overflow";
+
+#[derive(Debug, Default)]
+struct SpanSummary {
+    latency_counts: [u32; LATENCY_BUCKETS.len()],
+    latency_samples: [VecDeque<SpanData>; LATENCY_BUCKETS.len()],
+    error_count: u32,
+    error_samples: VecDeque<SpanData>,
+}
+
+#[derive(Debug)]
+struct Aggregation {
+    sample_size: usize,
+    running: HashMap<(TraceId, SpanId), SpanData>,
+    summaries: BTreeMap<Cow<'static, str>, SpanSummary>,
+}
+
+impl Aggregation {
+    fn record(&mut self, span: SpanData) {
+        self.running
+            .remove(&(span.span_context.trace_id(), span.span_context.span_id()));
+
+        let sample_size = self.sample_size;
+        let name =
+            if self.summaries.len() < MAX_SPAN_NAMES || self.summaries.contains_key(&span.name) {
+                span.name.clone()
+            } else {
+                Cow::Borrowed(OVERFLOW_SPAN_NAME)
+            };
+        let summary = self.summaries.entry(name).or_default();
+        let samples = if matches!(span.status, Status::Error { .. }) {
+            summary.error_count = summary.error_count.saturating_add(1);
+            &mut summary.error_samples
+        } else {
+            let bucket = latency_bucket(&span);
+            summary.latency_counts[bucket] = summary.latency_counts[bucket].saturating_add(1);
+            &mut summary.latency_samples[bucket]
+        };
+        if sample_size > 0 {
+            if samples.len() >= sample_size {
+                samples.pop_front();
+            }
+            samples.push_back(span);
+        }
+    }
+}
+
+fn latency_bucket(span: &SpanData) -> usize {
+    let latency = span
+        .end_time
+        .duration_since(span.start_time)
+        .unwrap_or_default();
+    LATENCY_BUCKETS
+        .iter()
+        .rposition(|bound| latency >= *bound)
+        .unwrap_or(0)
+}
+
+#[derive(Debug)]
+struct TracezState {
+    aggregation: Mutex<Aggregation>,
+    resource: Mutex<Option<Resource>>,
+    started: SystemTime,
+}
+
+/// A [`SpanProcessor`] keeping the spans of a process for the tracez page.
+///
+/// It tracks the running spans, and for each span name, counts the ended
+/// spans per latency bucket, see [`LATENCY_BUCKETS`], and the spans ended
+/// with an error status. The last few spans of each latency bucket and of the
+/// errors are kept as samples. Once 1000 span names are summarized, the spans
+/// of further names are summarized under `otel.zpages.overflow`.
+///
+/// Running spans are copied when they start, so their attributes, events and
+/// links are those set before the processor saw them. Copying every span and
+/// sharing one lock across threads has a cost, the processor is meant for
+/// diagnostics rather than high-throughput production paths.
+///
+/// The aggregated spans are read through a [`TracezQuerier`], which is
+/// usually served by a [`ZPagesServer`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use opentelemetry_sdk::trace::TracerProvider;
+/// use opentelemetry_zpages::{ZPagesServer, ZPagesSpanProcessor};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let processor = ZPagesSpanProcessor::new().with_sample_size(10);
+/// let server = ZPagesServer::builder(processor.querier()).start()?;
+///
+/// let provider = TracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`ZPagesServer`]: crate::ZPagesServer
+#[derive(Debug)]
+pub struct ZPagesSpanProcessor {
+    state: Arc<TracezState>,
+}
+
+impl Default for ZPagesSpanProcessor {
+    fn default() -> Self {
+        ZPagesSpanProcessor {
+            state: Arc::new(TracezState {
+                aggregation: Mutex::new(Aggregation {
+                    sample_size: DEFAULT_SAMPLE_SIZE,
+                    running: HashMap::new(),
+                    summaries: BTreeMap::new(),
+                }),
+                resource: Mutex::new(None),
+                started: opentelemetry::time::now(),
+            }),
+        }
+    }
+}
+
+impl ZPagesSpanProcessor {
+    /// Create a new `ZPagesSpanProcessor` keeping 5 samples per latency bucket
+    /// and 5 error samples for each span name.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of spans kept per latency bucket, and of error spans,
+    /// for each span name.
+    pub fn with_sample_size(self, sample_size: usize) -> Self {
+        if let Ok(mut aggregation) = self.state.aggregation.lock() {
+            aggregation.sample_size = sample_size;
+        }
+        self
+    }
+
+    /// A handle reading the aggregated spans, which can be kept after the
+    /// processor is moved into a provider.
+    pub fn querier(&self) -> TracezQuerier {
+        TracezQuerier {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl SpanProcessor for ZPagesSpanProcessor {
+    fn on_start(&self, span: &mut Span, _cx: &Context) {
+        let Some(data) = span.exported_data() else {
+            return;
+        };
+        if let Ok(mut aggregation) = self.state.aggregation.lock() {
+            let id = (data.span_context.trace_id(), data.span_context.span_id());
+            aggregation.running.insert(id, data);
+        }
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if let Ok(mut aggregation) = self.state.aggregation.lock() {
+            aggregation.record(span);
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        if let Ok(mut current) = self.state.resource.lock() {
+            *current = Some(resource.clone());
+        }
+    }
+}
+
+/// Reads the spans aggregated by a [`ZPagesSpanProcessor`] as tracez protos.
+///
+/// Span names are matched exactly, and latency buckets are indexes in
+/// [`LATENCY_BUCKETS`]. Unknown names and buckets give no spans.
+#[derive(Clone, Debug)]
+pub struct TracezQuerier {
+    state: Arc<TracezState>,
+}
+
+impl TracezQuerier {
+    /// The span counts of every span name, ordered by name.
+    pub fn aggregations(&self) -> Vec<TracezCounts> {
+        let Ok(aggregation) = self.state.aggregation.lock() else {
+            return Vec::new();
+        };
+        let mut counts: BTreeMap<&str, TracezCounts> = aggregation
+            .summaries
+            .iter()
+            .map(|(name, summary)| {
+                let counts = TracezCounts {
+                    spanname: name.to_string(),
+                    latency: summary.latency_counts.to_vec(),
+                    running: 0,
+                    error: summary.error_count,
+                };
+                (name.as_ref(), counts)
+            })
+            .collect();
+        for span in aggregation.running.values() {
+            counts
+                .entry(span.name.as_ref())
+                .or_insert_with(|| TracezCounts {
+                    spanname: span.name.to_string(),
+                    latency: vec![0; LATENCY_BUCKETS.len()],
+                    running: 0,
+                    error: 0,
+                })
+                .running += 1;
+        }
+        counts.into_values().collect()
+    }
+
+    /// The running spans named `span_name`, as they were when they started.
+    pub fn running(&self, span_name: &str) -> Vec<RunningData> {
+        self.running_spans(span_name)
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// The sampled spans named `span_name` that ended with an error status.
+    pub fn errors(&self, span_name: &str) -> Vec<ErrorData> {
+        self.error_spans(span_name)
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// The sampled spans named `span_name` that ended without an error, with
+    /// a latency in the given bucket.
+    pub fn latencies(&self, span_name: &str, bucket: usize) -> Vec<LatencyData> {
+        self.latency_spans(span_name, bucket)
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    pub(crate) fn running_spans(&self, span_name: &str) -> Vec<SpanData> {
+        let Ok(aggregation) = self.state.aggregation.lock() else {
+            return Vec::new();
+        };
+        let mut spans: Vec<_> = aggregation
+            .running
+            .values()
+            .filter(|span| span.name == span_name)
+            .cloned()
+            .collect();
+        spans.sort_by_key(|span| span.start_time);
+        spans
+    }
+
+    pub(crate) fn error_spans(&self, span_name: &str) -> Vec<SpanData> {
+        self.samples(span_name, |summary| &summary.error_samples)
+    }
+
+    pub(crate) fn latency_spans(&self, span_name: &str, bucket: usize) -> Vec<SpanData> {
+        if bucket >= LATENCY_BUCKETS.len() {
+            return Vec::new();
+        }
+        self.samples(span_name, |summary| &summary.latency_samples[bucket])
+    }
+
+    fn samples<F>(&self, span_name: &str, select: F) -> Vec<SpanData>
+    where
+        F: FnOnce(&SpanSummary) -> &VecDeque<SpanData>,
+    {
+        let Ok(aggregation) = self.state.aggregation.lock() else {
+            return Vec::new();
+        };
+        aggregation
+            .summaries
+            .get(span_name)
+            .map(|summary| select(summary).iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn sample_size(&self) -> usize {
+        self.state
+            .aggregation
+            .lock()
+            .map(|aggregation| aggregation.sample_size)
+            .unwrap_or_default()
+    }
+
+    /// The resource of the provider the processor was added to.
+    pub(crate) fn resource(&self) -> Option<Resource> {
+        self.state
+            .resource
+            .lock()
+            .ok()
+            .and_then(|resource| resource.clone())
+    }
+
+    pub(crate) fn started(&self) -> SystemTime {
+        self.state.started
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{Span as _, Tracer, TracerProvider as _};
+    use opentelemetry_sdk::trace::TracerProvider;
+
+    fn provider(processor: ZPagesSpanProcessor) -> TracerProvider {
+        TracerProvider::builder()
+            .with_span_processor(processor)
+            .build()
+    }
+
+    fn end_after(tracer: &impl Tracer, name: &'static str, latency: Duration, error: bool) {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let mut span = tracer
+            .span_builder(name)
+            .with_start_time(start)
+            .start(tracer);
+        if error {
+            span.set_status(Status::error("failed"));
+        }
+        span.end_with_timestamp(start + latency);
+    }
+
+    #[test]
+    fn ended_spans_are_counted_per_latency_bucket() {
+        let processor = ZPagesSpanProcessor::new();
+        let querier = processor.querier();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        end_after(&tracer, "get", Duration::from_micros(5), false);
+        end_after(&tracer, "get", Duration::from_millis(2), false);
+        end_after(&tracer, "get", Duration::from_millis(3), false);
+        end_after(&tracer, "get", Duration::from_secs(500), false);
+        end_after(&tracer, "get", Duration::from_millis(3), true);
+
+        let counts = querier.aggregations();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].spanname, "get");
+        assert_eq!(counts[0].latency, [1, 0, 0, 2, 0, 0, 0, 0, 1]);
+        assert_eq!(counts[0].error, 1);
+        assert_eq!(counts[0].running, 0);
+        assert_eq!(querier.latencies("get", 3).len(), 2);
+        assert_eq!(querier.errors("get").len(), 1);
+        assert!(querier.latencies("get", LATENCY_BUCKETS.len()).is_empty());
+        assert!(querier.errors("put").is_empty());
+    }
+
+    #[test]
+    fn samples_keep_the_last_spans() {
+        let processor = ZPagesSpanProcessor::new().with_sample_size(2);
+        let querier = processor.querier();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        for latency in 1..=4 {
+            end_after(&tracer, "get", Duration::from_millis(latency), false);
+        }
+
+        assert_eq!(querier.aggregations()[0].latency[3], 4);
+        let samples = querier.latency_spans("get", 3);
+        let latencies: Vec<_> = samples
+            .iter()
+            .map(|span| span.end_time.duration_since(span.start_time).unwrap())
+            .collect();
+        assert_eq!(
+            latencies,
+            [Duration::from_millis(3), Duration::from_millis(4)]
+        );
+    }
+
+    #[test]
+    fn span_names_beyond_the_limit_share_a_summary() {
+        let processor = ZPagesSpanProcessor::new();
+        let querier = processor.querier();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        for i in 0..MAX_SPAN_NAMES + 10 {
+            tracer.start(format!("span-{i}")).end();
+        }
+        tracer.start("span-0").end();
+
+        let counts = querier.aggregations();
+        assert_eq!(counts.len(), MAX_SPAN_NAMES + 1);
+        let overflow = counts
+            .iter()
+            .find(|counts| counts.spanname == OVERFLOW_SPAN_NAME)
+            .unwrap();
+        assert_eq!(overflow.latency.iter().sum::<u32>(), 10);
+        let first = counts
+            .iter()
+            .find(|counts| counts.spanname == "span-0")
+            .unwrap();
+        assert_eq!(first.latency.iter().sum::<u32>(), 2);
+    }
+
+    #[test]
+    fn running_spans_are_tracked_until_they_end() {
+        let processor = ZPagesSpanProcessor::new();
+        let querier = processor.querier();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        let mut first = tracer.start("query");
+        let second = tracer.start("query");
+        assert_eq!(querier.aggregations()[0].running, 2);
+        assert_eq!(querier.running("query").len(), 2);
+
+        first.end();
+        let counts = querier.aggregations();
+        assert_eq!(counts[0].running, 1);
+        assert_eq!(counts[0].latency.iter().sum::<u32>(), 1);
+        assert_eq!(
+            querier.running("query")[0].spanid,
+            second.span_context().span_id().to_bytes()
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use opentelemetry::trace::{Span, Status, Tracer, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_proto::tonic::tracez::v1::{ErrorData, RunningData, TracezCounts};
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::Resource;
+use opentelemetry_zpages::{ZPagesServer, ZPagesSpanProcessor};
+use prost::Message;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+
+fn get(addr: SocketAddr, target: &str, headers: &str) -> (String, Vec<u8>) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "GET {target} HTTP/1.1\r\nHost: localhost\r\n{headers}\r\n"
+    )
+    .unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .unwrap();
+    let body = response.split_off(split + 4);
+    (String::from_utf8(response).unwrap(), body)
+}
+
+fn decode_all<M: Message + Default>(mut body: &[u8]) -> Vec<M> {
+    let mut messages = Vec::new();
+    while !body.is_empty() {
+        messages.push(M::decode_length_delimited(&mut body).unwrap());
+    }
+    messages
+}
+
+#[test]
+fn serves_tracez_and_statusz() {
+    let processor = ZPagesSpanProcessor::new();
+    let server = ZPagesServer::builder(processor.querier())
+        .with_address("127.0.0.1:0")
+        .with_status("exporter", "otlp <grpc>")
+        .start()
+        .unwrap();
+    let addr = server.local_addr();
+    let provider = TracerProvider::builder()
+        .with_resource(Resource::new([KeyValue::new("service.name", "checkout")]))
+        .with_span_processor(processor)
+        .build();
+    let tracer = provider.tracer("test");
+
+    let _running = tracer.start("GET /cart");
+    let mut failed = tracer.start("GET /cart");
+    failed.set_attribute(KeyValue::new("user", "<admin>"));
+    failed.set_status(Status::error("timeout"));
+    failed.end();
+
+    let (head, body) = get(addr, "/tracez", "");
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(head.contains("content-type: text/html"));
+    let html = String::from_utf8(body).unwrap();
+    assert!(html.contains("GET /cart"));
+    assert!(html.contains("/tracez?name=GET%20%2Fcart&amp;type=running"));
+
+    let (head, body) = get(addr, "/tracez?format=proto", "");
+    assert!(head.contains("content-type: application/x-protobuf"));
+    let counts: Vec<TracezCounts> = decode_all(&body);
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].spanname, "GET /cart");
+    assert_eq!((counts[0].running, counts[0].error), (1, 1));
+
+    let (_, body) = get(
+        addr,
+        "/tracez?name=GET+%2Fcart&type=running",
+        "Accept: application/x-protobuf\r\n",
+    );
+    let running: Vec<RunningData> = decode_all(&body);
+    assert_eq!(running.len(), 1);
+
+    let (_, body) = get(
+        addr,
+        "/tracez?name=GET%20%2Fcart&type=error&format=proto",
+        "",
+    );
+    let errors: Vec<ErrorData> = decode_all(&body);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].status.as_ref().unwrap().message, "timeout");
+
+    let (_, body) = get(addr, "/tracez?name=GET%20%2Fcart&type=error", "");
+    let html = String::from_utf8(body).unwrap();
+    assert!(html.contains("user=&lt;admin&gt;"));
+    assert!(html.contains("Error: timeout"));
+
+    let (head, _) = get(addr, "/tracez?name=GET%20%2Fcart&type=latency&bucket=9", "");
+    assert!(head.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+    let (_, body) = get(addr, "/statusz", "");
+    let html = String::from_utf8(body).unwrap();
+    assert!(html.contains("<th>service.name</th><td>checkout</td>"));
+    assert!(html.contains("<th>exporter</th><td>otlp &lt;grpc&gt;</td>"));
+
+    let (head, _) = get(addr, "/unknown", "");
+    assert!(head.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    server.shutdown();
+    assert!(TcpStream::connect(addr).is_err());
+}
+
+#[test]
+fn slow_clients_do_not_stall_the_server() {
+    let processor = ZPagesSpanProcessor::new();
+    let server = ZPagesServer::builder(processor.querier())
+        .with_address("127.0.0.1:0")
+        .start()
+        .unwrap();
+    let addr = server.local_addr();
+
+    // a client that never sends its request
+    let mut idle = TcpStream::connect(addr).unwrap();
+    write!(idle, "GET /tracez HTTP/1.1\r\n").unwrap();
+
+    let (head, _) = get(addr, "/statusz", "");
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+
+    // shutting down closes the idle connection
+    server.shutdown();
+    let mut response = Vec::new();
+    assert_eq!(idle.read_to_end(&mut response).unwrap_or_default(), 0);
+}
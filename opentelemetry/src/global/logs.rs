@@ -0,0 +1,417 @@
+use crate::logs::{
+    self, AnyValue, LogRecord, Logger, LoggerProvider, NoopLoggerProvider, Severity,
+};
+#[cfg(feature = "trace")]
+use crate::trace::{SpanId, TraceFlags, TraceId};
+use crate::{InstrumentationScope, Key};
+use once_cell::sync::Lazy;
+use std::any::Any;
+use std::borrow::Cow;
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+/// Allows a specific [`LogRecord`] to be used generically by [`BoxedLogRecord`]
+/// instances by mirroring the interface with concrete argument types.
+pub trait ObjectSafeLogRecord {
+    /// Sets the `event_name` of a record.
+    fn set_event_name(&mut self, name: &'static str);
+
+    /// Sets the `target` of a record.
+    fn set_target(&mut self, target: Cow<'static, str>);
+
+    /// Sets the time when the event occurred measured by the origin clock.
+    fn set_timestamp(&mut self, timestamp: SystemTime);
+
+    /// Sets the observed event timestamp.
+    fn set_observed_timestamp(&mut self, timestamp: SystemTime);
+
+    /// Sets severity as text.
+    fn set_severity_text(&mut self, text: &'static str);
+
+    /// Sets severity as a numeric value.
+    fn set_severity_number(&mut self, number: Severity);
+
+    /// Sets the message body of the log.
+    fn set_body(&mut self, body: AnyValue);
+
+    /// Adds a single attribute.
+    fn add_attribute(&mut self, key: Key, value: AnyValue);
+
+    /// Sets the trace context of the log.
+    #[cfg(feature = "trace")]
+    fn set_trace_context(
+        &mut self,
+        trace_id: TraceId,
+        span_id: SpanId,
+        trace_flags: Option<TraceFlags>,
+    );
+
+    /// Converts the record back into its concrete type when it is emitted.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<R: LogRecord + 'static> ObjectSafeLogRecord for R {
+    fn set_event_name(&mut self, name: &'static str) {
+        LogRecord::set_event_name(self, name)
+    }
+
+    fn set_target(&mut self, target: Cow<'static, str>) {
+        LogRecord::set_target(self, target)
+    }
+
+    fn set_timestamp(&mut self, timestamp: SystemTime) {
+        LogRecord::set_timestamp(self, timestamp)
+    }
+
+    fn set_observed_timestamp(&mut self, timestamp: SystemTime) {
+        LogRecord::set_observed_timestamp(self, timestamp)
+    }
+
+    fn set_severity_text(&mut self, text: &'static str) {
+        LogRecord::set_severity_text(self, text)
+    }
+
+    fn set_severity_number(&mut self, number: Severity) {
+        LogRecord::set_severity_number(self, number)
+    }
+
+    fn set_body(&mut self, body: AnyValue) {
+        LogRecord::set_body(self, body)
+    }
+
+    fn add_attribute(&mut self, key: Key, value: AnyValue) {
+        LogRecord::add_attribute(self, key, value)
+    }
+
+    #[cfg(feature = "trace")]
+    fn set_trace_context(
+        &mut self,
+        trace_id: TraceId,
+        span_id: SpanId,
+        trace_flags: Option<TraceFlags>,
+    ) {
+        LogRecord::set_trace_context(self, trace_id, span_id, trace_flags)
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Wraps the [`BoxedLogger`]'s [`LogRecord`] so it can be used generically by
+/// applications without knowing the underlying type.
+pub struct BoxedLogRecord(Box<dyn ObjectSafeLogRecord + Send + Sync>);
+
+impl fmt::Debug for BoxedLogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BoxedLogRecord")
+    }
+}
+
+impl LogRecord for BoxedLogRecord {
+    fn set_event_name(&mut self, name: &'static str) {
+        self.0.set_event_name(name)
+    }
+
+    fn set_target<T>(&mut self, target: T)
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.0.set_target(target.into())
+    }
+
+    fn set_timestamp(&mut self, timestamp: SystemTime) {
+        self.0.set_timestamp(timestamp)
+    }
+
+    fn set_observed_timestamp(&mut self, timestamp: SystemTime) {
+        self.0.set_observed_timestamp(timestamp)
+    }
+
+    fn set_severity_text(&mut self, text: &'static str) {
+        self.0.set_severity_text(text)
+    }
+
+    fn set_severity_number(&mut self, number: Severity) {
+        self.0.set_severity_number(number)
+    }
+
+    fn set_body(&mut self, body: AnyValue) {
+        self.0.set_body(body)
+    }
+
+    fn add_attributes<I, K, V>(&mut self, attributes: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Key>,
+        V: Into<AnyValue>,
+    {
+        for (key, value) in attributes {
+            self.0.add_attribute(key.into(), value.into());
+        }
+    }
+
+    fn add_attribute<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<Key>,
+        V: Into<AnyValue>,
+    {
+        self.0.add_attribute(key.into(), value.into())
+    }
+
+    #[cfg(feature = "trace")]
+    fn set_trace_context(
+        &mut self,
+        trace_id: TraceId,
+        span_id: SpanId,
+        trace_flags: Option<TraceFlags>,
+    ) {
+        self.0.set_trace_context(trace_id, span_id, trace_flags)
+    }
+}
+
+/// Allows a specific [`Logger`] to be used generically by [`BoxedLogger`]
+/// instances by mirroring the interface and boxing the log records.
+pub trait ObjectSafeLogger {
+    /// Creates a new log record that is a trait object.
+    fn create_log_record_boxed(&self) -> Box<dyn ObjectSafeLogRecord + Send + Sync>;
+
+    /// Emits a log record created by [`ObjectSafeLogger::create_log_record_boxed`].
+    ///
+    /// Records created by a different logger are dropped.
+    fn emit_boxed(&self, record: Box<dyn ObjectSafeLogRecord + Send + Sync>);
+
+    /// Check if the given log level is enabled.
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str) -> bool;
+}
+
+impl<R, L> ObjectSafeLogger for L
+where
+    R: LogRecord + Send + Sync + 'static,
+    L: Logger<LogRecord = R>,
+{
+    fn create_log_record_boxed(&self) -> Box<dyn ObjectSafeLogRecord + Send + Sync> {
+        Box::new(self.create_log_record())
+    }
+
+    fn emit_boxed(&self, record: Box<dyn ObjectSafeLogRecord + Send + Sync>) {
+        if let Ok(record) = record.into_any().downcast::<R>() {
+            self.emit(*record)
+        }
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str) -> bool {
+        Logger::event_enabled(self, level, target)
+    }
+}
+
+/// Wraps the [`GlobalLoggerProvider`]'s [`Logger`] so it can be used generically
+/// by applications without knowing the underlying type.
+pub struct BoxedLogger(Box<dyn ObjectSafeLogger + Send + Sync>);
+
+impl BoxedLogger {
+    /// Create a `BoxedLogger` from an object-safe logger.
+    pub fn new(logger: Box<dyn ObjectSafeLogger + Send + Sync>) -> Self {
+        BoxedLogger(logger)
+    }
+}
+
+impl fmt::Debug for BoxedLogger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BoxedLogger")
+    }
+}
+
+impl Logger for BoxedLogger {
+    /// Global logger uses `BoxedLogRecord`s so that it can be a global
+    /// singleton, which is not possible if it takes generic type parameters.
+    type LogRecord = BoxedLogRecord;
+
+    fn create_log_record(&self) -> Self::LogRecord {
+        BoxedLogRecord(self.0.create_log_record_boxed())
+    }
+
+    fn emit(&self, record: Self::LogRecord) {
+        self.0.emit_boxed(record.0)
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str) -> bool {
+        self.0.event_enabled(level, target)
+    }
+}
+
+/// Allows a specific [`LoggerProvider`] to be used generically by the
+/// [`GlobalLoggerProvider`] by mirroring the interface and boxing the return types.
+pub trait ObjectSafeLoggerProvider {
+    /// Creates a logger with the given instrumentation scope that is a trait
+    /// object through the underlying `LoggerProvider`.
+    fn boxed_logger(&self, scope: InstrumentationScope) -> Box<dyn ObjectSafeLogger + Send + Sync>;
+}
+
+impl<R, L, P> ObjectSafeLoggerProvider for P
+where
+    R: LogRecord + Send + Sync + 'static,
+    L: Logger<LogRecord = R> + Send + Sync + 'static,
+    P: LoggerProvider<Logger = L>,
+{
+    fn boxed_logger(&self, scope: InstrumentationScope) -> Box<dyn ObjectSafeLogger + Send + Sync> {
+        Box::new(self.logger_with_scope(scope))
+    }
+}
+
+/// Represents the globally configured [`LoggerProvider`] instance for this
+/// application. This allows generic logging through the returned
+/// [`BoxedLogger`] instances.
+#[derive(Clone)]
+pub struct GlobalLoggerProvider {
+    provider: Arc<dyn ObjectSafeLoggerProvider + Send + Sync>,
+}
+
+impl fmt::Debug for GlobalLoggerProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("GlobalLoggerProvider")
+    }
+}
+
+impl GlobalLoggerProvider {
+    /// Create a new GlobalLoggerProvider instance from a struct that implements `LoggerProvider`.
+    fn new<P, L, R>(provider: P) -> Self
+    where
+        R: LogRecord + Send + Sync + 'static,
+        L: Logger<LogRecord = R> + Send + Sync + 'static,
+        P: LoggerProvider<Logger = L> + Send + Sync + 'static,
+    {
+        GlobalLoggerProvider {
+            provider: Arc::new(provider),
+        }
+    }
+}
+
+impl LoggerProvider for GlobalLoggerProvider {
+    type Logger = BoxedLogger;
+
+    /// Create a logger using the global provider.
+    fn logger_with_scope(&self, scope: InstrumentationScope) -> Self::Logger {
+        BoxedLogger(self.provider.boxed_logger(scope))
+    }
+}
+
+/// The global `Logger` provider singleton.
+static GLOBAL_LOGGER_PROVIDER: Lazy<RwLock<GlobalLoggerProvider>> =
+    Lazy::new(|| RwLock::new(GlobalLoggerProvider::new(NoopLoggerProvider::new())));
+
+/// Returns an instance of the currently configured global [`LoggerProvider`]
+/// through [`GlobalLoggerProvider`].
+pub fn logger_provider() -> GlobalLoggerProvider {
+    GLOBAL_LOGGER_PROVIDER
+        .read()
+        .expect("GLOBAL_LOGGER_PROVIDER RwLock poisoned")
+        .clone()
+}
+
+/// Creates a named instance of [`Logger`] via the configured [`GlobalLoggerProvider`].
+///
+/// If the name is an empty string, the provider will use a default name.
+///
+/// This is a more convenient way of expressing `global::logger_provider().logger(name)`.
+pub fn logger(name: impl Into<Cow<'static, str>>) -> BoxedLogger {
+    logger_provider().logger(name)
+}
+
+/// Sets the given [`LoggerProvider`] instance as the current global provider.
+///
+/// It returns the [`LoggerProvider`] instance that was previously mounted as
+/// global provider (e.g. [`NoopLoggerProvider`] if a provider had not been set
+/// before).
+///
+/// As `set_logger_provider` takes ownership of the provider, it is recommended
+/// to provide a clone of the provider, if the application needs to use the
+/// provider later to perform operations like shutdown.
+pub fn set_logger_provider<P, L, R>(new_provider: P) -> GlobalLoggerProvider
+where
+    R: LogRecord + Send + Sync + 'static,
+    L: Logger<LogRecord = R> + Send + Sync + 'static,
+    P: logs::LoggerProvider<Logger = L> + Send + Sync + 'static,
+{
+    let mut logger_provider = GLOBAL_LOGGER_PROVIDER
+        .write()
+        .expect("GLOBAL_LOGGER_PROVIDER RwLock poisoned");
+    mem::replace(
+        &mut *logger_provider,
+        GlobalLoggerProvider::new(new_provider),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{logger, set_logger_provider};
+    use crate::logs::{LogRecord, Logger, LoggerProvider, NoopLoggerProvider, Severity};
+    use crate::InstrumentationScope;
+    use std::borrow::Cow;
+    use std::sync::{Arc, Mutex};
+
+    type NoopLogger = <NoopLoggerProvider as LoggerProvider>::Logger;
+
+    #[derive(Clone, Debug, Default)]
+    struct TestLoggerProvider {
+        emitted: Arc<Mutex<Vec<Cow<'static, str>>>>,
+    }
+
+    #[derive(Debug)]
+    struct TestLogger {
+        scope: InstrumentationScope,
+        emitted: Arc<Mutex<Vec<Cow<'static, str>>>>,
+    }
+
+    impl Logger for TestLogger {
+        type LogRecord = <NoopLogger as Logger>::LogRecord;
+
+        fn create_log_record(&self) -> Self::LogRecord {
+            NoopLoggerProvider::new().logger("noop").create_log_record()
+        }
+
+        fn emit(&self, _record: Self::LogRecord) {
+            self.emitted
+                .lock()
+                .unwrap()
+                .push(self.scope.name().to_owned().into());
+        }
+
+        #[cfg(feature = "spec_unstable_logs_enabled")]
+        fn event_enabled(&self, _level: Severity, _target: &str) -> bool {
+            true
+        }
+    }
+
+    impl LoggerProvider for TestLoggerProvider {
+        type Logger = TestLogger;
+
+        fn logger_with_scope(&self, scope: InstrumentationScope) -> Self::Logger {
+            TestLogger {
+                scope,
+                emitted: self.emitted.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn global_logger_provider_emits_through_configured_provider() {
+        let provider = TestLoggerProvider::default();
+        let _ = set_logger_provider(provider.clone());
+
+        let logger = logger("test-logger");
+        let mut record = logger.create_log_record();
+        record.set_severity_number(Severity::Info);
+        record.add_attribute("key", "value");
+        logger.emit(record);
+
+        assert_eq!(provider.emitted.lock().unwrap().as_slice(), ["test-logger"]);
+
+        let _ = set_logger_provider(NoopLoggerProvider::new());
+    }
+}
@@ -129,8 +129,43 @@
 //!
 //! [`MeterProvider`]: crate::metrics::MeterProvider
 //! [`set_meter_provider`]: crate::global::set_meter_provider
+//!
+//! ## Global Logs API
+//!
+//! The global logs API **provides log appenders access to the configured
+//! [`LoggerProvider`] instance from anywhere in the codebase**, so that they
+//! do not need to be handed a provider explicitly.
+//!
+//! ### Usage in Appenders
+//!
+//! ```
+//! # #[cfg(feature="logs")]
+//! # {
+//! use opentelemetry::global;
+//! use opentelemetry::logs::{LogRecord, Logger, Severity};
+//!
+//! fn emit_log() {
+//!     let logger = global::logger("my-appender");
+//!     let mut record = logger.create_log_record();
+//!     record.set_severity_number(Severity::Info);
+//!     record.set_body("hello".into());
+//!     logger.emit(record);
+//! }
+//! # }
+//! ```
+//!
+//! ### Usage in Applications
+//!
+//! Application owners have the responsibility to set the global logger
+//! provider with the [`set_logger_provider`] function. Until then, a no-op
+//! provider is used and records are discarded.
+//!
+//! [`LoggerProvider`]: crate::logs::LoggerProvider
+//! [`set_logger_provider`]: crate::global::set_logger_provider
 
 mod internal_logging;
+#[cfg(feature = "logs")]
+mod logs;
 #[cfg(feature = "metrics")]
 mod metrics;
 #[cfg(feature = "trace")]
@@ -138,6 +173,9 @@ mod propagation;
 #[cfg(feature = "trace")]
 mod trace;
 
+#[cfg(feature = "logs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "logs")))]
+pub use logs::*;
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub use metrics::*;
@@ -0,0 +1,311 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+#[cfg(feature = "trace")]
+use crate::trace::TraceContextExt;
+use crate::{
+    logs::{AnyValue, LogRecord, Logger, LoggerProvider, Severity},
+    Context, InstrumentationScope, Key,
+};
+
+/// A named event with a structured payload, emitted by an [`EventLogger`].
+///
+/// Events are meant for application-level occurrences such as client-side
+/// interactions or business events, as opposed to the diagnostic messages of
+/// logging libraries bridged through the [`Logger`] API.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::{Event, Severity};
+///
+/// let event = Event::new("checkout.completed")
+///     .with_field("order.id", "1234")
+///     .with_field("order.amount", 59.9)
+///     .with_severity(Severity::Info2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Event {
+    name: &'static str,
+    payload: HashMap<Key, AnyValue>,
+    severity: Option<Severity>,
+    timestamp: Option<SystemTime>,
+    context: Option<Context>,
+}
+
+impl Event {
+    /// Create a new event with the given name and an empty payload.
+    pub fn new(name: &'static str) -> Self {
+        Event {
+            name,
+            payload: HashMap::new(),
+            severity: None,
+            timestamp: None,
+            context: None,
+        }
+    }
+
+    /// Adds a field to the payload of the event.
+    pub fn with_field<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<Key>,
+        V: Into<AnyValue>,
+    {
+        self.payload.insert(key.into(), value.into());
+        self
+    }
+
+    /// Adds fields to the payload of the event.
+    pub fn with_payload<I, K, V>(mut self, payload: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Key>,
+        V: Into<AnyValue>,
+    {
+        self.payload.extend(
+            payload
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    /// Sets the severity of the event, overriding the default severity of the
+    /// [`EventLogger`].
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    /// Sets the time at which the event occurred. Defaults to the time it is
+    /// emitted.
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets the context whose active span the event is associated with.
+    /// Defaults to the current context.
+    pub fn with_context(mut self, context: Context) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// The name of the event.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The payload of the event.
+    pub fn payload(&self) -> &HashMap<Key, AnyValue> {
+        &self.payload
+    }
+}
+
+/// Emits [`Event`]s as [`LogRecord`]s through a [`Logger`].
+///
+/// Each event is emitted as a log record with the event name, the payload as
+/// an [`AnyValue::Map`] body, the trace context of the event's context and
+/// the event's severity, or the logger's default severity.
+#[derive(Debug)]
+pub struct EventLogger<L> {
+    logger: L,
+    default_severity: Severity,
+}
+
+impl<L: Logger> EventLogger<L> {
+    /// Create a new `EventLogger` emitting through `logger`, with a default
+    /// severity of [`Severity::Info`].
+    pub fn new(logger: L) -> Self {
+        EventLogger {
+            logger,
+            default_severity: Severity::Info,
+        }
+    }
+
+    /// Sets the severity of the events which do not specify one.
+    pub fn with_default_severity(mut self, severity: Severity) -> Self {
+        self.default_severity = severity;
+        self
+    }
+
+    /// The logger this event logger emits through.
+    pub fn logger(&self) -> &L {
+        &self.logger
+    }
+
+    /// Emit an [`Event`].
+    pub fn emit(&self, event: Event) {
+        let mut record = self.logger.create_log_record();
+        record.set_event_name(event.name);
+        record.set_timestamp(event.timestamp.unwrap_or_else(SystemTime::now));
+        record.set_severity_number(event.severity.unwrap_or(self.default_severity));
+        record.set_body(AnyValue::Map(Box::new(event.payload)));
+
+        #[cfg(feature = "trace")]
+        {
+            let span_context = match &event.context {
+                Some(cx) => cx.span().span_context().clone(),
+                None => Context::map_current(|cx| cx.span().span_context().clone()),
+            };
+            if span_context.is_valid() {
+                record.set_trace_context(
+                    span_context.trace_id(),
+                    span_context.span_id(),
+                    Some(span_context.trace_flags()),
+                );
+            }
+        }
+        #[cfg(not(feature = "trace"))]
+        let _ = event.context;
+
+        self.logger.emit(record);
+    }
+}
+
+/// Creates [`EventLogger`]s from a [`LoggerProvider`].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::{Event, EventLoggerProvider};
+/// use opentelemetry_sdk::logs::LoggerProvider;
+///
+/// let provider = EventLoggerProvider::new(LoggerProvider::builder().build());
+/// let event_logger = provider.event_logger("my_app");
+///
+/// event_logger.emit(Event::new("button.clicked").with_field("button.id", "submit"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventLoggerProvider<P> {
+    provider: P,
+}
+
+impl<P: LoggerProvider> EventLoggerProvider<P> {
+    /// Create a new `EventLoggerProvider` creating its loggers from `provider`.
+    pub fn new(provider: P) -> Self {
+        EventLoggerProvider { provider }
+    }
+
+    /// Returns a new event logger with the given instrumentation scope.
+    pub fn event_logger_with_scope(&self, scope: InstrumentationScope) -> EventLogger<P::Logger> {
+        EventLogger::new(self.provider.logger_with_scope(scope))
+    }
+
+    /// Returns a new event logger with the given name.
+    pub fn event_logger(&self, name: impl Into<Cow<'static, str>>) -> EventLogger<P::Logger> {
+        EventLogger::new(self.provider.logger(name))
+    }
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default, Clone)]
+    struct TestRecord {
+        event_name: Option<&'static str>,
+        severity: Option<Severity>,
+        body: Option<AnyValue>,
+        trace_id: Option<crate::trace::TraceId>,
+    }
+
+    impl LogRecord for TestRecord {
+        fn set_event_name(&mut self, name: &'static str) {
+            self.event_name = Some(name);
+        }
+        fn set_target<T: Into<Cow<'static, str>>>(&mut self, _target: T) {}
+        fn set_timestamp(&mut self, _timestamp: SystemTime) {}
+        fn set_observed_timestamp(&mut self, _timestamp: SystemTime) {}
+        fn set_severity_text(&mut self, _text: &'static str) {}
+        fn set_severity_number(&mut self, number: Severity) {
+            self.severity = Some(number);
+        }
+        fn set_body(&mut self, body: AnyValue) {
+            self.body = Some(body);
+        }
+        fn add_attributes<I, K, V>(&mut self, _attributes: I)
+        where
+            I: IntoIterator<Item = (K, V)>,
+            K: Into<Key>,
+            V: Into<AnyValue>,
+        {
+        }
+        fn add_attribute<K, V>(&mut self, _key: K, _value: V)
+        where
+            K: Into<Key>,
+            V: Into<AnyValue>,
+        {
+        }
+        fn set_trace_context(
+            &mut self,
+            trace_id: crate::trace::TraceId,
+            _span_id: crate::trace::SpanId,
+            _trace_flags: Option<crate::trace::TraceFlags>,
+        ) {
+            self.trace_id = Some(trace_id);
+        }
+    }
+
+    #[derive(Debug, Default, Clone)]
+    struct TestLogger(Arc<Mutex<Vec<TestRecord>>>);
+
+    impl Logger for TestLogger {
+        type LogRecord = TestRecord;
+
+        fn create_log_record(&self) -> Self::LogRecord {
+            TestRecord::default()
+        }
+
+        fn emit(&self, record: Self::LogRecord) {
+            self.0.lock().unwrap().push(record);
+        }
+
+        #[cfg(feature = "spec_unstable_logs_enabled")]
+        fn event_enabled(&self, _level: Severity, _target: &str) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn emits_events_as_log_records() {
+        use crate::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+
+        let logger = TestLogger::default();
+        let event_logger = EventLogger::new(logger.clone()).with_default_severity(Severity::Debug);
+
+        event_logger.emit(Event::new("first").with_field("key", "value"));
+
+        let span_context = SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(1),
+            TraceFlags::SAMPLED,
+            false,
+            TraceState::default(),
+        );
+        let cx = Context::new().with_remote_span_context(span_context);
+        event_logger.emit(
+            Event::new("second")
+                .with_severity(Severity::Warn)
+                .with_context(cx),
+        );
+
+        let records = logger.0.lock().unwrap();
+        assert_eq!(records[0].event_name, Some("first"));
+        assert_eq!(records[0].severity, Some(Severity::Debug));
+        assert_eq!(
+            records[0].body,
+            Some(AnyValue::Map(Box::new(HashMap::from([(
+                Key::new("key"),
+                AnyValue::from("value")
+            )]))))
+        );
+        assert_eq!(records[0].trace_id, None);
+
+        assert_eq!(records[1].event_name, Some("second"));
+        assert_eq!(records[1].severity, Some(Severity::Warn));
+        assert_eq!(records[1].trace_id, Some(TraceId::from_u128(1)));
+    }
+}
@@ -0,0 +1,284 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, RwLock};
+
+use thiserror::Error;
+
+use super::Severity;
+
+/// Level of a directive disabling all records.
+const OFF: u8 = u8::MAX;
+/// Flag of the packed state set when some directives target specific targets.
+const HAS_TARGETS: u16 = 1 << 8;
+
+/// Error returned when a directive set cannot be parsed.
+#[derive(Error, Debug, PartialEq)]
+#[error("invalid level directive `{directive}`: {reason}")]
+pub struct DirectiveParseError {
+    directive: String,
+    reason: &'static str,
+}
+
+/// A set of directives such as `info,my_crate::db=debug`.
+#[derive(Clone, Debug, PartialEq)]
+struct Directives {
+    /// The level of the targets not matching any target directive, `None`
+    /// for off.
+    default: Option<Severity>,
+    /// Sorted by descending length, so that the first match is the longest.
+    targets: Vec<(String, Option<Severity>)>,
+}
+
+impl Directives {
+    fn parse(directives: &str) -> Result<Self, DirectiveParseError> {
+        let mut default = Some(Severity::Trace);
+        let mut targets: Vec<(String, Option<Severity>)> = Vec::new();
+        for directive in directives.split(',').map(str::trim) {
+            if directive.is_empty() {
+                continue;
+            }
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let target = target.trim();
+                    if target.is_empty() {
+                        return Err(DirectiveParseError {
+                            directive: directive.to_owned(),
+                            reason: "empty target",
+                        });
+                    }
+                    let level = parse_level(directive, level.trim())?;
+                    targets.retain(|(existing, _)| existing != target);
+                    targets.push((target.to_owned(), level));
+                }
+                None => default = parse_level(directive, directive)?,
+            }
+        }
+        targets.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(Directives { default, targets })
+    }
+
+    fn level(&self, target: &str) -> Option<Severity> {
+        self.targets
+            .iter()
+            .find(|(prefix, _)| target_matches(target, prefix))
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// The most verbose level of all directives, packed with the
+    /// [`HAS_TARGETS`] flag.
+    fn state(&self) -> u16 {
+        let min_level = std::iter::once(self.default)
+            .chain(self.targets.iter().map(|(_, level)| *level))
+            .map(|level| level.map_or(OFF, |level| level as u8))
+            .min()
+            .unwrap_or(OFF);
+        let has_targets = if self.targets.is_empty() {
+            0
+        } else {
+            HAS_TARGETS
+        };
+        has_targets | u16::from(min_level)
+    }
+}
+
+impl fmt::Display for Directives {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(level_name(self.default))?;
+        // most generic targets first, as they would usually be written
+        for (target, level) in self.targets.iter().rev() {
+            write!(f, ",{}={}", target, level_name(*level))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_level(directive: &str, level: &str) -> Result<Option<Severity>, DirectiveParseError> {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" => Ok(Some(Severity::Trace)),
+        "debug" => Ok(Some(Severity::Debug)),
+        "info" => Ok(Some(Severity::Info)),
+        "warn" => Ok(Some(Severity::Warn)),
+        "error" => Ok(Some(Severity::Error)),
+        "fatal" => Ok(Some(Severity::Fatal)),
+        "off" => Ok(None),
+        _ => Err(DirectiveParseError {
+            directive: directive.to_owned(),
+            reason: "unknown level",
+        }),
+    }
+}
+
+fn level_name(level: Option<Severity>) -> &'static str {
+    match level {
+        Some(level) if level >= Severity::Fatal => "fatal",
+        Some(level) if level >= Severity::Error => "error",
+        Some(level) if level >= Severity::Warn => "warn",
+        Some(level) if level >= Severity::Info => "info",
+        Some(level) if level >= Severity::Debug => "debug",
+        Some(_) => "trace",
+        None => "off",
+    }
+}
+
+/// Returns whether `target` is `prefix` or one of its submodules.
+fn target_matches(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: AtomicU16,
+    directives: RwLock<Directives>,
+}
+
+/// A handle to log levels which can be changed at runtime.
+///
+/// A `LevelControl` holds a set of directives such as `info,my_crate::db=debug`:
+/// a default level, optionally followed by levels for specific targets. A
+/// target directive also applies to the submodules of the target, and the
+/// longest matching target wins. The levels are `trace`, `debug`, `info`,
+/// `warn`, `error`, `fatal` and `off`, each enabling the records of its
+/// severity and above.
+///
+/// Clones share the same directives, so that a handle can be given to the
+/// log bridges and the SDK `LoggerProvider`, and another one kept to update
+/// the directives, e.g. from an admin endpoint. Checking a level does not
+/// lock when the severity is below all the directives, or when there are no
+/// target directives.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::{LevelControl, Severity};
+///
+/// let levels = LevelControl::new("info,my_crate::db=debug").unwrap();
+/// assert!(levels.is_enabled(Severity::Debug, "my_crate::db::pool"));
+/// assert!(!levels.is_enabled(Severity::Debug, "my_crate::http"));
+///
+/// levels.set_directives("warn").unwrap();
+/// assert!(!levels.is_enabled(Severity::Info, "my_crate::db"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LevelControl {
+    inner: Arc<Inner>,
+}
+
+impl Default for LevelControl {
+    /// A `LevelControl` enabling all records.
+    fn default() -> Self {
+        let directives = Directives {
+            default: Some(Severity::Trace),
+            targets: Vec::new(),
+        };
+        LevelControl {
+            inner: Arc::new(Inner {
+                state: AtomicU16::new(directives.state()),
+                directives: RwLock::new(directives),
+            }),
+        }
+    }
+}
+
+impl LevelControl {
+    /// Create a new `LevelControl` from a directive set such as
+    /// `info,my_crate::db=debug`. An empty directive set enables all records.
+    pub fn new(directives: &str) -> Result<Self, DirectiveParseError> {
+        let control = LevelControl::default();
+        control.set_directives(directives)?;
+        Ok(control)
+    }
+
+    /// Replaces all directives at once. The directives are left unchanged
+    /// if `directives` cannot be parsed.
+    pub fn set_directives(&self, directives: &str) -> Result<(), DirectiveParseError> {
+        let directives = Directives::parse(directives)?;
+        let state = directives.state();
+        let mut current = self
+            .inner
+            .directives
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *current = directives;
+        self.inner.state.store(state, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the current directives, in a form accepted by
+    /// [`set_directives`](Self::set_directives).
+    pub fn directives(&self) -> String {
+        self.inner
+            .directives
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .to_string()
+    }
+
+    /// Returns whether records of `severity` from `target` are enabled.
+    pub fn is_enabled(&self, severity: Severity, target: &str) -> bool {
+        let state = self.inner.state.load(Ordering::Acquire);
+        // below every directive, or the level of the only directive
+        let min_level = (state & !HAS_TARGETS) as u8;
+        if (severity as u8) < min_level {
+            return false;
+        }
+        if state & HAS_TARGETS == 0 {
+            return true;
+        }
+
+        self.inner
+            .directives
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .level(target)
+            .is_some_and(|level| severity >= level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_directives() {
+        let levels = LevelControl::new(" info , my_crate=warn, my_crate::db=DEBUG ").unwrap();
+        assert_eq!(levels.directives(), "info,my_crate=warn,my_crate::db=debug");
+
+        assert_eq!(LevelControl::default().directives(), "trace");
+        assert_eq!(LevelControl::new("").unwrap().directives(), "trace");
+
+        assert!(LevelControl::new("verbose").is_err());
+        assert!(LevelControl::new("=info").is_err());
+    }
+
+    #[test]
+    fn longest_target_wins() {
+        let levels = LevelControl::new("warn,my_crate=info,my_crate::db=debug,noisy=off").unwrap();
+
+        assert!(levels.is_enabled(Severity::Warn, "other"));
+        assert!(!levels.is_enabled(Severity::Info, "other"));
+        assert!(levels.is_enabled(Severity::Info2, "my_crate::http"));
+        assert!(!levels.is_enabled(Severity::Debug, "my_crate::http"));
+        assert!(levels.is_enabled(Severity::Debug, "my_crate::db::pool"));
+        assert!(!levels.is_enabled(Severity::Debug, "my_crate::dbx"));
+        assert!(!levels.is_enabled(Severity::Fatal, "noisy"));
+        assert!(!levels.is_enabled(Severity::Trace, "my_crate::db"));
+    }
+
+    #[test]
+    fn updates_are_shared_by_clones() {
+        let levels = LevelControl::new("info").unwrap();
+        let handle = levels.clone();
+
+        assert!(!levels.is_enabled(Severity::Debug, "my_crate"));
+        handle.set_directives("info,my_crate=debug").unwrap();
+        assert!(levels.is_enabled(Severity::Debug, "my_crate"));
+
+        assert!(handle.set_directives("info,my_crate=loud").is_err());
+        assert_eq!(levels.directives(), "info,my_crate=debug");
+
+        handle.set_directives("off").unwrap();
+        assert!(!levels.is_enabled(Severity::Fatal4, "my_crate"));
+    }
+}
@@ -1,4 +1,6 @@
 //! # OpenTelemetry Logs Bridge API
+mod events;
+mod level_control;
 ///  This API is not intended to be called by application developers directly.
 ///  It is provided for logging library authors to build log appenders, that
 ///  bridges existing logging systems with OpenTelemetry.
@@ -6,6 +8,8 @@ mod logger;
 mod noop;
 mod record;
 
+pub use events::{Event, EventLogger, EventLoggerProvider};
+pub use level_control::{DirectiveParseError, LevelControl};
 pub use logger::{Logger, LoggerProvider};
 pub use noop::NoopLoggerProvider;
 pub use record::{AnyValue, LogRecord, Severity};
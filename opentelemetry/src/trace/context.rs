@@ -185,6 +185,13 @@ impl SpanRef<'_> {
         self.with_inner_mut(move |inner| inner.update_name(new_name))
     }
 
+    /// Adds a [`Link`] to another [`SpanContext`].
+    ///
+    /// [`Link`]: crate::trace::Link
+    pub fn add_link(&self, span_context: SpanContext, attributes: Vec<KeyValue>) {
+        self.with_inner_mut(move |inner| inner.add_link(span_context, attributes))
+    }
+
     /// Signals that the operation described by this span has now ended.
     pub fn end(&self) {
         self.end_with_timestamp(crate::time::now());
@@ -20,8 +20,10 @@ if rustup component add clippy; then
                 "opentelemetry-proto"
                 "opentelemetry-sdk"
                 "opentelemetry-semantic-conventions"
+                "opentelemetry-statsd"
                 "opentelemetry-stdout"
-                "opentelemetry-zipkin")
+                "opentelemetry-zipkin"
+                "opentelemetry-zpages")
   for crate in "${crates[@]}"; do
       cargo clippy --manifest-path=$crate/Cargo.toml --all-targets --all-features -- \
           `# Exit with a nonzero code if there are clippy warnings` \
@@ -38,9 +40,14 @@ if rustup component add clippy; then
   cargo_feature opentelemetry-otlp "http-proto, reqwest-client"
   cargo_feature opentelemetry-otlp "http-proto, reqwest-rustls"
   cargo_feature opentelemetry-otlp "metrics"
+  cargo_feature opentelemetry-otlp "file"
+  cargo_feature opentelemetry-otlp "gzip-file"
 
   cargo_feature opentelemetry-jaeger-propagator "default"
 
+  # `valuable` support in `tracing` is only built with this cfg.
+  RUSTFLAGS="--cfg tracing_unstable" cargo_feature opentelemetry-appender-tracing "valuable"
+
   cargo_feature opentelemetry-proto "default"
   cargo_feature opentelemetry-proto "full"
   cargo_feature opentelemetry-proto "gen-tonic,trace"